@@ -0,0 +1,69 @@
+//! How long listing a large account takes: 10,000 repositories over 100 pages, answered by a
+//! `FakeTransport` so only pagination, JSON parsing and sorting are measured.
+//!
+//! ```text
+//! cargo bench --bench listing
+//! ```
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use privateer::{Client, FakeTransport, HttpResponse};
+use reqwest::Method;
+use std::sync::Arc;
+
+const PAGES: usize = 100;
+const PER_PAGE: usize = 100;
+
+/// The pages of the listing, each linking the next and the last one like GitHub does.
+fn pages() -> Vec<HttpResponse> {
+    (1..=PAGES)
+        .map(|page| {
+            let repos: Vec<String> = (0..PER_PAGE)
+                .map(|n| {
+                    let name = format!("repo-{:05}", (page - 1) * PER_PAGE + n);
+                    format!(
+                        r#"{{"name":"{name}","full_name":"octocat/{name}","url":"https://api.github.com/repos/octocat/{name}","html_url":"https://github.com/octocat/{name}","clone_url":"https://github.com/octocat/{name}.git","private":{private},"size":1024,"stargazers_count":{n},"pushed_at":"2024-01-01T00:00:00Z","created_at":"2020-01-01T00:00:00Z","owner":{{"login":"octocat"}},"permissions":{{"admin":true,"push":true,"pull":true}}}}"#,
+                        private = n % 2 == 0,
+                    )
+                })
+                .collect();
+            let response = HttpResponse::new(200, format!("[{}]", repos.join(",")));
+            match page < PAGES {
+                true => response.header(
+                    "link",
+                    &format!(
+                        r#"<https://api.github.com/user/repos?per_page=100&page={next}>; rel="next", <https://api.github.com/user/repos?per_page=100&page={PAGES}>; rel="last""#,
+                        next = page + 1
+                    ),
+                ),
+                false => response,
+            }
+        })
+        .collect()
+}
+
+fn listing(c: &mut Criterion) {
+    let pages = pages();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    c.bench_function("list 10k repos", |b| {
+        b.iter_batched(
+            || {
+                let fake = FakeTransport::new();
+                for page in &pages {
+                    fake.respond(Method::GET, "/user/repos", page.clone());
+                }
+                Client::new("token").with_transport(Arc::new(fake))
+            },
+            |client| {
+                let repos = runtime.block_on(client.list_repos()).unwrap();
+                assert_eq!(repos.len(), PAGES * PER_PAGE);
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, listing);
+criterion_main!(benches);
@@ -0,0 +1,156 @@
+//! Applies a visibility template to every repository of the token's user.
+//!
+//! Each rule is `PATTERN=private` or `PATTERN=public`, where `*` at either end of the pattern
+//! matches anything; the first rule matching a repository's name decides its visibility, and
+//! repositories no rule matches are left alone.
+//!
+//! ```text
+//! PAT_TOKEN=... cargo run --example apply_template -- 'dotfiles=private' '*-notes=private' '*=public'
+//! ```
+
+use anyhow::anyhow;
+use privateer::{Client, Repository};
+
+/// A pattern and the visibility it asks for, `true` for private.
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    pattern: String,
+    private: bool,
+}
+
+impl Rule {
+    fn parse(rule: &str) -> privateer::Result<Self> {
+        let (pattern, visibility) = rule
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected `PATTERN=private|public`, got `{rule}`"))?;
+        let private = match visibility {
+            "private" => true,
+            "public" => false,
+            other => return Err(anyhow!("Expected `private` or `public`, got `{other}`")),
+        };
+
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            private,
+        })
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        let pattern = self.pattern.as_str();
+        let (any_start, pattern) = match pattern.strip_prefix('*') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let (any_end, pattern) = match pattern.strip_suffix('*') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        match (any_start, any_end) {
+            (true, true) => name.contains(pattern),
+            (true, false) => name.ends_with(pattern),
+            (false, true) => name.starts_with(pattern),
+            (false, false) => name == pattern,
+        }
+    }
+}
+
+/// The repositories whose visibility differs from what the template wants, with that visibility.
+fn plan<'a>(template: &[Rule], repos: &'a [Repository]) -> Vec<(&'a Repository, bool)> {
+    repos
+        .iter()
+        .filter_map(|repo| {
+            let rule = template.iter().find(|rule| rule.matches(&repo.name))?;
+            (repo.private != Some(rule.private)).then_some((repo, rule.private))
+        })
+        .collect()
+}
+
+async fn apply(client: &Client, template: &[Rule]) -> privateer::Result<usize> {
+    let repos = client.list_repos().await?;
+    let changes = plan(template, &repos);
+    for (repo, private) in &changes {
+        client
+            .set_visibility(&repo.owner.login, &repo.name, *private)
+            .await?;
+        println!(
+            "{}/{}: {}",
+            repo.owner.login,
+            repo.name,
+            if *private { "private" } else { "public" }
+        );
+    }
+
+    Ok(changes.len())
+}
+
+#[tokio::main]
+async fn main() -> privateer::Result<()> {
+    let template = std::env::args()
+        .skip(1)
+        .map(|rule| Rule::parse(&rule))
+        .collect::<privateer::Result<Vec<_>>>()?;
+    let client = Client::new(std::env::var("PAT_TOKEN")?);
+    let changed = apply(&client, &template).await?;
+    println!("Changed {changed} repos");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use privateer::{FakeTransport, HttpResponse};
+    use reqwest::Method;
+    use std::sync::Arc;
+
+    fn repo(name: &str, private: bool) -> String {
+        format!(r#"{{"name":"{name}","url":"","owner":{{"login":"octocat"}},"private":{private}}}"#)
+    }
+
+    #[test]
+    fn the_first_matching_rule_decides() {
+        let template: Vec<Rule> = ["dotfiles=private", "*-notes=private", "*=public"]
+            .iter()
+            .map(|rule| Rule::parse(rule).unwrap())
+            .collect();
+        assert!(template[1].matches("work-notes"));
+        assert!(!template[1].matches("notes-old"));
+        assert!(Rule::parse("site").is_err());
+    }
+
+    #[tokio::test]
+    async fn only_repositories_off_template_are_changed() {
+        let fake = Arc::new(FakeTransport::new());
+        fake.respond(
+            Method::GET,
+            "/user/repos",
+            HttpResponse::new(
+                200,
+                format!("[{},{}]", repo("work-notes", false), repo("site", false)),
+            ),
+        );
+        for method in [Method::PATCH, Method::POST] {
+            fake.respond(
+                method,
+                "/repos/octocat/work-notes",
+                HttpResponse::new(200, repo("work-notes", true)),
+            );
+        }
+        fake.respond(
+            Method::GET,
+            "/repos/octocat/work-notes",
+            HttpResponse::new(200, repo("work-notes", true)),
+        );
+        let client = Client::new("token").with_transport(fake.clone());
+        let template = vec![
+            Rule::parse("*-notes=private").unwrap(),
+            Rule::parse("*=public").unwrap(),
+        ];
+
+        assert_eq!(apply(&client, &template).await.unwrap(), 1);
+        assert!(fake
+            .requests()
+            .iter()
+            .all(|request| !request.url.path().ends_with("/site")));
+    }
+}
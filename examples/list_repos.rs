@@ -0,0 +1,62 @@
+//! Lists the repositories of the token's user with their visibility.
+//!
+//! ```text
+//! PAT_TOKEN=... cargo run --example list_repos
+//! ```
+
+use privateer::{Client, Repository};
+
+/// One line per repository: `owner/name  private`.
+fn lines(repos: &[Repository]) -> Vec<String> {
+    repos
+        .iter()
+        .map(|repo| {
+            let visibility = match repo.private {
+                Some(true) => "private",
+                Some(false) => "public",
+                None => "unknown",
+            };
+            format!(
+                "{:40} {visibility}",
+                format!("{}/{}", repo.owner.login, repo.name)
+            )
+        })
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> privateer::Result<()> {
+    let client = Client::new(std::env::var("PAT_TOKEN")?);
+    for line in lines(&client.list_repos().await?) {
+        println!("{line}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use privateer::{FakeTransport, HttpResponse};
+    use reqwest::Method;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn lists_every_repository() {
+        let fake = Arc::new(FakeTransport::new());
+        fake.respond(
+            Method::GET,
+            "/user/repos",
+            HttpResponse::new(
+                200,
+                r#"[{"name":"dotfiles","url":"","owner":{"login":"octocat"},"private":true},
+                    {"name":"site","url":"","owner":{"login":"octocat"},"private":false}]"#,
+            ),
+        );
+        let client = Client::new("token").with_transport(fake);
+        let lines = lines(&client.list_repos().await.unwrap());
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("octocat/dotfiles") && lines[0].ends_with("private"));
+        assert!(lines[1].ends_with("public"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Makes one repository private or public.
+//!
+//! ```text
+//! PAT_TOKEN=... cargo run --example set_visibility -- octocat dotfiles private
+//! ```
+
+use anyhow::{anyhow, Context};
+use privateer::Client;
+
+/// `private` or `public`, as `true` for private.
+fn parse_visibility(visibility: &str) -> privateer::Result<bool> {
+    match visibility {
+        "private" => Ok(true),
+        "public" => Ok(false),
+        other => Err(anyhow!("Expected `private` or `public`, got `{other}`")),
+    }
+}
+
+#[tokio::main]
+async fn main() -> privateer::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let usage = "Usage: set_visibility <OWNER> <NAME> <private|public>";
+    let owner = args.next().context(usage)?;
+    let name = args.next().context(usage)?;
+    let private = parse_visibility(&args.next().context(usage)?)?;
+
+    let client = Client::new(std::env::var("PAT_TOKEN")?);
+    client.set_visibility(&owner, &name, private).await?;
+    println!(
+        "{owner}/{name} is now {}",
+        if private { "private" } else { "public" }
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use privateer::{FakeTransport, HttpResponse};
+    use reqwest::Method;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn changes_the_visibility_and_checks_it() {
+        let fake = Arc::new(FakeTransport::new());
+        let private = r#"{"name":"dotfiles","url":"","owner":{"login":"octocat"},"private":true}"#;
+        fake.respond(
+            Method::PATCH,
+            "/repos/octocat/dotfiles",
+            HttpResponse::new(200, private),
+        )
+        .respond(
+            Method::POST,
+            "/repos/octocat/dotfiles",
+            HttpResponse::new(200, private),
+        )
+        .respond(
+            Method::GET,
+            "/repos/octocat/dotfiles",
+            HttpResponse::new(200, private),
+        );
+        let client = Client::new("token").with_transport(fake.clone());
+
+        client
+            .set_visibility("octocat", "dotfiles", parse_visibility("private").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            fake.requests()
+                .first()
+                .and_then(|request| request.body.clone())
+                .as_deref(),
+            Some(r#"{"private":true}"#)
+        );
+        assert!(parse_visibility("internal").is_err());
+    }
+}
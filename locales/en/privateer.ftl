@@ -0,0 +1,213 @@
+# English messages of privateer, the reference every translation starts from.
+#
+# To translate privateer, copy this file to `locales/<locale>/privateer.ftl`, such as
+# `locales/de/privateer.ftl`, translate the text after each `=` and list the locale in
+# `src/i18n.rs`. Messages left out fall back to English. See https://projectfluent.org for the
+# syntax, such as the `{ $name }` placeholders.
+
+## The interactive flow
+
+multiple-repos-prompt = Do you want to modify multiple repositories?: (y/N)
+multiple-repos-invalid = Please enter either `y` or `n` or `Ctrl/Cmd-C to quit`
+goal-prompt = Make the selected repos private?: (y/n, empty to decide per repo)
+goal-invalid = Please enter either `y` or `n`
+select-repos = Please select repositories:
+none-selected = No repositories were selected. Hint! Use <space> to select, then <Enter> to confirm.
+    Exiting
+repo-prompt = Enter repository:
+repo-required = `repository` is required
+read-only-repos = Cannot modify `{ $count }` repos (read-only access):
+all-read-only = None of the repositories can be modified with this token
+private-prompt = {"  "}>> Make this repo private?: (y/n, a for all remaining)
+apply-prompt = Type `apply` to make these changes, anything else to cancel:
+nothing-applied = No changes were applied.
+conflict = `{ $repo }` was planned to go from { $planned } to { $wanted }, but is { $live } on GitHub now.
+conflict-prompt = {"  "}>> Skip it, apply anyway, or refresh and re-plan it from GitHub?: (s/a/r)
+conflict-invalid = Please enter `s` to skip, `a` to apply anyway or `r` to re-plan
+token-required = `PAT (Personal Access Token)` is required
+renamed = `{ $old }` was renamed to `{ $new }`, using the new name
+topic-none = No repositories carry the topic `{ $topic }`, so there is nothing to change
+
+## Dates, such as when a repository was last pushed to
+
+time-just-now = just now
+time-ago = { $time } ago
+time-in = in { $time }
+time-minutes = { $count ->
+    [one] 1 minute
+   *[other] { $count } minutes
+}
+time-hours = { $count ->
+    [one] 1 hour
+   *[other] { $count } hours
+}
+time-days = { $count ->
+    [one] 1 day
+   *[other] { $count } days
+}
+time-weeks = { $count ->
+    [one] 1 week
+   *[other] { $count } weeks
+}
+time-months = { $count ->
+    [one] 1 month
+   *[other] { $count } months
+}
+time-years = { $count ->
+    [one] 1 year
+   *[other] { $count } years
+}
+pushed = pushed { $date }
+never-pushed = never pushed
+
+## Progress and summaries of changes
+
+repos-listed = Fetched details of `{ $total }` repos successfully!
+all-repos-fetched = All repositories fetched!
+fetching-page = Fetched { $fetched } repos, fetching page { $page }
+change-queued = queued
+change-done = done
+change-skipped = skipped: { $reason }
+change-failed = failed: { $error }
+change-not-started = not started
+already-private = already private
+already-public = already public
+interrupted = Interrupted: finishing the changes in flight, press Ctrl-C again to abandon them
+rate-limited = Rate limited by GitHub, retrying in { $seconds }s
+cloning = cloning
+cloned = cloned
+already-cloned = already cloned
+step-checking = checking
+step-scanning-history = scanning history
+step-backing-up = backing up
+step-patching = patching
+step-verifying = verifying
+step-editing = editing
+timed-out = Gave up after no response for { $after } while { $step }
+timed-out-patching = Gave up after no response for { $after } while { $step }; the change may have been made anyway, so run again to check
+skip-already-desired-state = already has the visibility wanted
+skip-protected = exempt by the policy or the allowlist
+skip-fork = a fork, whose visibility GitHub doesn't change. Hint! `privateer detach-fork` makes a private copy
+skip-no-admin = no admin access
+skip-archived = archived, and so read-only
+skip-filtered = on the denylist
+skip-changed-since-planned = changed on GitHub since it was planned
+none = none
+summary-failed = Failed to summarize the run for notifications: { $error }
+summary-skipped = , skipped `{ $count }` ({ $reasons })
+summary-resume-hint = . Hint! `privateer --resume` makes the `{ $count }` changes left
+summary-history-hint = . Hint! `privateer history` lists every change
+summary-changed-before-interruption = Changed before the interruption: { $repos }
+summary-interrupted = Interrupted after updating `{ $updated }` of `{ $total }` repos{ $skipped }, `{ $not_started }` not attempted{ $hint }
+summary-unconfirmed = Updated `{ $updated }` repos{ $skipped }, `{ $unconfirmed }` not confirmed by the audit log
+summary-updated = Updated `{ $updated }` repos{ $skipped }
+summary-timed-out = {" "}(`{ $count }` timed out)
+summary-failed-repos = Updated `{ $updated }` repos{ $skipped }, `{ $failed }` failed{ $timed_out }{ $resume }
+diff-nothing-to-change = Nothing to change, { $count ->
+    [one] 1 repo
+   *[other] { $count } repos
+} already up to date
+diff-will-become = { $count ->
+    [one] 1 repo
+   *[other] { $count } repos
+} will become { $value }
+diff-already-skipped = { $count } already { $value } will be skipped
+
+## privateer open-source
+
+open-source-already-public = `{ $repo }` is already public
+open-source-not-ready = `{ $repo }` can't be made public yet:
+open-source-all-public = Every repository is already public
+open-source-ready = `{ $count }` repos will be made public
+open-source-made-public = Made `{ $repo }` public
+open-source-refused = Refused to make `{ $count }` repos public: { $repos }. Hint! Fix what they miss, or pass `--skip-check` to make them public anyway
+open-source-failed = Failed to make `{ $count }` repos public: { $repos }
+open-source-no-license = it has no license; add a `LICENSE` file
+open-source-no-description = it has no description; set one with `privateer tui` or on GitHub
+open-source-no-secret-scanning = secret scanning is off, so leaked secrets can't be ruled out; turn it on in the repository's security settings
+open-source-secret-alerts = it has { $alerts }; revoke the secrets and close them, or pass `--allow-secret-alerts`
+
+## privateer enforce
+
+enforce-violation = violation
+enforce-changed = changed
+enforce-already-set = already set
+enforce-skipped = skipped
+enforce-failed = failed
+enforce-alerts = { $counts } alerts
+enforce-summary = { $violations } of { $checked } repos violate the policy ({ $unfixable } can't be changed), { $changed } changed, { $skipped } skipped, { $failed } failed (report: { $path })
+
+## privateer watch
+
+serving-metrics = Serving metrics on { $url }
+watch-started = Watching for new repos every { $interval }, making them { $visibility }
+watch-changed = Made new repo `{ $repo }` { $visibility }
+watch-failures = `{ $count }` repos failed to change, retrying them next time
+watch-check-failed = Check failed, retrying next time: { $error }
+
+## Other commands
+
+detach-plan = This will:
+detach-plan-create = create the private repository `{ $repo }`
+detach-plan-push = push every branch and tag of `{ $repo }` to it
+detach-plan-keep = leave `{ $repo }` as it is
+detach-plan-archive = archive `{ $repo }`
+detach-plan-delete = delete `{ $repo }`, with its issues and pull requests
+detach-created = Created `{ $repo }`
+detach-pushed = Pushed `{ $fork }` to `{ $repo }`
+detach-done = `{ $fork }` is now the private `{ $repo }`
+lockdown-all-done = Every repository is already locked down
+lockdown-planned = `{ $count }` repos will be locked down
+lockdown-done = Locked down `{ $repo }`
+bulk-all-done = Every repository already matches its row
+bulk-planned = `{ $count }` repos will be changed
+bulk-done = Updated `{ $repo }` (line { $line })
+clone-done = Cloned `{ $cloned }` repos into { $dest }, `{ $skipped }` were already there
+clone-interrupted = Interrupted: `{ $count }` repos were not cloned
+audit-log-waiting = Waiting for GitHub to add the changes to the audit log...
+audit-log-found = `{ $repo }` is in the audit log of `{ $org }` as `{ $id }`
+no-audit-records = No audit records found.
+topic-removed = Removed the topic `{ $topic }` from `{ $count }` repos
+mirror-pushed = Pushed { $branches } of `{ $source }` to `{ $mirror }`
+man-written = Wrote `{ $count }` man pages to { $dir }
+
+## Prompts
+
+token-prompt = Enter token:
+not-a-terminal = Can't prompt `{ $message }` without a terminal. Hint! Pass the value as a flag or environment variable
+cancelled = Cancelled
+read-failed = Failed to read input, please try again: { $error }
+yes-no-invalid = Please enter either `y`/`yes` or `n`/`no`
+yes-no-all-invalid = Please enter either `y`/`yes` or `n`/`no`, or `a` to answer for all remaining
+all-remaining-prompt = {"  "}>> Answer for all remaining: (y/n)
+go-ahead-prompt = Go ahead? (y/n)
+nothing-changed = Cancelled; nothing was changed
+picker-numbers-prompt = Numbers to select, separated by commas, such as `1, 3, 5-7` or `all` (empty keeps the { $count } marked [x]):
+picker-not-a-number = `{ $input }` is not a number or range, such as `3` or `5-7`
+picker-out-of-range = `{ $number }` is not between 1 and { $count }
+tui-plain = The terminal UI redraws the screen, so it is not available with `--plain`. Hint! Run `privateer --plain` to pick repositories from a numbered list
+
+## privateer init
+
+init-not-a-terminal = `privateer init` requires a terminal. Hint! Write the configuration by hand, see the README
+init-overwrite-prompt = { $path } exists, overwrite it? (y/n)
+init-host-prompt = GitHub host, or empty for github.com:
+init-not-a-host = `{ $hostname }` is not a host name, such as `git.example.com`
+init-use-env-token-prompt = Use the token in `PAT_TOKEN`? (y/n)
+init-device-code = Open { $url } and enter the code { $code } to sign in privateer. Waiting...
+init-token-scopes = privateer needs a personal access token with the `repo` scope to change visibility,
+    and `delete_repo` too for `detach-fork --then delete`. Create one at:
+    {"  "}{ $url }
+init-token-rejected = The token was rejected
+init-signed-in = Signed in as `{ $login }`
+init-private-prompt = Should repositories be private unless allowed otherwise? (y/n)
+init-allow-prompt = Repositories to leave alone, separated by commas (e.g. dotfiles), or empty:
+init-owner-prompt = Owner of the repositories to list, such as an organization, or empty for `{ $login }`:
+init-deny-prompt = Repositories never to list or change, as `owner/name` globs separated by commas (e.g. acme/archive-*), or empty:
+init-not-a-glob = `{ $glob }` is not an `owner/name` glob, such as `acme/*`
+init-wrote-config = Wrote the configuration to { $path }
+init-save-token-prompt = Save the token to { $storage }? (y/n)
+init-saved-token = Saved the token to { $storage }
+init-set-host = Set `GH_HOST={ $hostname }` before running privateer
+init-set-token = Set `PAT_TOKEN` before running privateer, or it is asked for at each run
+init-next = Next, see what the policy would change with `privateer enforce --check-only`
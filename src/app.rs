@@ -0,0 +1,627 @@
+//! The command-line interface: argument handling, prompts and the interactive flow.
+
+use crate::cache::{self, RepoCache};
+use crate::cassette::{self, RecordingTransport, ReplayTransport};
+use crate::github::{self, Repository};
+use crate::i18n::t;
+use crate::output::{status, verbose};
+use crate::plan::SkipReason;
+use crate::transport;
+use crate::{
+    apply, audit, backup, bulk, cancel, checkpoint, cli, clone, config, conflict, credentials,
+    daemon, detach, diff, doctor, enforce, events, exit, format, heartbeat, i18n, init, list,
+    lists, lock, lockdown, man, mirror, notify, open_source, output, paths, plan, prompt_dialoguer,
+    prompter, ratelimit, secrets, shell, sizes, snapshot, stats, topic, tui, urls, validate, watch,
+    webhook, Result, ERROR_ICON,
+};
+use anyhow::anyhow;
+use clap::Parser;
+use console::style;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// Runs the `privateer` command with the process arguments, returning its exit status.
+///
+/// This function makes a PATCH request to the GitHub API to update the privacy settings of a repository.
+///
+/// To make a public repository private using a personal access token (PAT) on GitHub, you need to have the `repo` scope in your PAT.
+/// The `repo` scope allows the PAT to access and modify the repository, including changing its privacy settings.
+///
+/// Here are the steps to generate a PAT with the required scope:
+///
+/// 1. Go to your GitHub account settings and select "Developer settings" > "Personal access tokens" > "Generate new token".
+/// 2. Give the token a name and select the `repo` scope.
+/// 3. Click on "Generate token" to create the PAT.
+///
+/// Once you have generated the PAT, you can use it to make a public repository private by sending a
+/// PATCH request to the GitHub API with the following payload:
+///
+/// ```text
+/// { "private": true }
+/// ```
+///
+/// Make sure to include your PAT in the `Authorization` header of the request using the following format:
+///
+/// ```text
+/// Authorization: token <your PAT>
+/// ```
+///
+/// Replace `<your PAT>` with your actual PAT value.
+///
+/// See also: [update-a-repository] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#update-a-repository
+pub async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            ExitCode::from(exit::code(&err))
+        }
+    }
+}
+
+async fn run() -> Result<()> {
+    let cli = cli::Cli::parse();
+    let mut settings = (*transport::settings()).clone();
+    if let Some(version) = &cli.api_version {
+        settings.api_version = version.header();
+    }
+    settings.transport = match (&cli.record, &cli.replay) {
+        (Some(dir), _) => Arc::new(RecordingTransport::new(dir, settings.transport.clone())?),
+        (None, Some(dir)) => Arc::new(ReplayTransport::load(dir)?),
+        (None, None) => settings.transport,
+    };
+    // Only interactive runs reuse repositories: unattended ones act on what GitHub reports now,
+    // and recordings must hold every request.
+    let interactive = matches!(cli.command, None | Some(cli::Command::Tui));
+    if interactive && !cli.no_cache && cli.record.is_none() && cli.replay.is_none() {
+        let cache = RepoCache::load(&paths::repo_cache_file()?, cache::TTL);
+        settings.cache = Some(Arc::new(cache));
+    }
+
+    transport::with_settings(
+        Arc::new(settings),
+        cancel::scope(CancellationToken::new(), run_cli(cli)),
+    )
+    .await
+}
+
+async fn run_cli(cli: cli::Cli) -> Result<()> {
+    output::init(cli.quiet, cli.verbose, cli.no_color, cli.ascii, cli.plain);
+    i18n::configure(cli.locale.as_deref())?;
+    format::configure(cli.absolute_dates);
+    events::init(cli.progress)?;
+    backup::configure(cli.backup_dir.clone());
+    secrets::configure(cli.allow_secret_alerts, cli.scan_history);
+    conflict::configure(
+        cli.on_conflict,
+        cli.command.is_none() && prompter::is_interactive(),
+    );
+    if cli.persist_history {
+        prompter::enable_persistent_history()?;
+    }
+
+    let config = config::load_if_exists(&paths::config_file()?)?;
+    load_env_file(cli.env_file.as_deref(), config.environment.dotenv)?;
+    lists::configure(
+        cli.allowlist.as_deref(),
+        cli.denylist.as_deref(),
+        &config.filters.deny,
+    )?;
+    notify::configure(&config.notifications);
+    if cli.notify {
+        notify::enable_desktop();
+    }
+
+    match &cli.command {
+        Some(cli::Command::Init(args)) => return init::run(args).await,
+        Some(cli::Command::Doctor) => return doctor::run().await,
+        Some(cli::Command::RateLimit) => return ratelimit::show(&get_pat_token()?).await,
+        Some(cli::Command::History(args)) => return audit::history(args),
+        Some(cli::Command::Stats(args)) => return stats::run(args),
+        Some(cli::Command::Man { out }) => return man::write_pages(out).map(|_| ()),
+        Some(cli::Command::Snapshot(args)) => {
+            let _lock = lock::acquire(cli.force)?;
+            return snapshot::take(&get_pat_token()?, args).await;
+        }
+        Some(cli::Command::Drift(args)) => return snapshot::drift(&get_pat_token()?, args).await,
+        Some(cli::Command::List(args)) => {
+            return list::run(&get_pat_token()?, args, cli.sort, cli.order).await
+        }
+        Some(cli::Command::Open { repo }) => {
+            return shell::open_in_browser(repo_page(repo, get_pat_token).await?.as_str());
+        }
+        Some(cli::Command::Tui) => {
+            if !prompter::is_interactive() {
+                return Err(anyhow!("{ERROR_ICON} `privateer tui` requires a terminal"));
+            }
+            if output::is_plain() {
+                return Err(anyhow!("{ERROR_ICON} {}", t!("tui-plain")));
+            }
+            let _lock = lock::acquire(cli.force)?;
+            let username = cli
+                .username
+                .as_ref()
+                .or(config.filters.owner.as_ref())
+                .map(|username| validate::username(username))
+                .transpose()?;
+            return tui::run(&get_pat_token()?, username.as_deref(), cli.sort, cli.order).await;
+        }
+        Some(cli::Command::Watch(args)) => {
+            return watch::run(&get_pat_token()?, args, cli.force).await
+        }
+        Some(cli::Command::Daemon(args)) => {
+            return daemon::run(&get_pat_token()?, args, cli.force).await
+        }
+        Some(cli::Command::Enforce(args)) => {
+            return enforce::run(&get_pat_token()?, args, cli.force).await
+        }
+        Some(cli::Command::Health(args)) => return heartbeat::health(args),
+        Some(cli::Command::Serve(args)) => {
+            return webhook::run(&get_pat_token()?, args, cli.force).await
+        }
+        Some(cli::Command::Backup(args)) => return backup::run(&get_pat_token()?, args).await,
+        Some(cli::Command::DetachFork(args)) => {
+            let _lock = lock::acquire(cli.force)?;
+            return detach::run(&get_pat_token()?, args).await;
+        }
+        Some(cli::Command::Mirror(args)) => return mirror::run(&get_pat_token()?, args).await,
+        Some(cli::Command::Clone(args)) => return clone::run(&get_pat_token()?, args).await,
+        Some(cli::Command::Sizes(args)) => return sizes::run(&get_pat_token()?, args).await,
+        Some(cli::Command::Lockdown(args)) => return lockdown::run(&get_pat_token()?, args).await,
+        Some(cli::Command::Bulk(args)) => return bulk::run(&get_pat_token()?, args).await,
+        Some(cli::Command::OpenSource(args)) => {
+            return open_source::run(&get_pat_token()?, args).await
+        }
+        None if cli.resume => return resume(&cli).await,
+        None => {}
+    }
+
+    // Without a terminal, fail up front with everything that's missing instead of hanging on
+    // prompts that can never be answered.
+    if !prompter::is_interactive() {
+        let has_token = cassette::is_replaying()
+            || std::env::var("PAT_TOKEN").is_ok_and(|token| !token.is_empty())
+            || credentials::load(&urls::host()).is_some();
+        let missing = cli.missing_inputs(has_token);
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "{ERROR_ICON} Not running in a terminal, so these must be given up front:\n  {}",
+                missing.join("\n  ")
+            ));
+        }
+    }
+
+    let team = cli.team.as_deref().map(validate::team).transpose()?;
+    let topic = cli.topic.as_deref().map(validate::topic).transpose()?;
+
+    // Changes are applied at the end of the flow, so no other run may start meanwhile.
+    let _lock = lock::acquire(cli.force)?;
+
+    // Get personal access token.
+    let pat_token = get_pat_token()?;
+
+    let owner = cli.username.as_ref().or(config.filters.owner.as_ref());
+    let (actor, username) = actor_and_owner(owner.map(String::as_str), &pat_token).await?;
+    verbose!("Signed in as `{actor}`, changing repositories of `{username}`");
+
+    // let mut multiple_repository = Vec::new();
+    let mut repositories: Vec<Repository>;
+    // The desired privacy of every repository, if it's the same for all of them.
+    let mut goal: Option<bool> = cli.private;
+
+    // Prompt the user to select option for multiple repositories actions.
+    let should_select_multiple_repos: bool = cli.repos.is_empty()
+        && (team.is_some()
+            || topic.is_some()
+            || loop {
+                let input =
+                    prompter::prompt_user_input(&t!("multiple-repos-prompt"))?.to_lowercase();
+                if input == "y" || input == "n" {
+                    break input == "y";
+                } else {
+                    println!("{ERROR_ICON} {}", t!("multiple-repos-invalid"))
+                }
+            });
+
+    if !cli.repos.is_empty() {
+        repositories = Vec::with_capacity(cli.repos.len());
+        for input in &cli.repos {
+            let repo = validate::repo(input)?;
+            repositories.push(fetch_repo(&repo, &username, &pat_token).await?);
+        }
+    } else if should_select_multiple_repos {
+        // If user selects multiple repositories option.
+        repositories = match &team {
+            Some((org, team)) => {
+                cancel::on_ctrl_c(github::get_team_repos(
+                    org, team, &pat_token, cli.sort, cli.order,
+                ))
+                .await?
+            }
+            None => {
+                cancel::on_ctrl_c(github::get_selectable_repos(
+                    &pat_token, cli.sort, cli.order,
+                ))
+                .await?
+            }
+        };
+        let repos_ids: Vec<usize> = match &topic {
+            // The topic is the selection, so tagging repositories is all a scheduled run needs.
+            Some(topic) => {
+                let ids: Vec<usize> = (0..repositories.len())
+                    .filter(|&id| topic::has(&repositories[id], topic))
+                    .collect();
+                if ids.is_empty() {
+                    println!("{}", t!("topic-none", topic = topic.clone()));
+                    return Ok(());
+                }
+                ids
+            }
+            None => {
+                // Knowing the goal up front lets the repos that don't meet it yet be pre-selected.
+                if goal.is_none() {
+                    goal = loop {
+                        let input = prompter::prompt_user_input(&t!("goal-prompt"))?;
+                        match (input.is_empty(), prompter::parse_yes_no(&input)) {
+                            (true, _) => break None,
+                            (false, Some(private)) => break Some(private),
+                            (false, None) => println!("{ERROR_ICON} {}", t!("goal-invalid")),
+                        }
+                    };
+                }
+                let ids =
+                    prompt_dialoguer::run_dialoguer(actor.clone(), repositories.clone(), goal)?;
+                if ids.is_empty() {
+                    return Err(anyhow!("{ERROR_ICON} {}", t!("none-selected")));
+                }
+                ids
+            }
+        };
+        repositories = repos_ids
+            .into_iter()
+            .map(|id| {
+                let mut rep = repositories[id].clone();
+                let owner = match rep.owner.login.is_empty() {
+                    true => username.as_str(),
+                    false => rep.owner.login.as_str(),
+                };
+                rep.url = urls::host().html_repo_url(owner, &rep.name).into();
+                rep
+            })
+            .collect();
+    } else {
+        // Offer the repositories the token can reach for tab completion, if they can be listed.
+        let full_names = listed_full_names(&username, &pat_token, cli.sort, cli.order).await;
+        let completions = repo_completions(&full_names, &username);
+        let single_repository = loop {
+            let input = prompter::prompt_with_completions(&t!("repo-prompt"), completions.clone())?;
+            if input.is_empty() {
+                return Err(anyhow!("{ERROR_ICON} {}", t!("repo-required")));
+            }
+            match validate::repo(&input) {
+                Ok(repo) => break repo,
+                Err(err) => println!("{err}"),
+            }
+        };
+        // Fetching the repository validates it exists and tells its current visibility.
+        repositories = vec![fetch_repo(&single_repository, &username, &pat_token).await?];
+    }
+
+    // Changing the visibility requires admin access, so read-only repos would only fail later.
+    let (repositories, read_only) = plan::split_read_only(repositories);
+    if !read_only.is_empty() {
+        println!(
+            "{ERROR_ICON} {}",
+            t!("read-only-repos", count = read_only.len())
+        );
+        for repo in &read_only {
+            println!("  {}", style(&repo.name).dim());
+        }
+        if repositories.is_empty() {
+            return Err(anyhow!("{ERROR_ICON} {}", t!("all-read-only")));
+        }
+    }
+
+    // Prompt for the desired privacy setting of every repository before changing any of them.
+    let mut wanted: Vec<plan::Wanted> = Vec::with_capacity(repositories.len());
+    for repo in repositories {
+        let leftpad = 30;
+        let info_repo_url = style_repo_leftpad_url(&repo, Some(leftpad))?;
+
+        status!("{}", info_repo_url);
+        if let Some(private) = goal {
+            wanted.push((repo, private));
+            continue;
+        }
+
+        // Prompt the user to enter the privacy setting for the repository.
+        let private = match prompter::prompt_yes_no_all(&t!("private-prompt"))? {
+            prompter::Answer::One(private) => private,
+            prompter::Answer::All(private) => {
+                goal = Some(private);
+                private
+            }
+        };
+        wanted.push((repo, private));
+    }
+
+    // Once applied, the topic comes off the repositories that got the visibility wanted.
+    let untag = match (&topic, cli.remove_topic) {
+        (Some(topic), true) => Some((topic.as_str(), wanted.clone())),
+        _ => None,
+    };
+
+    // The same repo may be reached twice, such as under a user and an organization.
+    let steps = plan::plan(wanted, &username)?;
+
+    // Show the current state against the desired state so the changes can be reviewed at once.
+    status!();
+    for step in &steps {
+        status!(
+            "{}",
+            diff::render(&step.repo.name, std::slice::from_ref(&step.change))
+        );
+        if let Some(reason) = step
+            .skip
+            .filter(|&reason| reason != SkipReason::AlreadyDesiredState)
+        {
+            status!(
+                "    {}",
+                style(t!("change-skipped", reason = reason.describe())).dim()
+            );
+        }
+    }
+    status!(
+        "\n{}",
+        style(diff::summarize(&plan::changes(&steps))).bold()
+    );
+
+    // Repos already at their desired visibility, or that can't be changed, are left alone.
+    let skipped = read_only
+        .iter()
+        .map(|repo| (apply::full_name(repo, &username), SkipReason::NoAdmin))
+        .chain(plan::skipped(&steps, &username))
+        .collect();
+    let plan = plan::to_apply(steps);
+    let applied = match plan.is_empty() {
+        true => Ok(()),
+        false => {
+            let should_apply = cli.yes || {
+                let input = prompter::prompt_user_input(&t!("apply-prompt"))?;
+                input.eq_ignore_ascii_case("apply")
+            };
+            if !should_apply {
+                println!("{}", t!("nothing-applied"));
+                return Ok(());
+            }
+            apply::apply(
+                plan,
+                skipped,
+                &username,
+                &pat_token,
+                &actor,
+                &apply_options(&cli),
+            )
+            .await
+        }
+    };
+    // Even after some changes failed, those that were made leave the queue.
+    if let Some((topic, wanted)) = untag {
+        if let Err(err) = topic::remove(&wanted, topic, &username, &pat_token, &actor).await {
+            eprintln!("{err}");
+        }
+    }
+
+    applied
+}
+
+/// Applies the changes left by the last chunked run, as the same user.
+async fn resume(cli: &cli::Cli) -> Result<()> {
+    let _lock = lock::acquire(cli.force)?;
+    let pat_token = get_pat_token()?;
+    let actor = github::get_authenticated_user(&pat_token).await?;
+    let checkpoint = checkpoint::load()?;
+    if !checkpoint.actor.eq_ignore_ascii_case(&actor) {
+        return Err(anyhow!(
+            "{ERROR_ICON} The unfinished run was started by `{}`, not `{actor}`. Hint! Resume it with their token",
+            checkpoint.actor
+        ));
+    }
+    println!(
+        "Resuming the run of {}: `{}` changes made, `{}` left",
+        checkpoint.saved_at.format("%Y-%m-%d %H:%M"),
+        checkpoint.applied,
+        checkpoint.remaining.len()
+    );
+
+    apply::apply(
+        checkpoint.plan(),
+        Vec::new(),
+        &checkpoint.username,
+        &pat_token,
+        &actor,
+        &apply_options(cli),
+    )
+    .await
+}
+
+fn apply_options(cli: &cli::Cli) -> apply::Options {
+    apply::Options {
+        concurrency: cli.concurrency,
+        chunk_size: cli.chunk_size as usize,
+        timeout: Some(cli.repo_timeout).filter(|timeout| !timeout.is_zero()),
+        verify_audit_log: cli.verify_audit_log,
+    }
+}
+
+/// The full names of the repositories listed for `username`, reused from the cache if they were
+/// listed moments ago, or none if they can't be listed.
+pub(crate) async fn listed_full_names(
+    username: &str,
+    pat_token: &str,
+    sort: cli::SortKey,
+    order: cli::SortOrder,
+) -> Vec<String> {
+    let cache = transport::settings().cache.clone();
+    if let Some(full_names) = cache.as_ref().and_then(|cache| cache.listing(username)) {
+        verbose!("Reusing the repositories of `{username}` listed earlier");
+        return full_names;
+    }
+    let Ok(repos) = github::get_repos_request(username, pat_token, sort, order).await else {
+        return Vec::new();
+    };
+    let full_names: Vec<String> = repos
+        .iter()
+        .map(|repo| apply::full_name(repo, username))
+        .collect();
+    if let Some(cache) = &cache {
+        cache.insert_listing(username, full_names.clone());
+    }
+
+    full_names
+}
+
+/// What the repository prompt completes: every repository as `owner/name`, and those of
+/// `username` by their name alone too, since the owner may be left out for them.
+pub(crate) fn repo_completions(full_names: &[String], username: &str) -> Vec<String> {
+    let own = full_names.iter().filter_map(|full_name| {
+        full_name
+            .split_once('/')
+            .filter(|(owner, _)| owner.eq_ignore_ascii_case(username))
+            .map(|(_, name)| name.to_owned())
+    });
+
+    own.chain(full_names.iter().cloned()).collect()
+}
+
+/// The login the token belongs to, recorded as the actor of every change, and the owner of the
+/// repositories named without one: `owner` if given, such as an organization, or that login, so
+/// it only needs to be given for someone else's repositories.
+pub(crate) async fn actor_and_owner(
+    owner: Option<&str>,
+    pat_token: &str,
+) -> Result<(String, String)> {
+    let actor = github::get_authenticated_user(pat_token).await?;
+    let owner = match owner {
+        Some(owner) => validate::username(owner)?,
+        None => actor.clone(),
+    };
+
+    Ok((actor, owner))
+}
+
+/// The web page of `repo`, for `privateer open`. A name without an owner is one of the token's
+/// user, so `pat_token` is only asked for then.
+pub(crate) async fn repo_page(
+    repo: &str,
+    pat_token: impl FnOnce() -> Result<String>,
+) -> Result<url::Url> {
+    let repo = validate::repo(repo)?;
+    let owner = match repo.owner {
+        Some(owner) => owner,
+        None => github::get_authenticated_user(&pat_token()?).await?,
+    };
+
+    Ok(urls::host().html_repo_url(&owner, &repo.name))
+}
+
+/// Fetches `repo`, owned by `username` unless another owner was given, with its web page as
+/// `url`, for display and planning.
+pub(crate) async fn fetch_repo(
+    repo: &validate::RepoRef,
+    username: &str,
+    pat_token: &str,
+) -> Result<Repository> {
+    let owner = repo.owner.as_deref().unwrap_or(username);
+    let name = repo.name.as_str();
+    let mut repo = github::get_repo(owner, name, pat_token).await?;
+    // GitHub redirects requests for renamed or transferred repositories to their new location.
+    if !repo.full_name.is_empty()
+        && !repo
+            .full_name
+            .eq_ignore_ascii_case(&format!("{owner}/{name}"))
+    {
+        status!(
+            "{}",
+            t!(
+                "renamed",
+                old = format!("{owner}/{name}"),
+                new = repo.full_name.clone()
+            )
+        );
+    }
+    repo.url = match repo.html_url.is_empty() {
+        true => urls::host().html_repo_url(owner, name).into(),
+        false => repo.html_url.clone(),
+    };
+
+    Ok(repo)
+}
+
+/// The file to load environment variables from: `explicit` if given, otherwise `.env` in the
+/// current directory if it exists and `dotenv` allows it.
+pub(crate) fn env_file(explicit: Option<&Path>, dotenv: bool) -> Option<PathBuf> {
+    match explicit {
+        Some(path) => Some(path.to_owned()),
+        None => Some(PathBuf::from(".env")).filter(|path| dotenv && path.is_file()),
+    }
+}
+
+/// Loads environment variables from `explicit` or `.env`, see [`env_file`]. Variables already
+/// set keep their value.
+pub(crate) fn load_env_file(explicit: Option<&Path>, dotenv: bool) -> Result<()> {
+    let Some(path) = env_file(explicit, dotenv) else {
+        match dotenv {
+            true => verbose!("No `.env` file in the current directory"),
+            false => {
+                verbose!("Not loading `.env`: `environment.dotenv` is off in the configuration")
+            }
+        }
+        return Ok(());
+    };
+    dotenv::from_path(&path).map_err(|err| {
+        anyhow!("{ERROR_ICON} Failed to load the environment file {path:?}: {err}")
+    })?;
+    verbose!("Loaded environment variables from {path:?}");
+
+    Ok(())
+}
+
+/// Reads the personal access token from `PAT_TOKEN`, or the one saved by `privateer init`,
+/// prompting for it if neither is set.
+pub(crate) fn get_pat_token() -> Result<String> {
+    let pat_token = match std::env::var("PAT_TOKEN") {
+        // Whitespace is never part of a token, but easily kept by `set PAT_TOKEN=... ` in cmd.exe.
+        Ok(token) if !token.trim().is_empty() => token.trim().to_owned(),
+        // Recorded responses don't check the token.
+        _ if cassette::is_replaying() => "replay".to_owned(),
+        _ => match credentials::load(&urls::host()) {
+            Some(token) => token,
+            None => prompter::prompt_for_token()?,
+        },
+    };
+    if pat_token.is_empty() {
+        return Err(anyhow!("{ERROR_ICON} {}", t!("token-required")));
+    }
+
+    Ok(pat_token)
+}
+
+pub(crate) fn style_repo_leftpad_url(repo: &Repository, leftpad: Option<usize>) -> Result<String> {
+    use console::{measure_text_width, style};
+
+    let pad = leftpad.unwrap_or(8);
+    let name = style(&repo.name).yellow();
+    let url = style(&repo.url).green().underlined();
+    let name_width = measure_text_width(&repo.name);
+    let result_leftpad = format!(
+        "{name}{padding}{url}",
+        name = name,
+        padding = " ".repeat(pad.saturating_sub(name_width)),
+        url = url
+    );
+
+    Ok(result_leftpad)
+}
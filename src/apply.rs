@@ -0,0 +1,859 @@
+//! Applying a reviewed plan of privacy changes to GitHub.
+//!
+//! Repositories are updated concurrently, each moving through `queued → checking → patching →
+//! verifying → done/failed` as [`events`] shown by the frontend of the run. Repositories that
+//! already have the desired setting are skipped without a `PATCH`, so a plan can safely be
+//! applied again.
+//!
+//! `Ctrl-C` stops starting new updates and waits for the ones in flight, then reports what was
+//! changed; a second `Ctrl-C` abandons the ones in flight too, except those whose `PATCH` was
+//! sent, which run on to their audit record. Every change GitHub made is in the audit trail.
+
+use crate::checkpoint::{self, Checkpoint};
+use crate::conflict::{self, OnConflict};
+use crate::diff::FieldChange;
+use crate::events::{self, Event};
+use crate::github::{self, Repository, UpdateRepository};
+use crate::i18n::t;
+use crate::output::verbose;
+use crate::plan::SkipReason;
+use crate::{
+    audit, audit_log, backup, cancel, exit, metrics, notify, ratelimit, secrets, transport, Result,
+    ERROR_ICON, SUCCESS_ICON,
+};
+use anyhow::{anyhow, Context};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// A repository paired with its desired privacy setting, `"true"` or `"false"`.
+pub(crate) type Plan = Vec<(Repository, String)>;
+
+/// What [`apply_one`] did to a repository.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Applied {
+    Changed,
+    /// The repository already had the desired setting, so nothing was sent.
+    AlreadySet,
+    /// The repository changed since it was planned, and `--on-conflict` left it alone.
+    Skipped,
+}
+
+/// Removes repeated entries for the same repository from `plan`, keeping the first.
+///
+/// Repositories are compared by id, or by full name, case-insensitively, with `username` as the
+/// owner of those without one, when either id is unknown. Two entries asking for different
+/// settings are an error, since neither can be preferred.
+pub(crate) fn dedupe(plan: Plan, username: &str) -> Result<Plan> {
+    let full_name =
+        |repo: &Repository| match (repo.full_name.is_empty(), repo.owner.login.is_empty()) {
+            (false, _) => repo.full_name.to_lowercase(),
+            (true, false) => format!("{}/{}", repo.owner.login, repo.name).to_lowercase(),
+            (true, true) => format!("{username}/{}", repo.name).to_lowercase(),
+        };
+    let mut deduped: Plan = Vec::with_capacity(plan.len());
+    for (repo, privacy) in plan {
+        let name = full_name(&repo);
+        let same = |other: &Repository| repo.same_as(other).unwrap_or(full_name(other) == name);
+        match deduped.iter().find(|(other, _)| same(other)) {
+            Some((_, other)) if *other != privacy => {
+                return Err(anyhow!(
+                    "{ERROR_ICON} `{name}` is planned to become both private and public. Hint! Choose one setting for it"
+                ));
+            }
+            Some(_) => {}
+            None => deduped.push((repo, privacy)),
+        }
+    }
+
+    Ok(deduped)
+}
+
+/// How the update of one repository in a batch ended.
+enum Outcome {
+    Applied(Applied),
+    Failed(anyhow::Error),
+    /// The batch was interrupted before the update started.
+    NotStarted,
+}
+
+/// Describes a repository skipped for already having the desired setting.
+pub(crate) fn already(private: bool) -> String {
+    match private {
+        true => t!("already-private"),
+        false => t!("already-public"),
+    }
+}
+
+/// The `step` reported by [`apply_one`] in words, for people.
+pub(crate) fn describe_step(step: &str) -> String {
+    match step {
+        "checking" => t!("step-checking"),
+        SCANNING_HISTORY => t!("step-scanning-history"),
+        BACKING_UP => t!("step-backing-up"),
+        "patching" => t!("step-patching"),
+        "verifying" => t!("step-verifying"),
+        "editing" => t!("step-editing"),
+        step => step.to_owned(),
+    }
+}
+
+/// How [`apply`] goes about a plan.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Options {
+    /// Most changes in flight at once.
+    pub concurrency: usize,
+    /// Most changes applied between two checkpoints.
+    pub chunk_size: usize,
+    /// How long a change may make no progress before it is given up on.
+    pub timeout: Option<Duration>,
+    /// Whether to look up the changes to organizations' repositories in their audit logs.
+    pub verify_audit_log: bool,
+}
+
+/// Applies `plan` with at most `concurrency` requests in flight, recording each change in the
+/// audit trail with `actor`. The repositories planned to be left alone are reported as
+/// `skipped`, by `owner/name`, and counted in the summary by reason.
+///
+/// A plan of more than `chunk_size` changes is applied a chunk at a time. After each chunk the
+/// changes left, including those that failed, are saved as a [`checkpoint`] for `--resume`, and
+/// the next chunk waits until the rate limit leaves room for all of its requests.
+///
+/// A repository that makes no progress for `timeout`, such as when a request is lost on the way,
+/// is given up on as failed, see [`supervise`], and the others carry on.
+///
+/// With `verify_audit_log`, the changes to organizations' repositories are then looked up in
+/// their audit logs, see [`audit_log::verify`]; one that can't be found counts as a failure.
+///
+/// Every repository is attempted even if some fail; the failures are reported at the end, and
+/// make the run exit with [`exit::PARTIAL_FAILURE`], like an interruption with `Ctrl-C`.
+pub(crate) async fn apply(
+    plan: Plan,
+    skipped: Vec<(String, SkipReason)>,
+    username: &str,
+    pat_token: &str,
+    actor: &str,
+    options: &Options,
+) -> Result<()> {
+    let total = plan.len();
+    events::emit(Event::ApplyStarted {
+        total: total + skipped.len(),
+    });
+    let started = chrono::Utc::now();
+    let chunk_size = options.chunk_size.max(1);
+    let chunked = total > chunk_size;
+    // Cancelled by the first `Ctrl-C` or by whoever cancels the run; the tasks then stop at
+    // their next safe point. `abandon`, cancelled by the second one, also stops those checking
+    // or verifying, but never one whose `PATCH` was sent.
+    let token = cancel::current().child_token();
+    let abandon = CancellationToken::new();
+    let mut interrupts = 0;
+    let mut tally = Tally::default();
+    for (repo, reason) in skipped {
+        events::emit(Event::ChangeSkipped { repo, reason });
+        tally.skip(reason);
+    }
+    let mut remaining = plan;
+    while !remaining.is_empty() && !token.is_cancelled() {
+        let rest = remaining.split_off(chunk_size.min(remaining.len()));
+        let chunk = std::mem::replace(&mut remaining, rest);
+        if chunked && tally.attempted > 0 {
+            wait_for_headroom(chunk.len(), pat_token, &token).await;
+        }
+        let batch = Batch {
+            username,
+            pat_token,
+            actor,
+            concurrency: options.concurrency,
+            timeout: options.timeout,
+            token: &token,
+            abandon: &abandon,
+        };
+        tally.add(batch.run(chunk, &mut interrupts).await?);
+        if chunked {
+            let left: Plan = tally.retry.iter().chain(&remaining).cloned().collect();
+            checkpoint::save(&Checkpoint::new(username, actor, tally.updated.len(), left))?;
+        }
+    }
+    let not_started = tally.not_started + remaining.len();
+    tally.retry.extend(remaining);
+    if chunked && tally.retry.is_empty() {
+        checkpoint::clear()?;
+    }
+    events::emit(Event::ApplyFinished);
+    let Tally {
+        updated,
+        skipped,
+        failures,
+        retry,
+        ..
+    } = tally;
+    let failed = failures
+        .iter()
+        .map(|(name, err): &(String, anyhow::Error)| (name.clone(), err.to_string()))
+        .collect();
+    match notify::Summary::since(started, actor, failed) {
+        Ok(summary) => notify::send(&summary).await,
+        Err(err) => eprintln!(
+            "{ERROR_ICON} {}",
+            t!("summary-failed", error = err.to_string())
+        ),
+    }
+    let unconfirmed = match options.verify_audit_log && !updated.is_empty() {
+        true => audit_log::verify(&updated, actor, started, pat_token).await?,
+        false => 0,
+    };
+    let updated: Vec<String> = updated.into_iter().map(|repo| repo.name).collect();
+
+    let timed_out = failures
+        .iter()
+        .filter(|(_, err)| err.is::<TimedOut>())
+        .count();
+    let skipped = match skipped.values().sum::<usize>() {
+        0 => String::new(),
+        count => t!(
+            "summary-skipped",
+            count = count,
+            reasons = skipped
+                .iter()
+                .map(|(reason, count)| format!("{}: {count}", reason.name()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let resume = match chunked && !retry.is_empty() {
+        true => t!("summary-resume-hint", count = retry.len()),
+        false => String::new(),
+    };
+    if token.is_cancelled() {
+        println!(
+            "{}",
+            t!(
+                "summary-changed-before-interruption",
+                repos = match updated.is_empty() {
+                    true => t!("none"),
+                    false => updated.join(", "),
+                }
+            )
+        );
+        for (name, err) in &failures {
+            eprintln!("{ERROR_ICON} {name}: {err}");
+        }
+        let hint = match resume.is_empty() {
+            true => t!("summary-history-hint"),
+            false => resume,
+        };
+        return Err(exit::Exit {
+            code: exit::PARTIAL_FAILURE,
+            message: format!(
+                "{ERROR_ICON} {}",
+                t!(
+                    "summary-interrupted",
+                    updated = updated.len(),
+                    total = total,
+                    skipped = skipped,
+                    not_started = not_started,
+                    hint = hint
+                )
+            ),
+        }
+        .into());
+    }
+    if failures.is_empty() && unconfirmed > 0 {
+        return Err(exit::Exit {
+            code: exit::PARTIAL_FAILURE,
+            message: format!(
+                "{ERROR_ICON} {}",
+                t!(
+                    "summary-unconfirmed",
+                    updated = updated.len(),
+                    skipped = skipped,
+                    unconfirmed = unconfirmed
+                )
+            ),
+        }
+        .into());
+    }
+    if failures.is_empty() {
+        println!(
+            "{SUCCESS_ICON} {}",
+            t!(
+                "summary-updated",
+                updated = updated.len(),
+                skipped = skipped
+            )
+        );
+        return Ok(());
+    }
+    for (name, err) in &failures {
+        eprintln!("{ERROR_ICON} {name}: {err}");
+    }
+
+    Err(exit::Exit {
+        code: exit::PARTIAL_FAILURE,
+        message: format!(
+            "{ERROR_ICON} {}",
+            t!(
+                "summary-failed-repos",
+                updated = updated.len(),
+                skipped = skipped,
+                failed = failures.len(),
+                timed_out = match timed_out {
+                    0 => String::new(),
+                    timed_out => t!("summary-timed-out", count = timed_out),
+                },
+                resume = resume
+            )
+        ),
+    }
+    .into())
+}
+
+/// The step of a change searching the history of its repository for secrets, with `git`.
+const SCANNING_HISTORY: &str = "scanning history";
+
+/// The step of a change backing up its repository, with `git`.
+const BACKING_UP: &str = "backing up";
+
+/// Progress of the change to one repository, watched to abandon it safely or give up on it.
+pub(crate) struct Watch {
+    patching: AtomicBool,
+    /// The last step reached, and when.
+    last: Mutex<(&'static str, Instant)>,
+}
+
+impl Watch {
+    pub(crate) fn new() -> Self {
+        Self {
+            patching: AtomicBool::new(false),
+            last: Mutex::new(("checking", Instant::now())),
+        }
+    }
+
+    /// Notes that the change reached `step`, as reported by [`apply_one`].
+    pub(crate) fn step(&self, step: &'static str) {
+        if step == "patching" {
+            self.patching.store(true, Ordering::SeqCst);
+        }
+        *self.last.lock().unwrap_or_else(|err| err.into_inner()) = (step, Instant::now());
+    }
+
+    /// Waits until no step was reached for `timeout` and returns the last one. Time spent asking
+    /// about a conflict doesn't count, however long the answer takes, and neither does time
+    /// spent cloning with `git`, which reports no progress and takes as long as the repository
+    /// is large.
+    async fn stalled(&self, timeout: Duration) -> &'static str {
+        loop {
+            let (step, since) = *self.last.lock().unwrap_or_else(|err| err.into_inner());
+            if since.elapsed() < timeout {
+                tokio::time::sleep_until(since + timeout).await;
+            } else if conflict::is_asking() || [SCANNING_HISTORY, BACKING_UP].contains(&step) {
+                self.step(step);
+            } else {
+                return step;
+            }
+        }
+    }
+}
+
+/// A change given up on because it made no progress for too long.
+#[derive(Debug)]
+pub(crate) struct TimedOut {
+    pub after: Duration,
+    /// The step the change was stuck at.
+    pub step: &'static str,
+}
+
+impl std::fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let after = humantime::format_duration(self.after).to_string();
+        let step = describe_step(self.step);
+        match self.step {
+            "patching" => write!(
+                f,
+                "{}",
+                t!("timed-out-patching", after = after, step = step)
+            ),
+            _ => write!(f, "{}", t!("timed-out", after = after, step = step)),
+        }
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// Runs `work`, the change to one repository reporting its steps to `watch`, until it finishes.
+///
+/// Once `abandon` is cancelled, `work` is dropped unless its `PATCH` was sent, which is always
+/// waited for. If no step is reached for `timeout`, `work` is dropped either way, cancelling the
+/// request in flight, and fails with [`TimedOut`].
+pub(crate) async fn supervise(
+    work: impl std::future::Future<Output = Result<Applied>>,
+    watch: &Watch,
+    abandon: &CancellationToken,
+    timeout: Option<Duration>,
+) -> Result<Applied> {
+    let mut work = std::pin::pin!(work);
+    let mut stalled = std::pin::pin!(async {
+        match timeout {
+            Some(timeout) => watch.stalled(timeout).await,
+            None => std::future::pending().await,
+        }
+    });
+    let mut abandoned = false;
+    loop {
+        tokio::select! {
+            result = &mut work => return result,
+            _ = abandon.cancelled(), if !abandoned => match watch.patching.load(Ordering::SeqCst) {
+                true => abandoned = true,
+                false => return Err(cancel::Cancelled.into()),
+            },
+            step = &mut stalled => {
+                let after = timeout.unwrap_or_default();
+                return Err(TimedOut { after, step }.into());
+            }
+        }
+    }
+}
+
+/// Requests a change is assumed to need at most: fetching the repository, the `PATCH`, the
+/// verification and one to spare, such as for the secret scanning alerts.
+const REQUESTS_PER_CHANGE: u64 = 4;
+
+/// Waits until the rate limit has room for the requests of `changes` changes, unless `token` is
+/// cancelled first. The reset is measured against the server's clock, and waited for at most
+/// [`ratelimit::MAX_WAIT`]. If the rate limit can't be fetched, the chunk goes ahead and any
+/// rejection is waited out as usual.
+pub(crate) async fn wait_for_headroom(changes: usize, pat_token: &str, token: &CancellationToken) {
+    let limits = match github::get_rate_limit(pat_token).await {
+        Ok(limits) => limits,
+        Err(err) => {
+            verbose!("Not waiting for the rate limit: {err}");
+            return;
+        }
+    };
+    let needed = changes as u64 * REQUESTS_PER_CHANGE;
+    let Some(reset) = chrono::DateTime::from_timestamp(limits.core.reset, 0) else {
+        return;
+    };
+    if limits.core.remaining >= needed {
+        return;
+    }
+    let delay = ratelimit::reset_delay(reset, limits.server_now, chrono::Utc::now())
+        .min(ratelimit::MAX_WAIT);
+    events::emit(Event::RateLimited {
+        retry_in_secs: delay.as_secs(),
+    });
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => {}
+        _ = token.cancelled() => {}
+    }
+}
+
+/// What happened to the changes of one or more batches.
+#[derive(Default)]
+struct Tally {
+    attempted: usize,
+    updated: Vec<Repository>,
+    /// How many repositories were left alone, by reason.
+    skipped: BTreeMap<SkipReason, usize>,
+    failures: Vec<(String, anyhow::Error)>,
+    not_started: usize,
+    /// The changes that failed or were not started, to try again.
+    retry: Plan,
+}
+
+impl Tally {
+    fn skip(&mut self, reason: SkipReason) {
+        *self.skipped.entry(reason).or_default() += 1;
+    }
+
+    fn add(&mut self, other: Tally) {
+        self.attempted += other.attempted;
+        self.updated.extend(other.updated);
+        for (reason, count) in other.skipped {
+            *self.skipped.entry(reason).or_default() += count;
+        }
+        self.failures.extend(other.failures);
+        self.not_started += other.not_started;
+        self.retry.extend(other.retry);
+    }
+}
+
+/// Everything the changes of a batch share.
+struct Batch<'a> {
+    username: &'a str,
+    pat_token: &'a str,
+    actor: &'a str,
+    concurrency: usize,
+    timeout: Option<Duration>,
+    token: &'a CancellationToken,
+    abandon: &'a CancellationToken,
+}
+
+impl Batch<'_> {
+    /// Applies `plan` concurrently, cancelling on `Ctrl-C` as counted by `interrupts`.
+    async fn run(&self, plan: Plan, interrupts: &mut usize) -> Result<Tally> {
+        let semaphore = Arc::new(Semaphore::new(self.concurrency.max(1)));
+        let mut tasks = JoinSet::new();
+        for (repo, privacy) in plan {
+            let full_name = full_name(&repo, self.username);
+            events::emit(Event::ChangeQueued {
+                repo: full_name.clone(),
+            });
+
+            let semaphore = Arc::clone(&semaphore);
+            let (token, abandon) = (self.token.clone(), self.abandon.clone());
+            let timeout = self.timeout;
+            let (username, pat_token, actor) = (
+                self.username.to_owned(),
+                self.pat_token.to_owned(),
+                self.actor.to_owned(),
+            );
+            // The task keeps the transport of the run, such as a recording, its token and
+            // frontend.
+            let (settings, frontend) = (transport::settings(), events::frontend());
+            tasks.spawn(cancel::scope(token.clone(), async move {
+                transport::with_settings(
+                    settings,
+                    events::with_frontend(frontend, async move {
+                        let not_started = || {
+                            events::emit(Event::ChangeNotStarted {
+                                repo: full_name.clone(),
+                            })
+                        };
+                        let permit = tokio::select! {
+                            permit = semaphore.acquire_owned() => permit.ok(),
+                            _ = token.cancelled() => None,
+                        };
+                        let Some(_permit) = permit else {
+                            not_started();
+                            return (repo, privacy, Outcome::NotStarted);
+                        };
+                        let watch = Watch::new();
+                        let report = |step: &'static str| {
+                            watch.step(step);
+                            events::emit(Event::ChangeProgress {
+                                repo: full_name.clone(),
+                                step,
+                            })
+                        };
+                        let work =
+                            apply_one(&repo, &privacy, &username, &pat_token, &actor, &report);
+                        let result = supervise(work, &watch, &abandon, timeout).await;
+                        if matches!(&result, Err(err) if cancel::is_cancelled(err)) {
+                            not_started();
+                            return (repo, privacy, Outcome::NotStarted);
+                        }
+                        events::emit(match &result {
+                            Ok(Applied::Skipped) => Event::ChangeSkipped {
+                                repo: full_name.clone(),
+                                reason: SkipReason::ChangedSincePlanned,
+                            },
+                            Ok(Applied::AlreadySet) => Event::ChangeSkipped {
+                                repo: full_name.clone(),
+                                reason: SkipReason::AlreadyDesiredState,
+                            },
+                            Ok(Applied::Changed) => Event::ChangeApplied {
+                                repo: full_name.clone(),
+                                private: privacy == "true",
+                                already: false,
+                            },
+                            Err(err) => Event::ChangeFailed {
+                                repo: full_name.clone(),
+                                error: err.to_string(),
+                            },
+                        });
+
+                        let outcome = match result {
+                            Ok(applied) => Outcome::Applied(applied),
+                            Err(err) => Outcome::Failed(err),
+                        };
+                        (repo, privacy, outcome)
+                    }),
+                )
+                .await
+            }));
+        }
+
+        let mut tally = Tally::default();
+        let mut ctrl_c = std::pin::pin!(tokio::signal::ctrl_c());
+        loop {
+            let joined = tokio::select! {
+                joined = tasks.join_next() => joined,
+                _ = &mut ctrl_c, if *interrupts < 2 => {
+                    *interrupts += 1;
+                    ctrl_c.set(tokio::signal::ctrl_c());
+                    match interrupts {
+                        1 => {
+                            self.token.cancel();
+                            events::emit(Event::Interrupted);
+                        }
+                        _ => self.abandon.cancel(),
+                    }
+                    continue;
+                }
+            };
+            let (repo, privacy, outcome) = match joined {
+                None => break,
+                Some(Ok(joined)) => joined,
+                Some(Err(err)) => return Err(err.into()),
+            };
+            match outcome {
+                Outcome::Applied(Applied::Changed) => tally.updated.push(repo),
+                Outcome::Applied(Applied::AlreadySet) => {
+                    tally.skip(SkipReason::AlreadyDesiredState)
+                }
+                Outcome::Applied(Applied::Skipped) => tally.skip(SkipReason::ChangedSincePlanned),
+                Outcome::Failed(err) => {
+                    tally.failures.push((repo.name.clone(), err));
+                    tally.retry.push((repo, privacy));
+                }
+                Outcome::NotStarted => {
+                    tally.not_started += 1;
+                    tally.retry.push((repo, privacy));
+                    continue;
+                }
+            }
+            tally.attempted += 1;
+        }
+
+        Ok(tally)
+    }
+}
+
+/// `owner/name` of `repo`, which belongs to `username` if it has no owner.
+pub(crate) fn full_name(repo: &Repository, username: &str) -> String {
+    match repo.owner.login.is_empty() {
+        true => format!("{username}/{}", repo.name),
+        false => format!("{}/{}", repo.owner.login, repo.name),
+    }
+}
+
+/// Updates a single repository, records the change and verifies GitHub reports the new state.
+///
+/// The current state is fetched first, and nothing is changed if it already matches `privacy`.
+/// If it no longer matches the visibility `repo` was planned with either, the conflict is
+/// resolved first, see [`conflict`]. `report` is called with `"checking"`, `"patching"` and
+/// `"verifying"` as the update progresses, and with `"scanning history"` and `"backing up"` when
+/// those are configured.
+pub(crate) async fn apply_one(
+    repo: &Repository,
+    privacy: &str,
+    username: &str,
+    pat_token: &str,
+    actor: &str,
+    report: &(dyn Fn(&'static str) + Send + Sync),
+) -> Result<Applied> {
+    report("checking");
+    // Repositories listed for the token may belong to other owners, such as organizations.
+    let owner = match repo.owner.login.is_empty() {
+        true => username,
+        false => repo.owner.login.as_str(),
+    };
+    let new = privacy == "true";
+    cancel::check()?;
+    let fetched = github::get_repo_fresh(owner, &repo.name, pat_token).await;
+    // Renamed since it was listed, and its old name now taken by another repository or no longer
+    // leading to it; only its id still does. Any other failure is reported as it is.
+    let moved = match &fetched {
+        Ok(current) => repo.same_as(current) == Some(false),
+        Err(err) => repo.id != 0 && err.is::<github::RepoNotFound>(),
+    };
+    let current = match moved {
+        false => fetched?,
+        true => {
+            verbose!(
+                "`{owner}/{}` is no longer the repository listed; fetching it by its id `{}`",
+                repo.name,
+                repo.id
+            );
+            github::get_repo_by_id(repo.id, pat_token)
+                .await
+                .with_context(|| {
+                    format!(
+                        "{ERROR_ICON} `{owner}/{}` was not changed: it is no longer the repository listed, which can't be found",
+                        repo.name
+                    )
+                })?
+        }
+    };
+    let mut anyway = false;
+    if current.private != repo.private {
+        let full_name = full_name(repo, owner);
+        match conflict::resolve(&full_name, repo.private, current.private, new).await? {
+            OnConflict::Skip => return Ok(Applied::Skipped),
+            OnConflict::Apply => anyway = true,
+            OnConflict::Replan => {}
+        }
+    }
+    if current.private == Some(new) && !anyway {
+        return Ok(Applied::AlreadySet);
+    }
+    // A renamed or transferred repository is fetched from its new location; keep using it, and
+    // never send the stale name, which would rename it back.
+    let (owner, name) = match current.owner.login.is_empty() {
+        true => (owner, repo.name.as_str()),
+        false => (current.owner.login.as_str(), current.name.as_str()),
+    };
+    if !new {
+        secrets::check_alerts(owner, name, pat_token).await?;
+        if secrets::scans_history() {
+            report(SCANNING_HISTORY);
+            secrets::check_history(owner, name, pat_token).await?;
+        }
+    }
+
+    if backup::is_configured() {
+        report(BACKING_UP);
+    }
+    let backup = backup::before_change(owner, name, pat_token).await?;
+    // The last safe point: once the `PATCH` is sent, the change runs on to its audit record.
+    cancel::check()?;
+    report("patching");
+    let update = UpdateRepository {
+        private: Some(new),
+        ..UpdateRepository::default()
+    };
+    let request_id = github::update_repo(owner, name, &update, pat_token).await?;
+    audit::append(&audit::AuditRecord {
+        timestamp: chrono::Utc::now(),
+        actor: actor.to_owned(),
+        repo: format!("{owner}/{name}"),
+        node_id: audit::node_id(&current),
+        field: "private".to_owned(),
+        old: current
+            .private
+            .map_or(serde_json::Value::Null, serde_json::Value::Bool),
+        new: serde_json::Value::Bool(new),
+        request_id,
+        backup,
+    })?;
+
+    report("verifying");
+    // Fetched again, and kept in the cache in place of the copy from before the change.
+    let live = github::get_repo_fresh(owner, name, pat_token).await?;
+    if live.private != Some(new) {
+        return Err(anyhow!(
+            "GitHub still reports `private: {private:?}`",
+            private = live.private
+        ));
+    }
+    metrics::changed();
+
+    Ok(Applied::Changed)
+}
+
+/// Settings of a repository to change besides its visibility; `None` leaves a setting as it is.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Edit {
+    pub description: Option<String>,
+    pub topics: Option<Vec<String>>,
+    pub has_issues: Option<bool>,
+    pub has_projects: Option<bool>,
+    pub has_wiki: Option<bool>,
+}
+
+impl Edit {
+    /// Returns `true` if the edit changes nothing.
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The settings of `repo`, as listed, that the edit changes, named like the API names them.
+    pub(crate) fn changes(&self, repo: &Repository) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        let mut push = |field: &str, old: Value, new: Option<Value>| {
+            if let Some(new) = new {
+                changes.push(FieldChange {
+                    field: field.to_owned(),
+                    old: Some(old),
+                    new,
+                });
+            }
+        };
+        push(
+            "description",
+            Value::from(repo.description.clone().unwrap_or_default()),
+            self.description.clone().map(Value::from),
+        );
+        push(
+            "topics",
+            Value::from(repo.topics.clone()),
+            self.topics.clone().map(Value::from),
+        );
+        push(
+            "has_issues",
+            Value::from(repo.has_issues),
+            self.has_issues.map(Value::from),
+        );
+        push(
+            "has_projects",
+            Value::from(repo.has_projects),
+            self.has_projects.map(Value::from),
+        );
+        push(
+            "has_wiki",
+            Value::from(repo.has_wiki),
+            self.has_wiki.map(Value::from),
+        );
+
+        changes
+    }
+}
+
+/// Makes the changes of `edit` to `repo`, owned by `username` unless listed with an owner,
+/// recording each changed setting in the audit trail with `actor`.
+pub(crate) async fn apply_edit(
+    repo: &Repository,
+    edit: &Edit,
+    username: &str,
+    pat_token: &str,
+    actor: &str,
+) -> Result<()> {
+    let owner = match repo.owner.login.is_empty() {
+        true => username,
+        false => repo.owner.login.as_str(),
+    };
+    let name = repo.name.as_str();
+    let update = UpdateRepository {
+        description: edit.description.clone(),
+        has_issues: edit.has_issues,
+        has_projects: edit.has_projects,
+        has_wiki: edit.has_wiki,
+        ..UpdateRepository::default()
+    };
+    cancel::check()?;
+    let updated = match update == UpdateRepository::default() {
+        true => None,
+        false => github::update_repo(owner, name, &update, pat_token).await?,
+    };
+    let topics = match &edit.topics {
+        Some(topics) => github::replace_topics(owner, name, topics, pat_token).await?,
+        None => None,
+    };
+    for change in edit.changes(repo) {
+        let request_id = match change.field.as_str() {
+            "topics" => topics.clone(),
+            _ => updated.clone(),
+        };
+        audit::append(&audit::AuditRecord {
+            timestamp: chrono::Utc::now(),
+            actor: actor.to_owned(),
+            repo: format!("{owner}/{name}"),
+            node_id: audit::node_id(repo),
+            field: change.field,
+            old: change.old.unwrap_or(Value::Null),
+            new: change.new,
+            request_id,
+            backup: None,
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! Append-only audit trail of every change privateer makes to a repository.
+//!
+//! Each mutation is recorded as a single JSON line in [`paths::audit_file`]. Records are only
+//! ever appended, never rewritten, so the file can be kept as compliance evidence.
+
+use crate::cli::HistoryArgs;
+use crate::github::Repository;
+use crate::i18n::t;
+use crate::{paths, schema, Result};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use console::style;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// A single change made to a repository.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Login of the user the token belongs to.
+    pub actor: String,
+    /// Full name of the repository, `owner/name`.
+    pub repo: String,
+    /// GraphQL id of the repository, which its renames and transfers keep, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    pub field: String,
+    /// Value before the change, `null` if it was not known.
+    pub old: Value,
+    pub new: Value,
+    /// The `X-GitHub-Request-Id` of the response, if GitHub sent one.
+    pub request_id: Option<String>,
+    /// The mirror clone taken before the change, with `--backup-dir`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+}
+
+impl AuditRecord {
+    /// Returns `true` if the record matches the repository and date range of `args`.
+    pub(crate) fn matches(&self, args: &HistoryArgs) -> bool {
+        self.matches_repo(args) && self.in_range(args)
+    }
+
+    /// Returns `true` if the record is of the repository of `args` under the name it has there.
+    fn matches_repo(&self, args: &HistoryArgs) -> bool {
+        args.repo
+            .as_deref()
+            .is_none_or(|repo| match repo.contains('/') {
+                true => self.repo.eq_ignore_ascii_case(repo),
+                false => self
+                    .repo
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(repo)),
+            })
+    }
+
+    fn in_range(&self, args: &HistoryArgs) -> bool {
+        args.since.is_none_or(|since| self.timestamp >= since)
+            && args.until.is_none_or(|until| self.timestamp <= until)
+    }
+}
+
+/// The records of `records` matching `args`, including those of the same repository under the
+/// names it had before or after a rename or transfer, known by its id.
+pub(crate) fn select<'a>(records: &'a [AuditRecord], args: &HistoryArgs) -> Vec<&'a AuditRecord> {
+    let ids: Vec<&str> = records
+        .iter()
+        .filter(|record| record.matches_repo(args))
+        .filter_map(|record| record.node_id.as_deref())
+        .collect();
+    records
+        .iter()
+        .filter(|record| {
+            record.matches(args)
+                || (record.in_range(args)
+                    && record
+                        .node_id
+                        .as_deref()
+                        .is_some_and(|id| ids.contains(&id)))
+        })
+        .collect()
+}
+
+/// The GraphQL id of `repo` for its records, if GitHub reported it.
+pub(crate) fn node_id(repo: &Repository) -> Option<String> {
+    (!repo.node_id.is_empty()).then(|| repo.node_id.clone())
+}
+
+/// Appends `record` to the audit trail.
+pub(crate) fn append(record: &AuditRecord) -> Result<()> {
+    let path = paths::audit_file()?;
+    append_to(&path, record).with_context(|| format!("Failed to write audit record to {path:?}"))
+}
+
+pub(crate) fn append_to(path: &Path, record: &AuditRecord) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut line = serde_json::to_string(&schema::versioned(record))?;
+    line.push('\n');
+    // A single write keeps each record on its own line even if two runs append at once.
+    file.write_all(line.as_bytes())?;
+
+    Ok(())
+}
+
+/// Reads every record from the audit trail at `path`, oldest first.
+pub(crate) fn read_from(path: &Path) -> Result<Vec<AuditRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = std::fs::File::open(path)?;
+    let mut records = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .with_context(|| format!("Malformed audit record on line {}", index + 1))?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Prints the audit records matching `args`.
+pub(crate) fn history(args: &HistoryArgs) -> Result<()> {
+    let records = read_from(&paths::audit_file()?)?;
+    let matching = select(&records, args);
+    if matching.is_empty() {
+        println!("{}", t!("no-audit-records"));
+        return Ok(());
+    }
+
+    for record in matching {
+        println!(
+            "{timestamp}  {actor}  {repo}  {field}: {old} \u{2192} {new}  {request_id}",
+            timestamp = record.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            actor = record.actor,
+            repo = style(&record.repo).yellow(),
+            field = record.field,
+            old = record.old,
+            new = record.new,
+            request_id = style(record.request_id.as_deref().unwrap_or("-")).dim(),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! Cross-checks changes to the repositories of organizations against their audit logs.
+//!
+//! GitHub records every visibility change of an organization's repository in the organization's
+//! audit log, on GitHub Enterprise Cloud. With `--verify-audit-log`, once a batch is applied,
+//! each change is looked up there by repository, visibility and the actor expected, and the id of
+//! the entry found is appended to privateer's audit trail, so the trail points at GitHub's own
+//! record of the change as compliance evidence.
+
+use crate::audit::{self, AuditRecord};
+use crate::github::{self, AuditLogEntry, Repository};
+use crate::i18n::t;
+use crate::output::status;
+use crate::{cancel, paths, Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::time::Duration;
+
+/// How many times the audit log is read, since GitHub takes a while to add events to it.
+const ATTEMPTS: usize = 3;
+
+/// The wait between two reads of the audit log.
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// The entry of `entries` recording that `actor` made `repo` private if `private`, or public
+/// otherwise, no earlier than `since`.
+pub(crate) fn find<'a>(
+    entries: &'a [AuditLogEntry],
+    repo: &str,
+    actor: &str,
+    private: bool,
+    since: DateTime<Utc>,
+) -> Option<&'a AuditLogEntry> {
+    let visibility = match private {
+        true => "private",
+        false => "public",
+    };
+    entries.iter().find(|entry| {
+        entry.action == "repo.access"
+            && entry
+                .repo
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(repo))
+            && entry
+                .actor
+                .as_deref()
+                .is_some_and(|login| login.eq_ignore_ascii_case(actor))
+            && entry.visibility.as_deref().is_none_or(|seen| seen == visibility)
+            // Events are timestamped by another clock, to the millisecond.
+            && entry.timestamp >= since.timestamp_millis() - 1_000
+    })
+}
+
+/// Looks up the visibility changes `actor` recorded since `started` to those of `changed` that
+/// belong to organizations, appending the id of each entry found to the audit trail.
+///
+/// Returns how many changes the audit log doesn't confirm.
+pub(crate) async fn verify(
+    changed: &[Repository],
+    actor: &str,
+    started: DateTime<Utc>,
+    pat_token: &str,
+) -> Result<usize> {
+    let of_orgs: Vec<String> = changed
+        .iter()
+        .filter(|repo| repo.owner.kind == "Organization")
+        .map(|repo| format!("{}/{}", repo.owner.login, repo.name))
+        .collect();
+    if of_orgs.is_empty() {
+        return Ok(0);
+    }
+    let mut pending: Vec<AuditRecord> = audit::read_from(&paths::audit_file()?)
+        .context("Failed to read the audit trail")?
+        .into_iter()
+        .filter(|record| {
+            record.field == "private" && record.timestamp >= started && record.actor == actor
+        })
+        .filter(|record| {
+            of_orgs
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&record.repo))
+        })
+        .collect();
+    let mut orgs: Vec<String> = pending
+        .iter()
+        .filter_map(|record| Some(record.repo.split_once('/')?.0.to_owned()))
+        .collect();
+    orgs.sort_unstable();
+    orgs.dedup();
+    let phrase = format!(
+        "action:repo.access created:>={}",
+        started.format("%Y-%m-%d")
+    );
+    let token = cancel::current();
+    let mut unreadable = 0;
+
+    for attempt in 1..=ATTEMPTS {
+        if pending.is_empty() {
+            break;
+        }
+        if attempt > 1 {
+            status!("{}", t!("audit-log-waiting"));
+            tokio::select! {
+                _ = tokio::time::sleep(RETRY_DELAY) => {}
+                _ = token.cancelled() => break,
+            }
+        }
+        for org in &orgs {
+            let prefix = format!("{org}/");
+            let entries = match github::get_audit_log(org, &phrase, pat_token).await {
+                Ok(entries) => entries,
+                Err(err) => {
+                    eprintln!("{err}");
+                    // Reading it again won't help an organization without the audit log API.
+                    let before = pending.len();
+                    pending.retain(|record| !record.repo.starts_with(&prefix));
+                    unreadable += before - pending.len();
+                    continue;
+                }
+            };
+            pending.retain(|record| {
+                if !record.repo.starts_with(&prefix) {
+                    return true;
+                }
+                let private = record.new == Value::Bool(true);
+                let Some(entry) = find(&entries, &record.repo, actor, private, started) else {
+                    return true;
+                };
+                let confirmation = AuditRecord {
+                    timestamp: Utc::now(),
+                    field: "audit_log".to_owned(),
+                    old: Value::Null,
+                    new: Value::String(entry.id.clone()),
+                    backup: None,
+                    ..record.clone()
+                };
+                match audit::append(&confirmation) {
+                    Ok(()) => status!(
+                        "{SUCCESS_ICON} {}",
+                        t!(
+                            "audit-log-found",
+                            repo = record.repo.as_str(),
+                            org = org,
+                            id = entry.id.as_str()
+                        )
+                    ),
+                    Err(err) => eprintln!("{ERROR_ICON} {err}"),
+                }
+                false
+            });
+        }
+    }
+    for record in &pending {
+        eprintln!(
+            "{ERROR_ICON} `{}`: no change to its visibility by `{actor}` in the audit log",
+            record.repo
+        );
+    }
+
+    Ok(pending.len() + unreadable)
+}
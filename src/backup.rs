@@ -0,0 +1,312 @@
+//! Archives of repositories, as mirror clones or `git bundle` files.
+//!
+//! With `--backup-dir`, every repository is cloned with `git clone --mirror` right before its
+//! `PATCH`, and the path of the clone goes into the audit record of the change. A failed backup
+//! stops the change, so nothing is changed without one. `privateer backup` archives repositories
+//! on demand, next to a JSON file of their description, topics and settings. The token reaches
+//! `git` through its environment, never its arguments or the remote URL kept in the clone, and
+//! is only sent to the GitHub host, alongside any configuration already in the environment.
+
+use crate::cli::{BackupArgs, SortKey, SortOrder};
+use crate::github::{self, Repository};
+use crate::output::status;
+use crate::{schema, urls, validate, Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::{anyhow, Context};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::RwLock;
+use url::Url;
+
+static DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Backs up every repository to `dir` before it is changed, or none if `None`.
+pub(crate) fn configure(dir: Option<PathBuf>) {
+    if let Ok(mut configured) = DIR.write() {
+        *configured = dir;
+    }
+}
+
+/// Returns `true` if repositories are backed up before they are changed.
+pub(crate) fn is_configured() -> bool {
+    DIR.read().is_ok_and(|dir| dir.is_some())
+}
+
+/// Mirror clones `owner/name` into the backup directory, if one is configured, returning the
+/// path of the clone.
+///
+/// If the change is dropped before the clone finishes, such as on a second `Ctrl-C`, `git` is
+/// killed and the partial clone removed.
+pub(crate) async fn before_change(
+    owner: &str,
+    name: &str,
+    pat_token: &str,
+) -> Result<Option<PathBuf>> {
+    let Some(dir) = DIR.read().ok().and_then(|dir| dir.clone()) else {
+        return Ok(None);
+    };
+
+    mirror_into(&dir, owner, name, pat_token).await.map(Some)
+}
+
+/// Mirror clones `owner/name` into `dir`, under a name holding the time, see [`before_change`].
+pub(crate) async fn mirror_into(
+    dir: &Path,
+    owner: &str,
+    name: &str,
+    pat_token: &str,
+) -> Result<PathBuf> {
+    let url = clone_url(owner, name);
+    let dest = dir.join(owner).join(format!(
+        "{name}-{}.git",
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    let partial = Partial(Some(dest.clone()));
+    let mut git =
+        tokio::process::Command::from(clone_command(&url, &dest, &["--mirror"], pat_token)?);
+    let output =
+        git.kill_on_drop(true).output().await.with_context(|| {
+            format!("{ERROR_ICON} Failed to run `git`. Hint! Install git first")
+        })?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to clone `{url}` to {dest:?}: {err}. `{owner}/{name}` was not changed",
+            err = String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    partial.keep();
+
+    Ok(dest)
+}
+
+/// A clone in progress, removed when dropped unless kept.
+struct Partial(Option<PathBuf>);
+
+impl Partial {
+    fn keep(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for Partial {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.0 {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// The HTTPS URL `git` clones `owner/name` from.
+pub(crate) fn clone_url(owner: &str, name: &str) -> String {
+    format!("{}.git", urls::host().html_repo_url(owner, name))
+}
+
+/// Runs `git clone --mirror url dest`, authenticating with `pat_token` unless it is empty.
+pub(crate) fn mirror_clone(url: &str, dest: &Path, pat_token: &str) -> Result<()> {
+    clone(url, dest, &["--mirror"], pat_token)
+}
+
+/// Runs `git clone --bare url dest`, which leaves out GitHub's read-only `refs/pull/*`, so the
+/// clone can be pushed to another repository.
+pub(crate) fn bare_clone(url: &str, dest: &Path, pat_token: &str) -> Result<()> {
+    clone(url, dest, &["--bare"], pat_token)
+}
+
+/// Runs `git clone url dest`, checking out the default branch.
+pub(crate) fn working_clone(url: &str, dest: &Path, pat_token: &str) -> Result<()> {
+    clone(url, dest, &[], pat_token)
+}
+
+fn clone(url: &str, dest: &Path, flags: &[&str], pat_token: &str) -> Result<()> {
+    run_git(clone_command(url, dest, flags, pat_token)?)
+        .map_err(|err| anyhow!("{ERROR_ICON} Failed to clone `{url}` to {dest:?}: {err}"))
+}
+
+/// The `git clone` of `url` into `dest` with `flags`, creating the parent directory of `dest`.
+fn clone_command(url: &str, dest: &Path, flags: &[&str], pat_token: &str) -> Result<Command> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("{ERROR_ICON} Failed to create the directory {parent:?}"))?;
+    }
+    let mut git = git(pat_token);
+    git.arg("clone")
+        .args(flags)
+        .args(["--quiet", url])
+        .arg(dest);
+
+    Ok(git)
+}
+
+/// Pushes every branch and tag of the bare clone at `repo` to `url`, replacing what is there.
+pub(crate) fn push_mirror(repo: &Path, url: &str, pat_token: &str) -> Result<()> {
+    let mut git = git(pat_token);
+    git.arg("-C")
+        .arg(repo)
+        .args(["push", "--mirror", "--quiet", url]);
+    run_git(git).map_err(|err| anyhow!("{ERROR_ICON} Failed to push {repo:?} to `{url}`: {err}"))
+}
+
+/// A `git` command that never prompts, authenticated with `pat_token` unless it is empty.
+pub(crate) fn git(pat_token: &str) -> Command {
+    let mut git = Command::new("git");
+    git.env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    if !pat_token.trim().is_empty() {
+        let configured = std::env::var("GIT_CONFIG_COUNT")
+            .ok()
+            .and_then(|count| count.parse().ok())
+            .unwrap_or(0);
+        git.envs(auth_config(configured, &urls::host().web, pat_token));
+    }
+    git
+}
+
+/// The variables adding an `Authorization` header with `pat_token` to the requests `git` sends
+/// to `web`, and no other host, after the `configured` entries already in `GIT_CONFIG_COUNT`.
+pub(crate) fn auth_config(configured: usize, web: &Url, pat_token: &str) -> [(String, String); 3] {
+    let credentials =
+        base64::engine::general_purpose::STANDARD.encode(format!("x-access-token:{pat_token}"));
+    [
+        ("GIT_CONFIG_COUNT".to_owned(), (configured + 1).to_string()),
+        (
+            format!("GIT_CONFIG_KEY_{configured}"),
+            format!("http.{web}.extraHeader"),
+        ),
+        (
+            format!("GIT_CONFIG_VALUE_{configured}"),
+            format!("Authorization: Basic {credentials}"),
+        ),
+    ]
+}
+
+/// Runs `git`, failing with what it printed to standard error.
+pub(crate) fn run_git(mut git: Command) -> Result<()> {
+    let output = git
+        .output()
+        .with_context(|| format!("{ERROR_ICON} Failed to run `git`. Hint! Install git first"))?;
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// What is kept next to an archive: the repository as GitHub described it when archived.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Metadata {
+    pub archived_at: DateTime<Utc>,
+    /// File name of the archive, next to this file; `None` for an empty repository, which has
+    /// nothing to bundle.
+    pub archive: Option<String>,
+    /// Description, topics, visibility and the other settings of the repository.
+    pub repository: Repository,
+}
+
+/// Archives the repositories of `args` into `args.out`, reporting each.
+pub(crate) async fn run(pat_token: &str, args: &BackupArgs) -> Result<()> {
+    let username = github::get_authenticated_user(pat_token).await?;
+    let repos = match args.all {
+        true => github::get_repos_request(&username, pat_token, SortKey::Name, SortOrder::Asc)
+            .await?
+            .into_iter()
+            .filter(|repo| repo.owner.login.eq_ignore_ascii_case(&username))
+            .collect(),
+        false => {
+            let mut repos = Vec::with_capacity(args.repos.len());
+            for input in &args.repos {
+                let repo = validate::repo(input)?;
+                let owner = repo.owner.as_deref().unwrap_or(&username);
+                repos.push(github::get_repo(owner, &repo.name, pat_token).await?);
+            }
+            repos
+        }
+    };
+
+    let mut failures = Vec::new();
+    for repo in repos {
+        let full_name = format!("{}/{}", repo.owner.login, repo.name);
+        let url = clone_url(&repo.owner.login, &repo.name);
+        let (out, bundle, pat_token) = (args.out.clone(), args.bundle, pat_token.to_owned());
+        let archived =
+            tokio::task::spawn_blocking(move || archive(&url, &repo, &out, bundle, &pat_token))
+                .await?;
+        match archived {
+            Ok(path) => status!("{SUCCESS_ICON} {full_name}: {}", path.display()),
+            Err(err) => {
+                eprintln!("{err}");
+                failures.push(full_name);
+            }
+        }
+    }
+    if !failures.is_empty() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to archive `{count}` repos: {names}",
+            count = failures.len(),
+            names = failures.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Archives `repo`, cloned from `url`, into `out/<owner>/`: a mirror clone `<name>.git`, or with
+/// `bundle` a `<name>.bundle` file, plus `<name>.json` with its [`Metadata`]. Returns the path of
+/// the metadata file.
+pub(crate) fn archive(
+    url: &str,
+    repo: &Repository,
+    out: &Path,
+    bundle: bool,
+    pat_token: &str,
+) -> Result<PathBuf> {
+    let dir = out.join(&repo.owner.login);
+    let name = &repo.name;
+    let archive = match (bundle, repo.is_empty()) {
+        (false, _) => {
+            mirror_clone(url, &dir.join(format!("{name}.git")), pat_token)?;
+            Some(format!("{name}.git"))
+        }
+        // `git bundle` refuses to create a bundle without any commits.
+        (true, true) => None,
+        (true, false) => {
+            let mirror = dir.join(format!(".{name}.git.tmp"));
+            let _ = std::fs::remove_dir_all(&mirror);
+            mirror_clone(url, &mirror, pat_token)?;
+            let bundled = create_bundle(&mirror, &dir.join(format!("{name}.bundle")));
+            let _ = std::fs::remove_dir_all(&mirror);
+            bundled?;
+            Some(format!("{name}.bundle"))
+        }
+    };
+    let metadata = Metadata {
+        archived_at: Utc::now(),
+        archive,
+        repository: repo.clone(),
+    };
+    let path = dir.join(format!("{name}.json"));
+    std::fs::write(
+        &path,
+        serde_json::to_string_pretty(&schema::versioned(&metadata))?,
+    )
+    .with_context(|| format!("{ERROR_ICON} Failed to write {path:?}"))?;
+
+    Ok(path)
+}
+
+/// Writes every ref of the repository at `repo` to the bundle file `dest`.
+fn create_bundle(repo: &Path, dest: &Path) -> Result<()> {
+    let dest = std::path::absolute(dest)?;
+    let mut git = git("");
+    git.arg("-C")
+        .arg(repo)
+        .args(["bundle", "create", "--quiet"])
+        .arg(&dest)
+        .arg("--all");
+    run_git(git).map_err(|err| anyhow!("{ERROR_ICON} Failed to bundle {repo:?} to {dest:?}: {err}"))
+}
@@ -0,0 +1,354 @@
+//! `privateer bulk`: applies per-repository settings from a CSV file, such as a spreadsheet.
+//!
+//! Each row is `owner/name,visibility,archived,description`, where `visibility` is `public` or
+//! `private` and `archived` is `true` or `false`; an empty cell leaves the setting as it is, and
+//! a description with commas is quoted. A header row, blank lines and lines starting with `#`
+//! are ignored. The whole file is validated before anything is fetched, then every repository
+//! is compared with its row and the differences shown, and once confirmed the rows are applied
+//! one at a time: a row that fails doesn't stop the next.
+
+use crate::apply::{self, Applied};
+use crate::cli::BulkArgs;
+use crate::diff::{self, FieldChange};
+use crate::github::{self, Repository, UpdateRepository};
+use crate::i18n::t;
+use crate::output::status;
+use crate::plan::SkipReason;
+use crate::{cancel, exit, lists, lockdown, prompter, validate};
+use crate::{Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::{anyhow, Context};
+use console::style;
+
+/// The columns of a row, in order, as the header of a file names them.
+pub(crate) const COLUMNS: [&str; 4] = ["owner/name", "visibility", "archived", "description"];
+
+/// The settings one row of the file wants for a repository; `None` leaves a setting as it is.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Row {
+    /// Line of the file the row is on, from 1.
+    pub line: usize,
+    pub owner: String,
+    pub name: String,
+    pub private: Option<bool>,
+    pub archived: Option<bool>,
+    pub description: Option<String>,
+}
+
+impl Row {
+    pub(crate) fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+
+    /// The settings of the row against their current value in `repo`.
+    pub(crate) fn changes(&self, repo: &Repository) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        if let Some(private) = self.private {
+            changes.push(FieldChange::visibility(repo.private, private));
+        }
+        if let Some(archived) = self.archived {
+            changes.push(FieldChange {
+                field: "archived".to_owned(),
+                old: Some(repo.archived.into()),
+                new: archived.into(),
+            });
+        }
+        if let Some(description) = &self.description {
+            changes.push(FieldChange {
+                field: "description".to_owned(),
+                old: Some(repo.description.clone().unwrap_or_default().into()),
+                new: description.clone().into(),
+            });
+        }
+
+        changes
+    }
+}
+
+/// Validates the rows of the file of `args` and shows how each repository differs from its row,
+/// then applies the rows once confirmed.
+pub(crate) async fn run(pat_token: &str, args: &BulkArgs) -> Result<()> {
+    let path = &args.file;
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("{ERROR_ICON} Failed to read {path:?}"))?;
+    let rows = parse(&text).map_err(|errors| {
+        let lines: Vec<String> = errors
+            .iter()
+            .map(|(line, err)| format!("line {line}: {err}"))
+            .collect();
+        anyhow!(
+            "{ERROR_ICON} {path:?} has `{count}` invalid rows, so nothing was changed:\n  {}",
+            lines.join("\n  "),
+            count = errors.len()
+        )
+    })?;
+    if rows.is_empty() {
+        return Err(anyhow!(
+            "{ERROR_ICON} {path:?} has no rows. Hint! Each row is `{}`",
+            COLUMNS.join(",")
+        ));
+    }
+    let actor = github::get_authenticated_user(pat_token).await?;
+
+    let mut plans = Vec::with_capacity(rows.len());
+    let mut missing = Vec::new();
+    for row in rows {
+        let repo = match github::get_repo(&row.owner, &row.name, pat_token).await {
+            Ok(repo) => repo,
+            Err(err) => {
+                missing.push(format!("line {}: {err}", row.line));
+                continue;
+            }
+        };
+        if let Some(reason) = lists::reason(&repo) {
+            status!(
+                "{}: {}",
+                row.full_name(),
+                t!("change-skipped", reason = reason.describe())
+            );
+            continue;
+        }
+        let changes = row.changes(&repo);
+        println!("{}", diff::render(&row.full_name(), &changes));
+        if changes.iter().any(|change| !change.is_unchanged()) {
+            plans.push((row, repo, changes));
+        }
+    }
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "{ERROR_ICON} `{count}` rows name repositories that can't be fetched, so nothing was changed:\n  {}",
+            missing.join("\n  "),
+            count = missing.len()
+        ));
+    }
+    if plans.is_empty() {
+        status!("{SUCCESS_ICON} {}", t!("bulk-all-done"));
+        return Ok(());
+    }
+    println!(
+        "\n{}",
+        style(t!("bulk-planned", count = plans.len())).bold()
+    );
+    if args.dry_run {
+        return Ok(());
+    }
+    lockdown::confirm(args.yes)?;
+
+    let mut failures = Vec::new();
+    for (row, repo, changes) in &plans {
+        cancel::check()?;
+        match update(row, repo, changes, &actor, pat_token).await {
+            Ok(()) => status!(
+                "{SUCCESS_ICON} {}",
+                t!("bulk-done", repo = row.full_name(), line = row.line)
+            ),
+            Err(err) => {
+                eprintln!(
+                    "{ERROR_ICON} line {}: `{}`: {err}",
+                    row.line,
+                    row.full_name()
+                );
+                failures.push(row.line);
+            }
+        }
+    }
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    Err(exit::Exit {
+        code: exit::PARTIAL_FAILURE,
+        message: format!(
+            "{ERROR_ICON} Updated `{updated}` of `{total}` repos, the rows on lines {lines} failed",
+            updated = plans.len() - failures.len(),
+            total = plans.len(),
+            lines = failures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+    .into())
+}
+
+/// Makes the `changes` of `row` to `repo`, recording each in the audit trail with `actor`.
+///
+/// An archived repository is read-only, so it is unarchived before anything else changes, and
+/// archived after everything else did.
+async fn update(
+    row: &Row,
+    repo: &Repository,
+    changes: &[FieldChange],
+    actor: &str,
+    pat_token: &str,
+) -> Result<()> {
+    let changed = |field: &str| {
+        changes
+            .iter()
+            .find(|change| change.field == field && !change.is_unchanged())
+    };
+    if let Some(change) = changed("archived").filter(|_| row.archived == Some(false)) {
+        let update = UpdateRepository {
+            archived: Some(false),
+            ..UpdateRepository::default()
+        };
+        set(repo, update, change, actor, pat_token).await?;
+    }
+    if let (Some(private), Some(_)) = (row.private, changed("visibility")) {
+        let privacy = private.to_string();
+        let applied = apply::apply_one(repo, &privacy, actor, pat_token, actor, &|_| {}).await?;
+        if matches!(applied, Applied::Skipped) {
+            return Err(anyhow!(
+                "skipped: {}",
+                SkipReason::ChangedSincePlanned.describe()
+            ));
+        }
+    }
+    if let Some(change) = changed("description") {
+        let update = UpdateRepository {
+            description: row.description.clone(),
+            ..UpdateRepository::default()
+        };
+        set(repo, update, change, actor, pat_token).await?;
+    }
+    if let Some(change) = changed("archived").filter(|_| row.archived == Some(true)) {
+        let update = UpdateRepository {
+            archived: Some(true),
+            ..UpdateRepository::default()
+        };
+        set(repo, update, change, actor, pat_token).await?;
+    }
+
+    Ok(())
+}
+
+/// Sends `update` to `repo` and records it in the audit trail as `change`.
+async fn set(
+    repo: &Repository,
+    update: UpdateRepository,
+    change: &FieldChange,
+    actor: &str,
+    pat_token: &str,
+) -> Result<()> {
+    let request_id = github::update_repo(&repo.owner.login, &repo.name, &update, pat_token).await?;
+    lockdown::record(repo, change, actor, request_id)
+}
+
+/// Parses the rows of a file, or returns the line and problem of every invalid row.
+pub(crate) fn parse(text: &str) -> std::result::Result<Vec<Row>, Vec<(usize, String)>> {
+    let mut rows: Vec<Row> = Vec::new();
+    let mut errors = Vec::new();
+    // Spreadsheets often save a byte order mark first.
+    for (index, line) in text.trim_start_matches('\u{feff}').lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        match row(index + 1, line) {
+            Ok(None) => {}
+            Ok(Some(row)) => match rows
+                .iter()
+                .find(|other| other.full_name().eq_ignore_ascii_case(&row.full_name()))
+            {
+                Some(other) => errors.push((
+                    row.line,
+                    format!("`{}` is on line {} already", row.full_name(), other.line),
+                )),
+                None => rows.push(row),
+            },
+            Err(err) => errors.push((index + 1, err)),
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(rows),
+        false => Err(errors),
+    }
+}
+
+/// Parses the row on `line`, or returns `None` for a header row.
+fn row(line: usize, text: &str) -> std::result::Result<Option<Row>, String> {
+    let mut cells = fields(text)?;
+    if ["owner/name", "repo", "repository"]
+        .iter()
+        .any(|header| cells[0].eq_ignore_ascii_case(header))
+    {
+        return Ok(None);
+    }
+    if cells.len() > COLUMNS.len() {
+        return Err(format!(
+            "has `{}` columns instead of `{}`. Hint! Quote descriptions that contain commas",
+            cells.len(),
+            COLUMNS.join(",")
+        ));
+    }
+    cells.resize(COLUMNS.len(), String::new());
+
+    let invalid_repo = || format!("`{}` is not a repository as `owner/name`", cells[0]);
+    let repo = validate::repo(&cells[0]).map_err(|_| invalid_repo())?;
+    let owner = repo.owner.ok_or_else(invalid_repo)?;
+    let private = match cells[1].to_lowercase().as_str() {
+        "" => None,
+        "private" => Some(true),
+        "public" => Some(false),
+        _ => {
+            return Err(format!(
+                "`{}` is not a visibility; use `public` or `private`",
+                cells[1]
+            ))
+        }
+    };
+    let archived = match cells[2].is_empty() {
+        true => None,
+        false => Some(prompter::parse_yes_no(&cells[2]).ok_or_else(|| {
+            format!(
+                "`{}` is not whether to archive; use `true` or `false`",
+                cells[2]
+            )
+        })?),
+    };
+    let description = Some(cells[3].clone()).filter(|description| !description.is_empty());
+    if private.is_none() && archived.is_none() && description.is_none() {
+        return Err(format!(
+            "`{}/{}` changes nothing; fill in its visibility, archived or description",
+            owner, repo.name
+        ));
+    }
+
+    Ok(Some(Row {
+        line,
+        owner,
+        name: repo.name,
+        private,
+        archived,
+        description,
+    }))
+}
+
+/// Splits a CSV line into its trimmed cells, with `"` around cells that hold commas and `""` for
+/// a quote inside them.
+fn fields(text: &str) -> std::result::Result<Vec<String>, String> {
+    let mut cells = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let cell = cells.last_mut().expect("there is always a cell");
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.trim().is_empty() => {
+                cell.clear();
+                quoted = true;
+            }
+            ',' if !quoted => cells.push(String::new()),
+            c => cell.push(c),
+        }
+    }
+    if quoted {
+        return Err("has a quote that isn't closed".to_owned());
+    }
+
+    Ok(cells.iter().map(|cell| cell.trim().to_owned()).collect())
+}
@@ -0,0 +1,180 @@
+//! A cache of single repositories fetched during an interactive session.
+//!
+//! The interactive flow fetches the same repository to show it and plan its change. Each
+//! `GET /repos/{owner}/{name}` is kept for [`TTL`], in memory and in a file of the cache
+//! directory so that runs moments apart share it too. A repository about to be changed is always
+//! fetched from GitHub again, so a change is never checked against a stale copy, and any other
+//! request to a repository, such as the `PATCH` changing its visibility, drops its copy.
+//!
+//! The full names of the repositories listed for a user are kept the same way, to complete the
+//! name typed at the repository prompt without listing them again on every run.
+
+use crate::github::Repository;
+use crate::output::verbose;
+use crate::Result;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use url::Url;
+
+/// How long a fetched repository is reused.
+pub(crate) const TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    fetched_at: DateTime<Utc>,
+    repo: Repository,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Listing {
+    fetched_at: DateTime<Utc>,
+    full_names: Vec<String>,
+}
+
+/// What the cache file holds.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Contents {
+    /// Repositories by the API URL they were fetched from.
+    #[serde(default)]
+    repos: HashMap<String, Entry>,
+    /// Full names of the repositories listed for a user, by the lowercased login.
+    #[serde(default)]
+    listings: HashMap<String, Listing>,
+}
+
+/// Repositories by the API URL they were fetched from, and listings by user.
+#[derive(Debug)]
+pub(crate) struct RepoCache {
+    contents: Mutex<Contents>,
+    /// Where the entries are kept between runs.
+    file: PathBuf,
+    ttl: Duration,
+}
+
+impl RepoCache {
+    /// A cache kept in memory and in `file`, starting with the entries of the file younger than
+    /// `ttl`. A missing or malformed file starts it empty.
+    pub(crate) fn load(file: &Path, ttl: Duration) -> Self {
+        let now = Utc::now();
+        let contents: Contents = std::fs::read_to_string(file)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let cache = Self {
+            contents: Mutex::new(contents),
+            file: file.to_owned(),
+            ttl,
+        };
+        {
+            let mut contents = cache.lock();
+            contents
+                .repos
+                .retain(|_, entry| cache.is_fresh(entry.fetched_at, now));
+            contents
+                .listings
+                .retain(|_, listing| cache.is_fresh(listing.fetched_at, now));
+        }
+
+        cache
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Contents> {
+        self.contents.lock().expect("cache lock")
+    }
+
+    fn is_fresh(&self, fetched_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        (now - fetched_at).to_std().is_ok_and(|age| age < self.ttl)
+    }
+
+    /// The repository fetched from `url` less than the TTL ago, if any.
+    pub(crate) fn get(&self, url: &Url) -> Option<Repository> {
+        let now = Utc::now();
+        self.lock()
+            .repos
+            .get(&key(url))
+            .filter(|entry| self.is_fresh(entry.fetched_at, now))
+            .map(|entry| entry.repo.clone())
+    }
+
+    /// Keeps `repo`, just fetched from `url`.
+    pub(crate) fn insert(&self, url: &Url, repo: &Repository) {
+        let entry = Entry {
+            fetched_at: Utc::now(),
+            repo: repo.clone(),
+        };
+        self.lock().repos.insert(key(url), entry);
+        self.persist();
+    }
+
+    /// The full names of the repositories listed for `login` less than the TTL ago, if any.
+    pub(crate) fn listing(&self, login: &str) -> Option<Vec<String>> {
+        let now = Utc::now();
+        self.lock()
+            .listings
+            .get(&login.to_lowercase())
+            .filter(|listing| self.is_fresh(listing.fetched_at, now))
+            .map(|listing| listing.full_names.clone())
+    }
+
+    /// Keeps `full_names`, the repositories just listed for `login`.
+    pub(crate) fn insert_listing(&self, login: &str, full_names: Vec<String>) {
+        let listing = Listing {
+            fetched_at: Utc::now(),
+            full_names,
+        };
+        self.lock().listings.insert(login.to_lowercase(), listing);
+        self.persist();
+    }
+
+    /// Drops the repository that a request to `url`, such as its topics, may have changed, and
+    /// the listings, which a rename, transfer or new repository changes.
+    pub(crate) fn invalidate(&self, url: &Url) {
+        let url = key(url);
+        let removed = {
+            let mut contents = self.lock();
+            let before = contents.repos.len() + contents.listings.len();
+            contents.repos.retain(|repo, _| {
+                !url.strip_prefix(repo.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            });
+            contents.listings.clear();
+            before != contents.repos.len()
+        };
+        if removed {
+            self.persist();
+        }
+    }
+
+    /// Writes the entries to the cache file. The cache only saves requests, so failing to write
+    /// it is not an error.
+    fn persist(&self) {
+        if let Err(err) = self.save(&self.file) {
+            verbose!("Not caching repositories: {err:#}");
+        }
+    }
+
+    fn save(&self, file: &Path) -> Result<()> {
+        // Held until the file is renamed, as concurrent changes share the partial file.
+        let contents = self.lock();
+        let text = serde_json::to_string(&*contents)?;
+        // Written next to the file and renamed over it, so a concurrent run never reads half.
+        let partial = file.with_extension("json.tmp");
+        std::fs::write(&partial, text)
+            .and_then(|()| std::fs::rename(&partial, file))
+            .with_context(|| format!("Failed to write the cache {file:?}"))
+    }
+}
+
+/// The URL without its query, lowercased, as owners and names are case-insensitive.
+fn key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_query(None);
+    url.set_fragment(None);
+
+    url.as_str().trim_end_matches('/').to_lowercase()
+}
@@ -0,0 +1,63 @@
+//! Stopping work at safe points instead of aborting it.
+//!
+//! Long operations check the [`CancellationToken`] in scope between steps: before each page of
+//! a listing, and before each repository is checked or changed. A change whose `PATCH` was sent
+//! always runs on to its audit record, so what was changed is never lost. `Ctrl-C` in the
+//! command, `q` in the TUI and `SIGTERM` in the daemon cancel the token.
+
+use crate::{Result, ERROR_ICON};
+use std::fmt;
+use std::future::Future;
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static TOKEN: CancellationToken;
+}
+
+/// The error of work stopped by its cancellation token.
+#[derive(Debug)]
+pub(crate) struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{ERROR_ICON} Cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Runs `future` with `token` as the one checked at its safe points.
+pub(crate) async fn scope<F: Future>(token: CancellationToken, future: F) -> F::Output {
+    TOKEN.scope(token, future).await
+}
+
+/// The token in scope, or one that is never cancelled.
+pub(crate) fn current() -> CancellationToken {
+    TOKEN.try_with(CancellationToken::clone).unwrap_or_default()
+}
+
+/// Fails with [`Cancelled`] if the token in scope was cancelled; called at safe points.
+pub(crate) fn check() -> Result<()> {
+    match current().is_cancelled() {
+        true => Err(Cancelled.into()),
+        false => Ok(()),
+    }
+}
+
+/// Returns `true` if `err` comes from work stopped by its token.
+pub(crate) fn is_cancelled(err: &anyhow::Error) -> bool {
+    err.is::<Cancelled>()
+}
+
+/// Runs `future`, cancelling the token in scope on `Ctrl-C` so that `future` stops at its next
+/// safe point rather than immediately.
+pub(crate) async fn on_ctrl_c<F: Future>(future: F) -> F::Output {
+    let token = current();
+    let mut future = std::pin::pin!(future);
+    tokio::select! {
+        output = &mut future => return output,
+        _ = tokio::signal::ctrl_c() => token.cancel(),
+    }
+
+    future.await
+}
@@ -0,0 +1,244 @@
+//! Recording API exchanges to files, and replaying them instead of reaching GitHub.
+//!
+//! `--record <DIR>` writes every request and its response to a numbered JSON file in `DIR`.
+//! Only the method, path and body of requests are kept, never their headers, and the token is
+//! scrubbed from anything else that would be written. `--replay <DIR>` answers each request with
+//! the first recorded exchange for the same method and path that wasn't used yet, so a run can be
+//! repeated without a network or a token, such as in the integration tests.
+
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport, SendFuture};
+use crate::{Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// Response headers worth keeping; the rest are noise or identify the account.
+const KEPT_HEADERS: [&str; 8] = [
+    "link",
+    "location",
+    "retry-after",
+    "x-github-request-id",
+    "x-ratelimit-remaining",
+    "x-ratelimit-reset",
+    "deprecation",
+    "sunset",
+];
+
+/// Written in place of the token.
+const REDACTED: &str = "<token>";
+
+static REPLAYING: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` while answering from a recording, when no token is needed.
+pub(crate) fn is_replaying() -> bool {
+    REPLAYING.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedRequest {
+    pub method: String,
+    /// Path and query, without the host.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// The body as JSON, or as a string if it isn't JSON.
+    #[serde(default)]
+    pub body: Value,
+}
+
+/// One request and the response to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Exchange {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+fn path_of(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_owned(),
+    }
+}
+
+fn json_or_string(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_owned()))
+}
+
+impl Exchange {
+    /// The exchange of `request` and `response`, with `token` replaced wherever it appears.
+    pub(crate) fn new(request: &HttpRequest, response: &HttpResponse, token: Option<&str>) -> Self {
+        let scrub = |text: &str| match token {
+            Some(token) if !token.is_empty() => text.replace(token, REDACTED),
+            _ => text.to_owned(),
+        };
+        let headers = KEPT_HEADERS
+            .iter()
+            .filter_map(|&name| Some((name.to_owned(), scrub(response.header_str(name)?))))
+            .collect();
+
+        Self {
+            request: RecordedRequest {
+                method: request.method.to_string(),
+                path: scrub(&path_of(&request.url)),
+                body: request
+                    .body
+                    .as_deref()
+                    .map(|body| json_or_string(&scrub(body))),
+            },
+            response: RecordedResponse {
+                status: response.status.as_u16(),
+                headers,
+                body: json_or_string(&scrub(&response.body)),
+            },
+        }
+    }
+
+    /// The recorded response, as if it came from `url`.
+    pub(crate) fn response(&self, url: Url) -> Result<HttpResponse> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.response.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+        let body = match &self.response.body {
+            Value::String(text) => text.clone(),
+            Value::Null => String::new(),
+            body => body.to_string(),
+        };
+
+        Ok(HttpResponse {
+            status: StatusCode::from_u16(self.response.status)?,
+            headers,
+            url,
+            body,
+        })
+    }
+}
+
+/// Sends requests through another transport, writing each exchange to a directory.
+pub(crate) struct RecordingTransport {
+    inner: Arc<dyn HttpTransport>,
+    dir: PathBuf,
+    count: AtomicUsize,
+}
+
+impl RecordingTransport {
+    /// Records the requests sent through `inner` to `dir`, creating it if needed.
+    pub(crate) fn new(dir: &Path, inner: Arc<dyn HttpTransport>) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("{ERROR_ICON} Failed to create the directory {dir:?}"))?;
+
+        Ok(Self {
+            inner,
+            dir: dir.to_owned(),
+            count: AtomicUsize::new(0),
+        })
+    }
+}
+
+impl HttpTransport for RecordingTransport {
+    fn send(&self, request: HttpRequest) -> SendFuture<'_> {
+        Box::pin(async move {
+            let token = request
+                .headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split_whitespace().nth(1))
+                .map(str::to_owned);
+            let response = self.inner.send(request.clone()).await?;
+            let exchange = Exchange::new(&request, &response, token.as_deref());
+            let number = self.count.fetch_add(1, Ordering::Relaxed) + 1;
+            let slug: String = request
+                .url
+                .path()
+                .trim_matches('/')
+                .chars()
+                .map(|c| match c.is_ascii_alphanumeric() {
+                    true => c,
+                    false => '-',
+                })
+                .collect();
+            let path = self
+                .dir
+                .join(format!("{number:03}-{}-{slug}.json", request.method));
+            std::fs::write(&path, serde_json::to_string_pretty(&exchange)?)
+                .with_context(|| format!("{ERROR_ICON} Failed to record to {path:?}"))?;
+
+            Ok(response)
+        })
+    }
+}
+
+/// Answers requests from the exchanges recorded in a directory.
+pub(crate) struct ReplayTransport {
+    dir: PathBuf,
+    /// Exchanges not used yet, in the order they were recorded.
+    exchanges: Mutex<Vec<Exchange>>,
+}
+
+impl ReplayTransport {
+    /// Loads the exchanges recorded in `dir`, and stops asking for a token.
+    pub(crate) fn load(dir: &Path) -> Result<Self> {
+        let mut paths = std::fs::read_dir(dir)
+            .with_context(|| format!("{ERROR_ICON} Failed to read the recording {dir:?}"))?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "json"));
+        paths.sort();
+        let exchanges = paths
+            .iter()
+            .map(|path| {
+                let text = std::fs::read_to_string(path)?;
+                serde_json::from_str(&text)
+                    .with_context(|| format!("{ERROR_ICON} Invalid recorded exchange {path:?}"))
+            })
+            .collect::<Result<Vec<Exchange>>>()?;
+        REPLAYING.store(true, Ordering::Relaxed);
+
+        Ok(Self {
+            dir: dir.to_owned(),
+            exchanges: Mutex::new(exchanges),
+        })
+    }
+
+    fn next(&self, method: &Method, path: &str) -> Option<Exchange> {
+        let mut exchanges = self.exchanges.lock().ok()?;
+        let index = exchanges.iter().position(|exchange| {
+            exchange.request.method == method.as_str() && exchange.request.path == path
+        })?;
+
+        Some(exchanges.remove(index))
+    }
+}
+
+impl HttpTransport for ReplayTransport {
+    fn send(&self, request: HttpRequest) -> SendFuture<'_> {
+        let path = path_of(&request.url);
+        let response = match self.next(&request.method, &path) {
+            Some(exchange) => exchange.response(request.url),
+            None => Err(anyhow!(
+                "{ERROR_ICON} Nothing recorded in {dir:?} answers {method} {path}. Hint! Record the run again with `--record`",
+                dir = self.dir,
+                method = request.method
+            )),
+        };
+
+        Box::pin(std::future::ready(response))
+    }
+}
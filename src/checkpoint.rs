@@ -0,0 +1,103 @@
+//! Progress of a large apply, saved after each chunk so an interrupted run can be resumed.
+//!
+//! A plan of more than `--chunk-size` changes is applied a chunk at a time. After each chunk the
+//! changes that are left, including the ones that failed, are written here; `privateer --resume`
+//! applies them without fetching and reviewing the repositories again. A run that finishes every
+//! change removes the checkpoint.
+
+use crate::apply::Plan;
+use crate::github::Repository;
+use crate::{paths, schema, Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Checkpoint {
+    pub saved_at: DateTime<Utc>,
+    /// The user repositories without an owner belong to.
+    pub username: String,
+    /// Who applied the changes, as recorded in the audit trail.
+    pub actor: String,
+    /// How many changes were made before the checkpoint.
+    pub applied: usize,
+    pub remaining: Vec<Remaining>,
+}
+
+/// A change left to apply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Remaining {
+    pub repo: Repository,
+    /// `"true"` to make the repository private, `"false"` to make it public.
+    pub private: String,
+}
+
+impl Checkpoint {
+    pub(crate) fn new(username: &str, actor: &str, applied: usize, remaining: Plan) -> Self {
+        Self {
+            saved_at: Utc::now(),
+            username: username.to_owned(),
+            actor: actor.to_owned(),
+            applied,
+            remaining: remaining
+                .into_iter()
+                .map(|(repo, private)| Remaining { repo, private })
+                .collect(),
+        }
+    }
+
+    /// The changes left, as a plan to apply.
+    pub(crate) fn plan(&self) -> Plan {
+        self.remaining
+            .iter()
+            .map(|change| (change.repo.clone(), change.private.clone()))
+            .collect()
+    }
+}
+
+/// Saves `checkpoint`, replacing the previous one.
+pub(crate) fn save(checkpoint: &Checkpoint) -> Result<()> {
+    save_at(&paths::checkpoint_file()?, checkpoint)
+}
+
+pub(crate) fn save_at(path: &Path, checkpoint: &Checkpoint) -> Result<()> {
+    // Written next to the file and renamed over it, so a crash never leaves half a checkpoint.
+    let partial = path.with_extension("json.tmp");
+    std::fs::write(
+        &partial,
+        serde_json::to_string_pretty(&schema::versioned(checkpoint))?,
+    )
+    .and_then(|()| std::fs::rename(&partial, path))
+    .with_context(|| format!("Failed to write the checkpoint {path:?}"))
+}
+
+/// Returns the checkpoint of the last unfinished run, failing if there is none.
+pub(crate) fn load() -> Result<Checkpoint> {
+    read(&paths::checkpoint_file()?)?.ok_or_else(|| {
+        anyhow!("{ERROR_ICON} No unfinished run to resume. Hint! Only plans larger than `--chunk-size` are checkpointed")
+    })
+}
+
+pub(crate) fn read(path: &Path) -> Result<Option<Checkpoint>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(path)?;
+    schema::check(&text, &format!("The checkpoint {path:?}"))?;
+    let checkpoint =
+        serde_json::from_str(&text).with_context(|| format!("Malformed checkpoint {path:?}"))?;
+
+    Ok(Some(checkpoint))
+}
+
+/// Removes the checkpoint once every change was made.
+pub(crate) fn clear() -> Result<()> {
+    let path = paths::checkpoint_file()?;
+    match std::fs::remove_file(&path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            Err(err).with_context(|| format!("Failed to remove the checkpoint {path:?}"))
+        }
+        _ => Ok(()),
+    }
+}
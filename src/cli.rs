@@ -0,0 +1,902 @@
+//! Command-line interface definitions.
+//!
+//! Running `privateer` without a subcommand starts the interactive flow that prompts for
+//! repositories and their privacy settings.
+
+use crate::conflict::OnConflict;
+use crate::events::ProgressFormat;
+use crate::policy::Policy;
+use crate::query::Path;
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// CLI Application for making a GitHub repository private.
+#[derive(Debug, Parser)]
+#[command(name = "privateer", version, about, after_long_help = EXAMPLES)]
+pub(crate) struct Cli {
+    /// Only print errors and the final summary.
+    #[arg(long, short, global = true)]
+    pub quiet: bool,
+
+    /// Explain what privateer does on standard error, such as which `.env` file it loaded.
+    #[arg(long, short, global = true)]
+    pub verbose: bool,
+
+    /// Load environment variables, such as `PAT_TOKEN`, from this file instead of `.env` in the
+    /// current directory. The file must exist.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub env_file: Option<PathBuf>,
+
+    /// Disable colors and emoji icons; also enabled by the `NO_COLOR` environment variable.
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Print `[OK]`/`[FAIL]` instead of emoji icons; the default when the terminal can't show them.
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// Only ever append to the output, for screen readers: no colors, icons or progress bars,
+    /// and repositories are picked by typing their numbers in a list instead of with the cursor.
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// Show dates as timestamps, such as `2023-05-11T09:30:00Z`, instead of the time since,
+    /// such as `3 weeks ago`.
+    #[arg(long, global = true)]
+    pub absolute_dates: bool,
+
+    /// How to show the progress of listings and changes: bars on a terminal and plain lines
+    /// otherwise (`auto`), plain lines, or JSON lines on standard error for other programs.
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub progress: ProgressFormat,
+
+    /// Mirror clone each repository into this directory with `git clone --mirror` before changing
+    /// it, recording the clone in the audit trail.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub backup_dir: Option<PathBuf>,
+
+    /// Make repositories public even if secret scanning reports open alerts for them, or their
+    /// alerts can't be listed.
+    #[arg(long, global = true)]
+    pub allow_secret_alerts: bool,
+
+    /// Before making a repository public, clone the last COMMITS commits of its default branch
+    /// and refuse if they add likely secrets, such as AWS access keys or private keys.
+    #[arg(
+        long,
+        global = true,
+        value_name = "COMMITS",
+        num_args = 0..=1,
+        default_missing_value = "100",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub scan_history: Option<u64>,
+
+    /// Language of prompts and messages, such as `en` [default: from `LC_ALL`, `LC_MESSAGES` or
+    /// `LANG`].
+    #[arg(long, global = true, value_name = "LOCALE")]
+    pub locale: Option<String>,
+
+    /// What to do with a repository whose visibility changed on GitHub since the changes were
+    /// planned [default: ask in the interactive flow, `replan` otherwise].
+    #[arg(long, global = true, value_enum, value_name = "ACTION")]
+    pub on_conflict: Option<OnConflict>,
+
+    /// File of `owner/name` globs, one per line, of repositories no command changes, such as
+    /// those meant to stay public.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub allowlist: Option<PathBuf>,
+
+    /// File of `owner/name` globs, one per line, of repositories no command touches or lists.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub denylist: Option<PathBuf>,
+
+    /// Run even if another privateer run seems to be active.
+    #[arg(long, global = true)]
+    pub force: bool,
+
+    /// Save the input history of prompts to the state directory and recall it in later runs.
+    #[arg(long)]
+    pub persist_history: bool,
+
+    /// Show a desktop notification with the summary when a batch of changes or a watch-mode
+    /// change finishes.
+    #[arg(long, global = true)]
+    pub notify: bool,
+
+    /// Write every GitHub API request and response to numbered JSON files in this directory,
+    /// without the token.
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Answer GitHub API requests from the files written by `--record` instead of the network.
+    /// No token is needed.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
+    /// Fetch every repository from GitHub, instead of reusing those fetched in the last five
+    /// minutes by this or an earlier interactive run.
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// REST API version to request with every call, as `YYYY-MM-DD` [default: 2022-11-28].
+    /// `none` requests none, for GitHub Enterprise Server releases that predate API versions.
+    #[arg(
+        long,
+        global = true,
+        value_name = "VERSION",
+        env = "PRIVATEER_API_VERSION",
+        value_parser = parse_api_version
+    )]
+    pub api_version: Option<ApiVersion>,
+
+    /// Owner of the repositories named without one, such as an organization [default: the user
+    /// the token belongs to].
+    #[arg(long, visible_alias = "owner", env = "PRIVATEER_USERNAME")]
+    pub username: Option<String>,
+
+    /// Repository to change as `name`, `owner/name` or a GitHub URL, instead of prompting for
+    /// them; may be repeated.
+    #[arg(long = "repo", value_name = "NAME")]
+    pub repos: Vec<String>,
+
+    /// Pick from the repositories of this team, as `org/team`, instead of all of yours.
+    #[arg(long, value_name = "ORG/TEAM", conflicts_with = "repos")]
+    pub team: Option<String>,
+
+    /// Change the repositories carrying this topic, such as `to-privatize`, instead of picking
+    /// them; with `--team`, only the team's.
+    #[arg(long, value_name = "TOPIC", conflicts_with = "repos")]
+    pub topic: Option<String>,
+
+    /// Remove `--topic` from each repository once it has the visibility wanted, so tagged
+    /// repositories are only changed once.
+    #[arg(long, requires = "topic")]
+    pub remove_topic: bool,
+
+    /// Make the repositories private (`true`) or public (`false`), instead of prompting per repo.
+    #[arg(long, value_name = "BOOL")]
+    pub private: Option<bool>,
+
+    /// Apply the changes without asking for confirmation.
+    #[arg(long, short)]
+    pub yes: bool,
+
+    /// Maximum number of repositories updated at the same time.
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+
+    /// Apply more changes than this a chunk at a time, saving the changes left after each chunk
+    /// for `--resume`.
+    #[arg(long, value_name = "N", default_value_t = 50, value_parser = clap::value_parser!(u64).range(1..))]
+    pub chunk_size: u64,
+
+    /// Give up on a repository whose change makes no progress for this long, such as when a proxy
+    /// swallows a request, and carry on with the others; `0s` waits forever.
+    #[arg(long, value_name = "DURATION", default_value = "2m", value_parser = humantime::parse_duration)]
+    pub repo_timeout: Duration,
+
+    /// After applying, look up each change to an organization's repository in the organization's
+    /// audit log, recording the ids of the entries in the audit trail; needs GitHub Enterprise
+    /// Cloud and the `read:audit_log` scope.
+    #[arg(long)]
+    pub verify_audit_log: bool,
+
+    /// Apply the changes left by the last run that didn't finish its chunks, without prompting.
+    #[arg(long, conflicts_with_all = ["repos", "team", "topic", "private"])]
+    pub resume: bool,
+
+    /// Order in which repositories are listed for selection.
+    #[arg(long, value_enum, default_value_t = SortKey::Pushed)]
+    pub sort: SortKey,
+
+    /// Sort direction of `--sort`.
+    #[arg(long, value_enum, default_value_t = SortOrder::Desc)]
+    pub order: SortOrder,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// Lists the values the interactive flow would prompt for that were not given up front.
+    ///
+    /// Without a terminal nothing can be prompted for, so every one of them is required.
+    pub(crate) fn missing_inputs(&self, has_token: bool) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if !has_token {
+            missing.push("`PAT_TOKEN`");
+        }
+        if self.repos.is_empty() && self.topic.is_none() {
+            missing.push("--repo <NAME>");
+        }
+        if self.private.is_none() {
+            missing.push("--private <true|false>");
+        }
+        if !self.yes {
+            missing.push("--yes");
+        }
+
+        missing
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum SortKey {
+    /// By `owner/name`, the order GitHub lists them in.
+    Name,
+    Pushed,
+    Created,
+    Stars,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Command {
+    /// Set up privateer step by step: the GitHub host, the token and the visibility policy.
+    #[command(after_long_help = INIT_EXAMPLES)]
+    Init(InitArgs),
+    /// Check the token, its scopes, the network, the clock, the configuration and the terminal,
+    /// suggesting a fix for each problem.
+    #[command(after_long_help = DOCTOR_EXAMPLES)]
+    Doctor,
+    /// Show the API requests left to the token and when the quotas reset.
+    #[command(after_long_help = RATE_LIMIT_EXAMPLES)]
+    RateLimit,
+    /// Query the audit trail of repository changes made by privateer.
+    #[command(after_long_help = HISTORY_EXAMPLES)]
+    History(HistoryArgs),
+    /// Summarize the audit trail: changes per month, the share of private repositories over
+    /// time, and the most changed repositories.
+    #[command(after_long_help = STATS_EXAMPLES)]
+    Stats(StatsArgs),
+    /// Save the current state of your repositories as a baseline for `drift`.
+    #[command(after_long_help = SNAPSHOT_EXAMPLES)]
+    Snapshot(SnapshotArgs),
+    /// Compare the live state of your repositories against the saved snapshot.
+    #[command(after_long_help = DRIFT_EXAMPLES)]
+    Drift(SnapshotArgs),
+    /// List your repositories with their visibility and last push, or every column with
+    /// `--wide`.
+    #[command(after_long_help = LIST_EXAMPLES)]
+    List(ListArgs),
+    /// Open a repository's page in the default browser.
+    #[command(after_long_help = OPEN_EXAMPLES)]
+    Open {
+        /// Repository as `owner/name` or a GitHub URL, or `name` for one of your own repositories.
+        repo: String,
+    },
+    /// Browse, stage and apply visibility changes in a full-screen terminal UI.
+    #[command(after_long_help = TUI_EXAMPLES)]
+    Tui,
+    /// Poll for newly created repositories and give them the default visibility.
+    #[command(after_long_help = WATCH_EXAMPLES)]
+    Watch(WatchArgs),
+    /// Enforce the configured visibility policy on a schedule, unattended.
+    #[command(after_long_help = DAEMON_EXAMPLES)]
+    Daemon(DaemonArgs),
+    /// Check every repository against the configured policy once, changing the violations.
+    #[command(after_long_help = ENFORCE_EXAMPLES)]
+    Enforce(EnforceArgs),
+    /// Check that an enforcement run succeeded recently, for external monitoring.
+    #[command(after_long_help = HEALTH_EXAMPLES)]
+    Health(HealthArgs),
+    /// Receive GitHub `repository` webhooks and enforce the policy as soon as repos change.
+    #[command(after_long_help = SERVE_EXAMPLES)]
+    Serve(ServeArgs),
+    /// Archive repositories as mirror clones, or as `git bundle` files with `--bundle`, each
+    /// with a JSON file of its description, topics and settings.
+    #[command(after_long_help = BACKUP_EXAMPLES)]
+    Backup(BackupArgs),
+    /// Turn a public fork, which can't be made private, into a standalone private repository
+    /// with the same branches and tags.
+    #[command(after_long_help = DETACH_FORK_EXAMPLES)]
+    DetachFork(DetachForkArgs),
+    /// Push the configured branches of repositories to their mirrors, such as sanitized public
+    /// copies of private repositories.
+    #[command(after_long_help = MIRROR_EXAMPLES)]
+    Mirror(MirrorArgs),
+    /// Clone repositories into a directory, such as those about to be made private, skipping
+    /// any already there.
+    #[command(after_long_help = CLONE_EXAMPLES)]
+    Clone(CloneArgs),
+    /// List repositories by disk usage, largest first, with totals per visibility.
+    #[command(after_long_help = SIZES_EXAMPLES)]
+    Sizes(SizesArgs),
+    /// Lock repositories down in one go: private, no wiki or projects, no forks, secret scanning
+    /// and push protection on, and only local actions, or the preset of the configuration.
+    #[command(after_long_help = LOCKDOWN_EXAMPLES)]
+    Lockdown(LockdownArgs),
+    /// Make repositories public once they have a license and a description and no open secret
+    /// scanning alerts.
+    #[command(after_long_help = OPEN_SOURCE_EXAMPLES)]
+    OpenSource(OpenSourceArgs),
+    /// Apply the visibility, archived state and description of each repository in a CSV file,
+    /// such as one prepared in a spreadsheet, row by row.
+    #[command(after_long_help = BULK_EXAMPLES)]
+    Bulk(BulkArgs),
+    /// Write a man page for privateer and one for each of its commands, for packagers.
+    #[command(hide = true)]
+    Man {
+        /// Directory to write the pages to.
+        #[arg(long, default_value = "man")]
+        out: PathBuf,
+    },
+}
+
+const EXAMPLES: &str = "\
+Examples:
+  # Pick repositories from a list and choose the visibility of each
+  privateer
+
+  # Make two repositories private without prompting
+  privateer --repo gittidy --repo dotfiles --private true --yes
+
+  # Make a repository of an organization public
+  privateer --owner my-org --repo website --private false --yes
+
+  # Pick from the repositories of a team
+  privateer --team my-org/platform
+
+Run `privateer <COMMAND> --help` for the examples of each command.";
+
+const INIT_EXAMPLES: &str = "\
+Examples:
+  # Answer a few questions and write the configuration
+  privateer init";
+
+const DOCTOR_EXAMPLES: &str = "\
+Examples:
+  # Find out why privateer can't reach GitHub or change repositories
+  privateer doctor";
+
+const RATE_LIMIT_EXAMPLES: &str = "\
+Examples:
+  privateer rate-limit";
+
+const HISTORY_EXAMPLES: &str = "\
+Examples:
+  # Changes to one repository during May
+  privateer history --repo gittidy --since 2023-05-01 --until 2023-05-31";
+
+const STATS_EXAMPLES: &str = "\
+Examples:
+  privateer stats
+
+  # This year only, with the 20 most changed repositories
+  privateer stats --since 2024-01-01 --top 20
+
+  # For a dashboard
+  privateer stats --json";
+
+const SNAPSHOT_EXAMPLES: &str = "\
+Examples:
+  privateer snapshot
+  privateer snapshot --file baseline.json";
+
+const DRIFT_EXAMPLES: &str = "\
+Examples:
+  privateer drift
+  privateer drift --file baseline.json";
+
+const LIST_EXAMPLES: &str = "\
+Examples:
+  privateer list
+  privateer --sort stars list --wide
+
+  # For scripts: only some fields of each repository, as JSON
+  privateer list --fields name,visibility,pushed_at";
+
+const OPEN_EXAMPLES: &str = "\
+Examples:
+  privateer open gittidy
+  privateer open lloydlobo/dotfiles";
+
+const TUI_EXAMPLES: &str = "\
+Examples:
+  privateer --sort stars tui";
+
+const WATCH_EXAMPLES: &str = "\
+Examples:
+  # Make new repositories private within 10 minutes, except dotfiles
+  privateer watch --allow dotfiles
+
+  # Check every hour and serve Prometheus metrics
+  privateer watch --interval 1h --metrics-addr 127.0.0.1:9465";
+
+const DAEMON_EXAMPLES: &str = "\
+Examples:
+  privateer daemon
+  privateer daemon --config /etc/privateer.toml --health-addr 127.0.0.1:9464 --syslog";
+
+const ENFORCE_EXAMPLES: &str = "\
+Examples:
+  # Report violations only, exiting with status 3 if there are any
+  privateer enforce --check-only
+
+  # Fix them
+  privateer enforce --report report.json";
+
+const HEALTH_EXAMPLES: &str = "\
+Examples:
+  privateer health --max-age 2h";
+
+const SERVE_EXAMPLES: &str = "\
+Examples:
+  PRIVATEER_WEBHOOK_SECRET=... privateer serve --port 8080";
+
+const BACKUP_EXAMPLES: &str = "\
+Examples:
+  privateer backup --bundle --out ~/archives gittidy lloydlobo/dotfiles
+  privateer backup --all --out ~/archives";
+
+const DETACH_FORK_EXAMPLES: &str = "\
+Examples:
+  # Copy the fork to linux-private, then archive the fork
+  privateer detach-fork linux --then archive
+
+  # Without prompting, under another name
+  privateer detach-fork lloydlobo/linux --name linux-notes --yes";
+
+const MIRROR_EXAMPLES: &str = "\
+Examples:
+  # Push the branches listed in `[[mirrors]]` of the configuration
+  privateer mirror
+
+  # Only the mirror of notes, every hour
+  privateer mirror notes --every 1h";
+
+const CLONE_EXAMPLES: &str = "\
+Examples:
+  # Pick repositories from a list
+  privateer clone --dest ~/src
+
+  # Everything you own, eight at a time
+  privateer clone --all --dest ~/src --concurrency 8";
+
+const SIZES_EXAMPLES: &str = "\
+Examples:
+  privateer sizes --top 20";
+
+const LOCKDOWN_EXAMPLES: &str = "\
+Examples:
+  # See what would change, then confirm
+  privateer lockdown gittidy dotfiles
+
+  # Everything you own, without asking
+  privateer lockdown --all --yes
+
+  # Keep the wiki: set `has_wiki = true` under `[lockdown]` in the configuration
+  privateer lockdown notes";
+
+const BULK_EXAMPLES: &str = "\
+Examples:
+  # Rows of `owner/name,visibility,archived,description`; empty cells are left as they are
+  privateer bulk changes.csv
+
+  # Check the file and show what would change, without changing anything
+  privateer bulk --dry-run changes.csv
+
+  # Without asking, such as in CI
+  privateer bulk --yes changes.csv";
+
+const OPEN_SOURCE_EXAMPLES: &str = "\
+Examples:
+  # Check two repositories, then make those that pass public
+  privateer open-source gittidy dotfiles
+
+  # A repository without a description yet
+  privateer open-source notes --skip-check description
+
+  # Also search the last 500 commits for secrets
+  privateer open-source notes --scan-history=500";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Visibility {
+    Private,
+    Public,
+}
+
+impl Visibility {
+    pub(crate) fn is_private(self) -> bool {
+        self == Visibility::Private
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Visibility::Private => "private",
+            Visibility::Public => "public",
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct WatchArgs {
+    /// Time between checks, such as `30s`, `10m` or `1h`.
+    #[arg(long, default_value = "10m", value_parser = humantime::parse_duration)]
+    pub interval: Duration,
+
+    /// Visibility new repositories are changed to if they don't have it.
+    #[arg(long, value_enum, default_value_t = Visibility::Private)]
+    pub default_visibility: Visibility,
+
+    /// Repository to leave alone even if it is new; may be repeated.
+    #[arg(long, value_name = "NAME")]
+    pub allow: Vec<String>,
+
+    /// Address to serve Prometheus metrics on, as `GET /metrics`.
+    #[arg(long, value_name = "ADDR")]
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// Most API requests to make per minute, spread out evenly [default: unlimited].
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub requests_per_minute: Option<u32>,
+}
+
+impl WatchArgs {
+    pub(crate) fn policy(&self) -> Policy {
+        Policy {
+            default_visibility: self.default_visibility,
+            allow: self.allow.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct DaemonArgs {
+    /// Path of the configuration file [default: `$XDG_CONFIG_HOME/privateer/config.toml`].
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Address to serve `GET /health` on, overriding `daemon.health_addr` in the configuration.
+    #[arg(long, value_name = "ADDR")]
+    pub health_addr: Option<SocketAddr>,
+
+    /// Log to the local syslog daemon instead of standard error.
+    #[arg(long)]
+    pub syslog: bool,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct EnforceArgs {
+    /// Only report violations, exiting with status 3 if there are any.
+    #[arg(long)]
+    pub check_only: bool,
+
+    /// Path to write the JSON report to.
+    #[arg(long, default_value = "privateer-report.json")]
+    pub report: PathBuf,
+
+    /// Path of the configuration file [default: `$XDG_CONFIG_HOME/privateer/config.toml`].
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct BackupArgs {
+    /// Repositories to archive as `name`, `owner/name` or a GitHub URL.
+    #[arg(
+        value_name = "REPO",
+        required_unless_present = "all",
+        conflicts_with = "all"
+    )]
+    pub repos: Vec<String>,
+
+    /// Archive every repository the token's user owns.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Write a single `git bundle` file per repository instead of a mirror clone.
+    #[arg(long)]
+    pub bundle: bool,
+
+    /// Directory to write the archives to, one subdirectory per owner.
+    #[arg(long, default_value = ".")]
+    pub out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct DetachForkArgs {
+    /// The fork, as `name`, `owner/name` or a GitHub URL.
+    pub repo: String,
+
+    /// Name of the new private repository [default: the fork's name with `-private` appended].
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// What to do with the fork once its contents are in the new repository.
+    #[arg(long, value_enum, default_value_t = AfterDetach::Keep)]
+    pub then: AfterDetach,
+
+    /// Go ahead without asking for confirmation.
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct MirrorArgs {
+    /// Only sync the mirrors of these sources, as named in the configuration [default: all].
+    #[arg(value_name = "SOURCE")]
+    pub sources: Vec<String>,
+
+    /// Sync again after each interval, such as `30m` or `1h`, until interrupted.
+    #[arg(long, value_name = "INTERVAL", value_parser = humantime::parse_duration)]
+    pub every: Option<Duration>,
+
+    /// Path of the configuration file [default: `$XDG_CONFIG_HOME/privateer/config.toml`].
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct CloneArgs {
+    /// Repositories to clone as `name`, `owner/name` or a GitHub URL [default: pick them from
+    /// a list].
+    #[arg(value_name = "REPO", conflicts_with = "all")]
+    pub repos: Vec<String>,
+
+    /// Clone every repository the token's user owns.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Directory to clone into, one subdirectory per owner.
+    #[arg(long, default_value = ".")]
+    pub dest: PathBuf,
+
+    /// Maximum number of repositories cloned at the same time.
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct InitArgs {
+    /// Path of the configuration file to write [default: `$XDG_CONFIG_HOME/privateer/config.toml`].
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Client ID of an OAuth app with the device flow enabled, to sign in with the browser
+    /// instead of pasting a personal access token.
+    #[arg(long, value_name = "ID", env = "PRIVATEER_CLIENT_ID")]
+    pub client_id: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct SizesArgs {
+    /// Only list this many of the largest repositories; the totals still count all of them.
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct LockdownArgs {
+    /// Repositories to lock down as `name`, `owner/name` or a GitHub URL [default: pick them
+    /// from a list].
+    #[arg(value_name = "REPO", conflicts_with = "all")]
+    pub repos: Vec<String>,
+
+    /// Lock down every repository the token's user owns.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Go ahead without asking for confirmation.
+    #[arg(long, short)]
+    pub yes: bool,
+
+    /// Path of the configuration file [default: `$XDG_CONFIG_HOME/privateer/config.toml`].
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct BulkArgs {
+    /// CSV file with a row of `owner/name,visibility,archived,description` per repository.
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Validate the file and show what would change, without changing anything.
+    #[arg(long, conflicts_with = "yes")]
+    pub dry_run: bool,
+
+    /// Go ahead without asking for confirmation.
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct OpenSourceArgs {
+    /// Repositories to make public as `name`, `owner/name` or a GitHub URL [default: pick them
+    /// from a list].
+    #[arg(value_name = "REPO", conflicts_with = "all")]
+    pub repos: Vec<String>,
+
+    /// Make every private repository the token's user owns public.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Prerequisite not to check; may be repeated.
+    #[arg(long, value_enum, value_name = "CHECK")]
+    pub skip_check: Vec<Prerequisite>,
+
+    /// Go ahead without asking for confirmation.
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+/// What a repository needs before `open-source` makes it public.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Prerequisite {
+    /// A license GitHub recognizes.
+    License,
+    /// A description.
+    Description,
+    /// Secret scanning turned on. Open alerts block making a repository public regardless,
+    /// unless `--allow-secret-alerts` is passed.
+    #[value(alias = "secret-alerts")]
+    SecretScanning,
+}
+
+/// What `detach-fork` does with the fork afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum AfterDetach {
+    /// Leave it as it is.
+    Keep,
+    /// Archive it, making it read-only.
+    Archive,
+    /// Delete it; needs a token with the `delete_repo` scope.
+    Delete,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct HealthArgs {
+    /// Fail if the last successful run is older than this, such as `30m` or `2h`.
+    #[arg(long, default_value = "2h", value_parser = humantime::parse_duration)]
+    pub max_age: Duration,
+
+    /// Path of the heartbeat file [default: `$XDG_STATE_HOME/privateer/heartbeat.json`].
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ServeArgs {
+    /// Port to listen on, on all interfaces.
+    #[arg(long, default_value_t = 8080)]
+    pub port: u16,
+
+    /// Secret the webhook was configured with, used to verify each delivery's signature.
+    #[arg(long, env = "PRIVATEER_WEBHOOK_SECRET", hide_env_values = true)]
+    pub webhook_secret: String,
+
+    /// Path of the configuration file [default: `$XDG_CONFIG_HOME/privateer/config.toml`].
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ListArgs {
+    /// Also show the web page, stars, forks, language and size of each repository.
+    #[arg(long)]
+    pub wide: bool,
+
+    /// Print the repositories as a JSON array instead of a table.
+    #[arg(long, conflicts_with = "wide")]
+    pub json: bool,
+
+    /// Only these fields of each repository, separated by commas, such as
+    /// `name,visibility,pushed_at`; implies `--json`.
+    #[arg(
+        long,
+        value_name = "FIELDS",
+        value_delimiter = ',',
+        conflicts_with = "wide"
+    )]
+    pub fields: Vec<String>,
+
+    /// Print the values at this path of the JSON output, one per line, such as `.[].name`;
+    /// implies `--json`.
+    #[arg(long, value_name = "PATH", conflicts_with = "wide")]
+    pub jq: Option<Path>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct SnapshotArgs {
+    /// Path of the snapshot file [default: `$XDG_STATE_HOME/privateer/snapshot.json`].
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct HistoryArgs {
+    /// Only show records for this repository (`name` or `owner/name`).
+    #[arg(long)]
+    pub repo: Option<String>,
+
+    /// Only show records made on or after this date (`YYYY-MM-DD` or RFC 3339).
+    #[arg(long, value_parser = parse_since)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only show records made on or before this date (`YYYY-MM-DD` or RFC 3339).
+    #[arg(long, value_parser = parse_until)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct StatsArgs {
+    /// Only count changes made on or after this date (`YYYY-MM-DD` or RFC 3339).
+    #[arg(long, value_parser = parse_since)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only count changes made on or before this date (`YYYY-MM-DD` or RFC 3339).
+    #[arg(long, value_parser = parse_until)]
+    pub until: Option<DateTime<Utc>>,
+
+    /// How many of the most changed repositories to show.
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub top: usize,
+
+    /// Print the statistics as JSON instead of tables.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The REST API version to request, from `--api-version`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ApiVersion {
+    /// No `X-GitHub-Api-Version` header.
+    None,
+    Date(String),
+}
+
+impl ApiVersion {
+    /// The value of `X-GitHub-Api-Version`, if any.
+    pub(crate) fn header(&self) -> Option<String> {
+        match self {
+            ApiVersion::None => None,
+            ApiVersion::Date(date) => Some(date.clone()),
+        }
+    }
+}
+
+/// Parses an API version: a date such as `2022-11-28`, or `none`.
+pub(crate) fn parse_api_version(value: &str) -> Result<ApiVersion, String> {
+    if value.eq_ignore_ascii_case("none") {
+        return Ok(ApiVersion::None);
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|_| ApiVersion::Date(value.to_owned()))
+        .map_err(|_| format!("invalid API version `{value}`, expected `YYYY-MM-DD` or `none`"))
+}
+
+/// Parses a lower date bound; a bare date means the start of that day (UTC).
+pub(crate) fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_date_bound(value, false)
+}
+
+/// Parses an upper date bound; a bare date means the end of that day (UTC).
+pub(crate) fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_date_bound(value, true)
+}
+
+fn parse_date_bound(value: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date `{value}`, expected `YYYY-MM-DD` or RFC 3339"))?;
+    let time = match end_of_day {
+        true => date.and_hms_milli_opt(23, 59, 59, 999),
+        false => date.and_hms_opt(0, 0, 0),
+    };
+
+    Ok(time.expect("valid time of day").and_utc())
+}
@@ -0,0 +1,253 @@
+//! The library API, for tools that embed privateer instead of running the command.
+
+use crate::cli::{SortKey, SortOrder};
+use crate::github::{self, Collaborator, Gist, Organization, Repository, UpdateRepository};
+use crate::transport::{self, HttpTransport, Settings};
+use crate::urls::Host;
+use crate::{Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// A GitHub client authenticated with a personal access token.
+///
+/// `GH_HOST` selects a GitHub Enterprise Server, like it does for the command, unless the client
+/// is made with [`Client::builder`] and a base URL.
+///
+/// ```no_run
+/// # async fn example() -> privateer::Result<()> {
+/// let client = privateer::Client::new(std::env::var("PAT_TOKEN")?);
+/// for repo in client.list_repos().await? {
+///     if repo.private == Some(false) {
+///         client.set_visibility(&repo.owner.login, &repo.name, true).await?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Client {
+    token: String,
+    settings: Arc<Settings>,
+}
+
+/// The [`Client`], under the name of the crate.
+pub type PrivateerClient = Client;
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client").finish_non_exhaustive()
+    }
+}
+
+impl Client {
+    /// A client that authenticates with `token`, which needs the `repo` scope to change
+    /// visibility.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            settings: Arc::new(Settings::default()),
+        }
+    }
+
+    /// Configures a client beyond its token.
+    ///
+    /// ```no_run
+    /// # fn example() -> privateer::Result<()> {
+    /// let client = privateer::PrivateerClient::builder()
+    ///     .token(std::env::var("PAT_TOKEN")?)
+    ///     .base_url("https://github.example.com/api/v3/")
+    ///     .user_agent("compliance-bot")
+    ///     .timeout(std::time::Duration::from_secs(30))
+    ///     .retries(3)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Sends the requests of this client through `transport`, such as a
+    /// [`FakeTransport`](crate::FakeTransport) in tests.
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        Arc::make_mut(&mut self.settings).transport = transport;
+        self
+    }
+
+    async fn scoped<F: Future>(&self, future: F) -> F::Output {
+        transport::with_settings(Arc::clone(&self.settings), future).await
+    }
+
+    /// Login of the user the token belongs to.
+    pub async fn authenticated_user(&self) -> Result<String> {
+        self.scoped(github::get_authenticated_user(&self.token))
+            .await
+    }
+
+    /// Every repository the token's user can access, by name.
+    pub async fn list_repos(&self) -> Result<Vec<Repository>> {
+        self.scoped(github::get_repos_request(
+            "",
+            &self.token,
+            SortKey::Name,
+            SortOrder::Asc,
+        ))
+        .await
+    }
+
+    /// Every organization the token's user belongs to.
+    pub async fn list_orgs(&self) -> Result<Vec<Organization>> {
+        self.scoped(github::list_orgs(&self.token)).await
+    }
+
+    /// Every user with access to the repository `owner/name`.
+    pub async fn list_collaborators(&self, owner: &str, name: &str) -> Result<Vec<Collaborator>> {
+        self.scoped(github::list_collaborators(owner, name, &self.token))
+            .await
+    }
+
+    /// Every gist of the token's user, secret ones included.
+    pub async fn list_gists(&self) -> Result<Vec<Gist>> {
+        self.scoped(github::list_gists(&self.token)).await
+    }
+
+    /// The repository `owner/name`.
+    pub async fn get_repo(&self, owner: &str, name: &str) -> Result<Repository> {
+        self.scoped(github::get_repo(owner, name, &self.token))
+            .await
+    }
+
+    /// Makes the repository `owner/name` private or public, and checks GitHub reports the change.
+    pub async fn set_visibility(&self, owner: &str, name: &str, private: bool) -> Result<()> {
+        let update = UpdateRepository {
+            private: Some(private),
+            ..UpdateRepository::default()
+        };
+        self.scoped(github::update_repo(owner, name, &update, &self.token))
+            .await?;
+        let live = self.get_repo(owner, name).await?;
+        match live.private == Some(private) {
+            true => Ok(()),
+            false => Err(anyhow!(
+                "GitHub still reports `private: {:?}` for `{owner}/{name}`",
+                live.private
+            )),
+        }
+    }
+}
+
+/// Builds a [`Client`], see [`Client::builder`].
+#[derive(Default)]
+pub struct ClientBuilder {
+    token: Option<String>,
+    base_url: Option<String>,
+    user_agent: Option<String>,
+    api_version: Option<Option<String>>,
+    timeout: Option<Duration>,
+    retries: Option<u32>,
+    transport: Option<Arc<dyn HttpTransport>>,
+}
+
+impl std::fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("base_url", &self.base_url)
+            .field("user_agent", &self.user_agent)
+            .field("api_version", &self.api_version)
+            .field("timeout", &self.timeout)
+            .field("retries", &self.retries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ClientBuilder {
+    /// The personal access token to authenticate with. Required.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Root of the REST API, such as `https://github.example.com/api/v3/` for a GitHub
+    /// Enterprise Server. Defaults to the host selected by `GH_HOST`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// The `User-Agent` sent with every request, `privateer/<version> (+<repository URL>)` by
+    /// default.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// The REST API version sent as `X-GitHub-Api-Version` with every request, `2022-11-28` by
+    /// default; `None` sends none, for a GitHub Enterprise Server that predates API versions.
+    pub fn api_version(mut self, version: Option<&str>) -> Self {
+        self.api_version = Some(version.map(str::to_owned));
+        self
+    }
+
+    /// Longest wait for each response. Unlimited by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// How many times a request rejected for the rate limit is sent again, once by default.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    /// Sends the requests through `transport` instead of the network.
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// The configured client.
+    ///
+    /// # Errors
+    ///
+    /// If no token was given, or the base URL, user agent or API version is invalid.
+    pub fn build(self) -> Result<Client> {
+        let token = self
+            .token
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| anyhow!("{ERROR_ICON} A token is required to build a client"))?;
+        let mut settings = Settings::default();
+        if let Some(base_url) = self.base_url {
+            let api = Url::parse(&base_url)
+                .ok()
+                .filter(|url| matches!(url.scheme(), "http" | "https"))
+                .with_context(|| format!("{ERROR_ICON} Invalid base URL `{base_url}`"))?;
+            settings.host = Host::from_api(api);
+        }
+        if let Some(user_agent) = self.user_agent {
+            reqwest::header::HeaderValue::from_str(&user_agent)
+                .with_context(|| format!("{ERROR_ICON} Invalid user agent `{user_agent}`"))?;
+            settings.user_agent = user_agent;
+        }
+        if let Some(version) = self.api_version {
+            if let Some(version) = &version {
+                reqwest::header::HeaderValue::from_str(version)
+                    .with_context(|| format!("{ERROR_ICON} Invalid API version `{version}`"))?;
+            }
+            settings.api_version = version;
+        }
+        settings.timeout = self.timeout.or(settings.timeout);
+        settings.retries = self.retries.unwrap_or(settings.retries);
+        if let Some(transport) = self.transport {
+            settings.transport = transport;
+        }
+
+        Ok(Client {
+            token,
+            settings: Arc::new(settings),
+        })
+    }
+}
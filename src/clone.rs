@@ -0,0 +1,181 @@
+//! `privateer clone`: local copies of repositories, such as those about to be made private.
+//!
+//! Each repository is cloned to `<dest>/<owner>/<name>` with `git clone`, several at a time. A
+//! repository whose directory already exists is skipped rather than fetched or overwritten, so
+//! running the command again only clones what is missing.
+
+use crate::cli::{CloneArgs, SortKey, SortOrder};
+use crate::events::{self, Event};
+use crate::i18n::t;
+use crate::output::status;
+use crate::{backup, cancel, github, prompt_dialoguer, prompter, validate, Result};
+use crate::{ERROR_ICON, SUCCESS_ICON};
+use anyhow::anyhow;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// A repository to clone: its `owner/name` and the URL to clone it from.
+pub(crate) type Job = (String, String);
+
+/// What happened to a batch of clones.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Cloned {
+    pub cloned: usize,
+    /// Repositories whose directory already existed.
+    pub skipped: usize,
+    /// Repositories not cloned because the batch was interrupted.
+    pub not_started: usize,
+    /// `owner/name` of each repository that failed.
+    pub failures: Vec<String>,
+}
+
+/// Clones the repositories of `args`, or those picked from a list of all of them.
+pub(crate) async fn run(pat_token: &str, args: &CloneArgs) -> Result<()> {
+    let username = github::get_authenticated_user(pat_token).await?;
+    let repos: Vec<(String, String)> = match (args.all, args.repos.is_empty()) {
+        (true, _) => github::get_repos_request(&username, pat_token, SortKey::Name, SortOrder::Asc)
+            .await?
+            .into_iter()
+            .filter(|repo| repo.owner.login.eq_ignore_ascii_case(&username))
+            .map(|repo| (repo.owner.login, repo.name))
+            .collect(),
+        (false, false) => args
+            .repos
+            .iter()
+            .map(|input| {
+                let repo = validate::repo(input)?;
+                Ok((repo.owner.unwrap_or_else(|| username.clone()), repo.name))
+            })
+            .collect::<Result<_>>()?,
+        (false, true) => {
+            if !prompter::is_interactive() {
+                return Err(anyhow!(
+                    "{ERROR_ICON} No repositories to clone. Hint! Name them, or pass `--all`"
+                ));
+            }
+            let repos =
+                github::get_selectable_repos(pat_token, SortKey::Pushed, SortOrder::Desc).await?;
+            let selected = prompt_dialoguer::run_dialoguer(username.clone(), repos.clone(), None)?;
+            selected
+                .into_iter()
+                .map(|index| (repos[index].owner.login.clone(), repos[index].name.clone()))
+                .collect()
+        }
+    };
+    let jobs = repos
+        .into_iter()
+        .map(|(owner, name)| {
+            let url = backup::clone_url(&owner, &name);
+            (format!("{owner}/{name}"), url)
+        })
+        .collect();
+
+    let cloned = cancel::on_ctrl_c(clone_all(jobs, &args.dest, pat_token, args.concurrency)).await;
+    status!(
+        "{SUCCESS_ICON} {}",
+        t!(
+            "clone-done",
+            cloned = cloned.cloned,
+            dest = format!("{:?}", args.dest),
+            skipped = cloned.skipped
+        )
+    );
+    if cloned.not_started > 0 {
+        status!("{}", t!("clone-interrupted", count = cloned.not_started));
+    }
+    match cloned.failures.is_empty() {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "{ERROR_ICON} Failed to clone `{count}` repos: {names}",
+            count = cloned.failures.len(),
+            names = cloned.failures.join(", ")
+        )),
+    }
+}
+
+/// Clones every job into `dest/<owner>/<name>` with at most `concurrency` clones at a time,
+/// skipping those whose directory exists.
+pub(crate) async fn clone_all(
+    jobs: Vec<Job>,
+    dest: &Path,
+    pat_token: &str,
+    concurrency: usize,
+) -> Cloned {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let token = cancel::current();
+    let mut tasks = JoinSet::new();
+    for (repo, url) in jobs {
+        let path = dest.join(&repo);
+        let semaphore = Arc::clone(&semaphore);
+        let (token, frontend, pat_token) =
+            (token.clone(), events::frontend(), pat_token.to_owned());
+        tasks.spawn(events::with_frontend(frontend, async move {
+            let permit = tokio::select! {
+                permit = semaphore.acquire_owned() => permit.ok(),
+                _ = token.cancelled() => None,
+            };
+            let Some(_permit) = permit else {
+                return (repo, Outcome::NotStarted);
+            };
+            let outcome = clone_one(&repo, url, path, pat_token).await;
+            (repo, outcome)
+        }));
+    }
+
+    let mut cloned = Cloned::default();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((_, Outcome::Cloned)) => cloned.cloned += 1,
+            Ok((_, Outcome::Skipped)) => cloned.skipped += 1,
+            Ok((_, Outcome::NotStarted)) => cloned.not_started += 1,
+            Ok((repo, Outcome::Failed)) => cloned.failures.push(repo),
+            Err(err) => cloned.failures.push(err.to_string()),
+        }
+    }
+    cloned.failures.sort();
+
+    cloned
+}
+
+enum Outcome {
+    Cloned,
+    Skipped,
+    NotStarted,
+    Failed,
+}
+
+async fn clone_one(repo: &str, url: String, path: PathBuf, pat_token: String) -> Outcome {
+    if path.exists() {
+        events::emit(Event::CloneFinished {
+            repo: repo.to_owned(),
+            skipped: true,
+        });
+        return Outcome::Skipped;
+    }
+    events::emit(Event::CloneStarted {
+        repo: repo.to_owned(),
+    });
+    let cloned =
+        tokio::task::spawn_blocking(move || backup::working_clone(&url, &path, &pat_token)).await;
+    match cloned
+        .map_err(anyhow::Error::from)
+        .and_then(|cloned| cloned)
+    {
+        Ok(()) => {
+            events::emit(Event::CloneFinished {
+                repo: repo.to_owned(),
+                skipped: false,
+            });
+            Outcome::Cloned
+        }
+        Err(err) => {
+            events::emit(Event::CloneFailed {
+                repo: repo.to_owned(),
+                error: err.to_string(),
+            });
+            Outcome::Failed
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! The configuration file read by every command, mostly by `privateer daemon`, `enforce`,
+//! `mirror` and `lockdown`.
+//!
+//! ```toml
+//! [policy]
+//! default_visibility = "private"
+//! allow = ["dotfiles", "lloydlobo.github.io"]
+//!
+//! [daemon]
+//! interval = "1h"
+//! health_addr = "127.0.0.1:9464"
+//! requests_per_minute = 60
+//!
+//! [[notifications.webhooks]]
+//! kind = "slack"
+//! url = "https://hooks.slack.com/services/T000/B000/XXXX"
+//!
+//! [environment]
+//! dotenv = false
+//!
+//! [filters]
+//! owner = "acme"
+//! deny = ["acme/archive-*"]
+//!
+//! [lockdown]
+//! has_wiki = true
+//! actions = "disabled"
+//!
+//! [[mirrors]]
+//! source = "lloydlobo/notes"
+//! mirror = "lloydlobo/notes-public"
+//! branches = ["main"]
+//! ```
+
+use crate::github::ActionsPermissions;
+use crate::lists;
+use crate::notify::Notifications;
+use crate::policy::Policy;
+use crate::{Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Deserializer};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    pub policy: Policy,
+    pub daemon: DaemonConfig,
+    pub notifications: Notifications,
+    pub environment: EnvironmentConfig,
+    pub filters: Filters,
+    /// The preset of `privateer lockdown`.
+    pub lockdown: Lockdown,
+    /// Public copies kept in sync by `privateer mirror`.
+    pub mirrors: Vec<MirrorConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct DaemonConfig {
+    /// Time between enforcement runs, such as `30m` or `1h`.
+    #[serde(deserialize_with = "duration")]
+    pub interval: Duration,
+    /// Address to serve `GET /health` on, if any.
+    pub health_addr: Option<SocketAddr>,
+    /// Most API requests to make per minute, spread out evenly; unlimited if unset.
+    pub requests_per_minute: Option<u32>,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60 * 60),
+            health_addr: None,
+            requests_per_minute: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct EnvironmentConfig {
+    /// Whether `.env` in the current directory is loaded; `--env-file` is loaded regardless.
+    pub dotenv: bool,
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> Self {
+        Self { dotenv: true }
+    }
+}
+
+/// Filters of the repositories listed, used unless the command line sets its own.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Filters {
+    /// Owner of the repositories named without one, as `--owner`.
+    pub owner: Option<String>,
+    /// `owner/name` globs of repositories no command touches or lists, added to `--denylist`.
+    pub deny: Vec<String>,
+}
+
+/// The settings `privateer lockdown` gives repositories.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Lockdown {
+    pub private: bool,
+    pub has_wiki: bool,
+    pub has_projects: bool,
+    /// Whether forks may be made; only set on the repositories of organizations, the only ones
+    /// where it can be turned off.
+    pub allow_forking: bool,
+    pub secret_scanning: bool,
+    pub push_protection: bool,
+    /// Which GitHub Actions may run.
+    pub actions: ActionsPreset,
+}
+
+impl Default for Lockdown {
+    fn default() -> Self {
+        Self {
+            private: true,
+            has_wiki: false,
+            has_projects: false,
+            allow_forking: false,
+            secret_scanning: true,
+            push_protection: true,
+            actions: ActionsPreset::LocalOnly,
+        }
+    }
+}
+
+/// Which GitHub Actions may run in a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ActionsPreset {
+    All,
+    /// Only the actions and reusable workflows of the repository's owner.
+    LocalOnly,
+    /// Those allowed in the repository's settings on GitHub.
+    Selected,
+    Disabled,
+}
+
+impl ActionsPreset {
+    pub(crate) fn permissions(self) -> ActionsPermissions {
+        let allowed = |allowed: &str| ActionsPermissions {
+            enabled: true,
+            allowed_actions: Some(allowed.to_owned()),
+        };
+        match self {
+            ActionsPreset::All => allowed("all"),
+            ActionsPreset::LocalOnly => allowed("local_only"),
+            ActionsPreset::Selected => allowed("selected"),
+            ActionsPreset::Disabled => ActionsPermissions {
+                enabled: false,
+                allowed_actions: None,
+            },
+        }
+    }
+}
+
+/// A repository pushed to another, such as a sanitized public copy of a private one.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct MirrorConfig {
+    /// The repository pushed from, as `owner/name` or `name`.
+    pub source: String,
+    /// The repository pushed to, as `owner/name` or `name`.
+    pub mirror: String,
+    /// The branches pushed; the others, and every tag, stay in the source.
+    pub branches: Vec<String>,
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Duration, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    humantime::parse_duration(&text).map_err(serde::de::Error::custom)
+}
+
+/// Parses the configuration from `text`.
+pub(crate) fn parse(text: &str) -> Result<Config> {
+    let mut config: Config = toml::from_str(text)?;
+    if config.daemon.interval.is_zero() {
+        return Err(anyhow!("`daemon.interval` must be longer than zero"));
+    }
+    if config.daemon.requests_per_minute == Some(0) {
+        return Err(anyhow!("`daemon.requests_per_minute` must be at least 1"));
+    }
+    config.filters.deny = lists::parse(&config.filters.deny.join("\n")).map_err(|(_, glob)| {
+        anyhow!("`filters.deny`: `{glob}` is not an `owner/name` glob, such as `acme/*`")
+    })?;
+    for mirror in &config.mirrors {
+        if mirror.branches.is_empty() {
+            return Err(anyhow!(
+                "`mirrors.branches` of `{}` must list at least one branch",
+                mirror.source
+            ));
+        }
+        if mirror.source == mirror.mirror {
+            return Err(anyhow!("`{}` can't be its own mirror", mirror.source));
+        }
+    }
+
+    Ok(config)
+}
+
+/// Reads the configuration from `path`, which must exist.
+pub(crate) fn load(path: &Path) -> Result<Config> {
+    let text = std::fs::read_to_string(path).with_context(|| {
+        format!("{ERROR_ICON} Failed to read the configuration {path:?}. Hint! Create it with a `[policy]` table, see the README")
+    })?;
+
+    parse(&text).with_context(|| format!("{ERROR_ICON} Malformed configuration {path:?}"))
+}
+
+/// Reads the configuration from `path`, or the defaults if it doesn't exist.
+pub(crate) fn load_if_exists(path: &Path) -> Result<Config> {
+    match path.exists() {
+        true => load(path),
+        false => Ok(Config::default()),
+    }
+}
@@ -0,0 +1,120 @@
+//! What to do with a repository whose visibility changed between planning and applying.
+//!
+//! A plan shows each change against the visibility the repository was listed with. If GitHub
+//! reports another one by the time the change is applied, someone changed the repository in the
+//! meantime, and the reviewed change may no longer be what is wanted. `--on-conflict` decides
+//! what happens then; without it, the interactive flow pauses to ask, and every other command
+//! plans the repository again from its live state.
+
+use crate::events;
+use crate::i18n::t;
+use crate::{prompter, Result};
+use clap::ValueEnum;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
+
+/// How to resolve a conflict between a plan and the live state of a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OnConflict {
+    /// Leave the repository as it is.
+    Skip,
+    /// Send the planned change anyway.
+    Apply,
+    /// Plan the repository again from its live state, changing it only if that still needs it.
+    Replan,
+}
+
+/// `--on-conflict`, and whether to ask when it wasn't given.
+static POLICY: RwLock<(Option<OnConflict>, bool)> = RwLock::new((None, false));
+
+/// Only one conflict is asked about at a time, however many changes are in flight.
+static ASKING: Mutex<()> = Mutex::new(());
+
+/// Conflicts being asked about or waiting for their turn.
+static WAITING: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns `true` while a conflict is being asked about, so no change counts as stuck meanwhile.
+pub(crate) fn is_asking() -> bool {
+    WAITING.load(Ordering::SeqCst) > 0
+}
+
+struct Waiting;
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        WAITING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Resolves every conflict with `on_conflict` if given, otherwise by asking if `ask`, or else by
+/// planning again.
+pub(crate) fn configure(on_conflict: Option<OnConflict>, ask: bool) {
+    if let Ok(mut policy) = POLICY.write() {
+        *policy = (on_conflict, ask);
+    }
+}
+
+/// Decides what to do with `repo`, planned to become private if `wanted` from the `planned`
+/// visibility, which GitHub now reports as `live`.
+pub(crate) async fn resolve(
+    repo: &str,
+    planned: Option<bool>,
+    live: Option<bool>,
+    wanted: bool,
+) -> Result<OnConflict> {
+    let (on_conflict, ask) = POLICY.read().map(|policy| *policy).unwrap_or((None, false));
+    match on_conflict {
+        Some(on_conflict) => return Ok(on_conflict),
+        None if !ask => return Ok(OnConflict::Replan),
+        None => {}
+    }
+
+    let message = t!(
+        "conflict",
+        repo = repo.to_owned(),
+        planned = visibility(planned),
+        wanted = visibility(Some(wanted)),
+        live = visibility(live)
+    );
+    // Progress bars are paused, so they don't draw over the question.
+    let frontend = events::frontend();
+    WAITING.fetch_add(1, Ordering::SeqCst);
+    let _waiting = Waiting;
+    tokio::task::spawn_blocking(move || {
+        let _asking = ASKING.lock().unwrap_or_else(|err| err.into_inner());
+        let mut answer = None;
+        frontend.suspend(&mut || {
+            println!("{message}");
+            answer = Some(ask_resolution());
+        });
+        answer.expect("the frontend ran the prompt")
+    })
+    .await?
+}
+
+fn ask_resolution() -> Result<OnConflict> {
+    loop {
+        match parse(&prompter::prompt_user_input(&t!("conflict-prompt"))?) {
+            Some(on_conflict) => return Ok(on_conflict),
+            None => println!("{}", t!("conflict-invalid")),
+        }
+    }
+}
+
+/// Interprets an answer to the conflict prompt: `s`, `a` or `r`, or the whole word.
+pub(crate) fn parse(input: &str) -> Option<OnConflict> {
+    match input.trim().to_lowercase().as_str() {
+        "s" | "skip" => Some(OnConflict::Skip),
+        "a" | "apply" => Some(OnConflict::Apply),
+        "r" | "replan" | "refresh" => Some(OnConflict::Replan),
+        _ => None,
+    }
+}
+
+fn visibility(private: Option<bool>) -> &'static str {
+    match private {
+        Some(true) => "private",
+        Some(false) => "public",
+        None => "unknown",
+    }
+}
@@ -0,0 +1,97 @@
+//! The token saved by `privateer init`, used when `PAT_TOKEN` isn't set.
+//!
+//! On macOS and Windows it is kept in the keychain of the OS, under the service `privateer` and
+//! the host name, such as `github.com`. Elsewhere, where a keychain may not be running, it is
+//! kept in `tokens/<host>` of the configuration directory, a file only its owner can read.
+
+use crate::output::verbose;
+use crate::urls::Host;
+use crate::{paths, Result, ERROR_ICON};
+use anyhow::Context;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where a saved token is kept, for messages such as ``"Saved the token to {storage}"``.
+#[cfg(any(target_os = "macos", windows))]
+pub(crate) fn storage(_host: &Host) -> Result<String> {
+    Ok("the keychain".to_owned())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+pub(crate) fn storage(host: &Host) -> Result<String> {
+    Ok(format!("{:?}", token_file(host)?))
+}
+
+/// Saves `token` for `host`, replacing any token saved for it before.
+#[cfg(any(target_os = "macos", windows))]
+pub(crate) fn save(host: &Host, token: &str) -> Result<()> {
+    keyring::Entry::new(env!("CARGO_PKG_NAME"), host.name())
+        .and_then(|entry| entry.set_password(token))
+        .with_context(|| format!("{ERROR_ICON} Failed to save the token to the keychain"))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+pub(crate) fn save(host: &Host, token: &str) -> Result<()> {
+    save_at(&token_file(host)?, token)
+}
+
+/// The token saved for `host`, if any. A token that can't be read is not an error, since the
+/// user can still be asked for one.
+#[cfg(any(target_os = "macos", windows))]
+pub(crate) fn load(host: &Host) -> Option<String> {
+    match keyring::Entry::new(env!("CARGO_PKG_NAME"), host.name())
+        .and_then(|entry| entry.get_password())
+    {
+        Ok(token) => Some(token),
+        Err(keyring::Error::NoEntry) => None,
+        Err(err) => {
+            verbose!("Not reading the token saved in the keychain: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+pub(crate) fn load(host: &Host) -> Option<String> {
+    let path = token_file(host).ok()?;
+    match std::fs::read_to_string(&path) {
+        Ok(token) => Some(token.trim().to_owned()).filter(|token| !token.is_empty()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => {
+            verbose!("Not reading the token saved in {path:?}: {err}");
+            None
+        }
+    }
+}
+
+#[cfg_attr(any(target_os = "macos", windows), allow(dead_code))]
+fn token_file(host: &Host) -> Result<PathBuf> {
+    Ok(paths::config_dir()?.join("tokens").join(host.name()))
+}
+
+/// Writes `token` to `path`, a file created readable by its owner alone, so the token is never
+/// readable by others, not even while it is written.
+#[cfg_attr(any(target_os = "macos", windows), allow(dead_code))]
+pub(crate) fn save_at(path: &Path, token: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // A file created before keeps its permissions, so it is replaced instead.
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            return Err(err).with_context(|| format!("{ERROR_ICON} Failed to replace {path:?}"))
+        }
+        _ => {}
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{token}"))
+        .with_context(|| format!("{ERROR_ICON} Failed to write {path:?}"))
+}
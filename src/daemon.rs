@@ -0,0 +1,374 @@
+//! `privateer daemon`: enforces the configured policy on a schedule, unattended.
+//!
+//! Meant to run under a service manager such as systemd. Log lines carry a syslog priority
+//! prefix that journald understands when it captures standard error, or go to the local syslog
+//! daemon with `--syslog`. `SIGHUP` reloads the configuration without restarting, and
+//! `GET /health` reports whether the last run succeeded, next to `GET /metrics`.
+
+use crate::cli::{DaemonArgs, SortKey, SortOrder};
+use crate::config::{self, Config};
+use crate::enforce::{self, Status};
+use crate::github;
+use crate::plan;
+use crate::policy::Policy;
+use crate::queue::{self, Priority};
+use crate::server::{self, Request, Response};
+use crate::{cancel, heartbeat, lock, metrics, notify, paths, schema, Result};
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::net::TcpListener;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Level {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Level {
+    /// The syslog severity of the level.
+    fn severity(self) -> u8 {
+        match self {
+            Level::Error => 3,
+            Level::Warning => 4,
+            Level::Info => 6,
+        }
+    }
+}
+
+enum Sink {
+    /// Standard error captured by journald, which reads the `<N>` priority prefix.
+    Journal,
+    #[cfg(unix)]
+    Syslog(std::os::unix::net::UnixDatagram),
+    Stderr,
+}
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+
+/// Chooses where [`log`] writes to: syslog if asked, journald when started by systemd, and
+/// timestamped lines on standard error otherwise.
+pub(crate) fn init_log(syslog: bool) -> Result<()> {
+    let sink = match syslog {
+        #[cfg(unix)]
+        true => {
+            let socket = std::os::unix::net::UnixDatagram::unbound()?;
+            socket
+                .connect("/dev/log")
+                .context("Failed to connect to syslog at /dev/log")?;
+            Sink::Syslog(socket)
+        }
+        #[cfg(not(unix))]
+        true => return Err(anyhow!("`--syslog` is only supported on Unix")),
+        false if std::env::var_os("JOURNAL_STREAM").is_some() => Sink::Journal,
+        false => Sink::Stderr,
+    };
+    let _ = SINK.set(sink);
+
+    Ok(())
+}
+
+/// Writes one log line at `level`.
+pub(crate) fn log(level: Level, message: impl Display) {
+    match SINK.get().unwrap_or(&Sink::Stderr) {
+        Sink::Journal => eprintln!("<{}>{message}", level.severity()),
+        #[cfg(unix)]
+        Sink::Syslog(socket) => {
+            // Facility 3 is `daemon`.
+            let line = format!(
+                "<{priority}>{name}[{pid}]: {message}",
+                priority = 3 * 8 + level.severity(),
+                name = env!("CARGO_PKG_NAME"),
+                pid = std::process::id()
+            );
+            if socket.send(line.as_bytes()).is_err() {
+                eprintln!("{message}");
+            }
+        }
+        Sink::Stderr => eprintln!("{} {message}", Utc::now().format("%Y-%m-%d %H:%M:%S")),
+    }
+}
+
+/// The outcome of the runs so far, reported by `GET /health`.
+#[derive(Debug)]
+pub(crate) struct Health {
+    pub started: DateTime<Utc>,
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub changed: u64,
+}
+
+impl Health {
+    pub(crate) fn new(started: DateTime<Utc>) -> Self {
+        Self {
+            started,
+            last_run: None,
+            last_error: None,
+            changed: 0,
+        }
+    }
+
+    /// `200` while the last run succeeded, `503` after it failed, so probes restart or alert.
+    pub(crate) fn response(&self) -> Response {
+        let status = match self.last_error {
+            None => 200,
+            Some(_) => 503,
+        };
+        Response::json(
+            status,
+            &serde_json::json!({
+                "schema_version": schema::SCHEMA_VERSION,
+                "status": match status { 200 => "ok", _ => "failing" },
+                "started": self.started,
+                "last_run": self.last_run,
+                "last_error": self.last_error,
+                "changed": self.changed,
+            }),
+        )
+    }
+}
+
+fn route(request: &Request, health: &Mutex<Health>) -> Response {
+    if let Some(response) = metrics::route(request) {
+        return response;
+    }
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/health") => health.lock().expect("health lock").response(),
+        (_, "/health") => Response::text(405, "Method Not Allowed\n"),
+        _ => Response::not_found(),
+    }
+}
+
+/// What a signal asks of a long-running mode.
+pub(crate) enum Event {
+    /// `SIGHUP`: read the configuration again.
+    Reload,
+    /// `SIGTERM` or `Ctrl-C`: stop once the work in progress is done.
+    Stop,
+}
+
+/// The signals a long-running mode, such as `privateer daemon` or `privateer serve`, obeys.
+#[cfg(unix)]
+pub(crate) struct Signals {
+    hangup: tokio::signal::unix::Signal,
+    terminate: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl Signals {
+    pub(crate) fn new() -> Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+        Ok(Self {
+            hangup: signal(SignalKind::hangup())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+
+    pub(crate) async fn next(&mut self) -> Event {
+        tokio::select! {
+            _ = self.hangup.recv() => Event::Reload,
+            _ = self.terminate.recv() => Event::Stop,
+            _ = tokio::signal::ctrl_c() => Event::Stop,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) struct Signals;
+
+#[cfg(not(unix))]
+impl Signals {
+    pub(crate) fn new() -> Result<Self> {
+        Ok(Self)
+    }
+
+    pub(crate) async fn next(&mut self) -> Event {
+        let _ = tokio::signal::ctrl_c().await;
+        Event::Stop
+    }
+}
+
+/// Changes every repository that violates `policy`, returning how many were changed.
+async fn run_once(pat_token: &str, actor: &str, policy: &Policy) -> Result<u64> {
+    let repos = github::get_repos_request("", pat_token, SortKey::Name, SortOrder::Asc).await?;
+    metrics::scanned(repos.len());
+    let entries = enforce::fix(&plan::for_policy(policy, &repos), pat_token, actor).await;
+    let (mut changed, mut failed) = (0, 0);
+    for entry in &entries {
+        let full_name = &entry.full_name;
+        match (entry.status, &entry.error) {
+            (Status::Changed, _) => {
+                changed += 1;
+                log(
+                    Level::Info,
+                    format!(
+                        "Made `{full_name}` {visibility}",
+                        visibility = policy.default_visibility.name()
+                    ),
+                );
+            }
+            (Status::Failed, error) => {
+                failed += 1;
+                log(
+                    Level::Error,
+                    format!("`{full_name}`: {}", error.as_deref().unwrap_or_default()),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    match failed {
+        0 => Ok(changed),
+        _ => Err(anyhow!(
+            "{failed} of {total} repos could not be changed",
+            total = entries.len()
+        )),
+    }
+}
+
+fn announce(config: &Config) {
+    log(
+        Level::Info,
+        format!(
+            "Enforcing {visibility} repos every {interval}, {allowed} allowlisted",
+            visibility = config.policy.default_visibility.name(),
+            interval = humantime::format_duration(config.daemon.interval),
+            allowed = config.policy.allow.len()
+        ),
+    );
+}
+
+fn reload(path: &Path, config: &mut Config) {
+    match config::load(path) {
+        Ok(reloaded) => {
+            if reloaded.daemon.health_addr != config.daemon.health_addr {
+                log(
+                    Level::Warning,
+                    "`daemon.health_addr` changed, restart to serve health checks there",
+                );
+            }
+            notify::configure(&reloaded.notifications);
+            queue::configure(reloaded.daemon.requests_per_minute);
+            *config = reloaded;
+            log(
+                Level::Info,
+                format!("Reloaded the configuration from {path:?}"),
+            );
+            announce(config);
+        }
+        Err(err) => log(
+            Level::Error,
+            format!("Keeping the previous configuration: {err:#}"),
+        ),
+    }
+}
+
+/// Enforces the policy every `daemon.interval` until stopped with `SIGTERM` or `Ctrl-C`.
+pub(crate) async fn run(pat_token: &str, args: &DaemonArgs, force: bool) -> Result<()> {
+    init_log(args.syslog)?;
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => paths::config_file()?,
+    };
+    let mut config = config::load(&path)?;
+    notify::configure(&config.notifications);
+    queue::configure(config.daemon.requests_per_minute);
+    let actor = github::get_authenticated_user(pat_token).await?;
+
+    let health = Arc::new(Mutex::new(Health::new(Utc::now())));
+    if let Some(addr) = args.health_addr.or(config.daemon.health_addr) {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to listen on {addr}"))?;
+        let health = Arc::clone(&health);
+        tokio::spawn(server::serve(listener, move |request| {
+            let health = Arc::clone(&health);
+            async move { route(&request, &health) }
+        }));
+        log(
+            Level::Info,
+            format!("Serving health checks on http://{addr}/health and metrics on /metrics"),
+        );
+    }
+    let mut signals = Signals::new()?;
+    announce(&config);
+
+    loop {
+        let (mut stopping, mut reloading) = (false, false);
+        // Another run may be changing repositories, in which case this one waits for the next.
+        match lock::acquire(force) {
+            Ok(_lock) => {
+                // A signal during a run stops it at its next safe point rather than mid-change.
+                let token = CancellationToken::new();
+                let mut run = std::pin::pin!(cancel::scope(
+                    token.clone(),
+                    queue::with_priority(
+                        Priority::Background,
+                        run_once(pat_token, &actor, &config.policy),
+                    ),
+                ));
+                let result = loop {
+                    tokio::select! {
+                        result = &mut run => break result,
+                        event = signals.next(), if !stopping => match event {
+                            Event::Reload => reloading = true,
+                            Event::Stop => {
+                                log(Level::Info, "Stopping after the changes in flight");
+                                token.cancel();
+                                stopping = true;
+                            }
+                        },
+                    }
+                };
+                let mut health = health.lock().expect("health lock");
+                health.last_run = Some(Utc::now());
+                metrics::ran(Utc::now(), result.is_ok());
+                match result {
+                    Ok(changed) => {
+                        health.changed += changed;
+                        health.last_error = None;
+                        if let Err(err) = heartbeat::beat("daemon") {
+                            log(Level::Warning, err);
+                        }
+                    }
+                    Err(err) if cancel::is_cancelled(&err) => {}
+                    Err(err) => {
+                        log(
+                            Level::Error,
+                            format!("Run failed, retrying next time: {err}"),
+                        );
+                        health.last_error = Some(err.to_string());
+                    }
+                }
+            }
+            Err(err) => log(Level::Warning, err),
+        }
+        if stopping {
+            log(Level::Info, "Stopping");
+            return Ok(());
+        }
+        if reloading {
+            reload(&path, &mut config);
+        }
+
+        let next_run = Instant::now() + config.daemon.interval;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(next_run) => break,
+                event = signals.next() => match event {
+                    Event::Reload => reload(&path, &mut config),
+                    Event::Stop => {
+                        log(Level::Info, "Stopping");
+                        return Ok(());
+                    }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Early warnings about GitHub API endpoints scheduled for removal.
+//!
+//! GitHub marks such endpoints with `Deprecation` and `Sunset` response headers. Each endpoint is
+//! warned about once per run, so users hear about it before the endpoint stops working.
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Describes the deprecation of the endpoint at `path` announced by `headers`, if any.
+pub(crate) fn notice(path: &str, headers: &HeaderMap) -> Option<String> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let deprecation = header("deprecation");
+    let sunset = header("sunset").and_then(http_date);
+    if deprecation.is_none() && sunset.is_none() {
+        return None;
+    }
+    // `Deprecation` is either `true`, a date, or `@` followed by a Unix timestamp.
+    let since = deprecation.and_then(|value| match value.strip_prefix('@') {
+        Some(timestamp) => DateTime::from_timestamp(timestamp.parse().ok()?, 0),
+        None => http_date(value),
+    });
+
+    let mut notice = format!("The GitHub API endpoint `{path}` is deprecated");
+    if let Some(since) = since {
+        notice.push_str(&format!(" since {}", since.format("%Y-%m-%d")));
+    }
+    if let Some(sunset) = sunset {
+        notice.push_str(&format!(
+            " and will be removed on {}",
+            sunset.format("%Y-%m-%d")
+        ));
+    }
+    notice.push_str(". Hint! Look for a newer version of privateer");
+
+    Some(notice)
+}
+
+fn http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Prints the deprecation notice for `path`, unless it was already printed in this run.
+pub(crate) fn warn_once(path: &str, headers: &HeaderMap) {
+    static WARNED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+    let Some(notice) = notice(path, headers) else {
+        return;
+    };
+    let Ok(mut warned) = WARNED.lock() else {
+        return;
+    };
+    if warned
+        .get_or_insert_with(HashSet::new)
+        .insert(path.to_owned())
+    {
+        eprintln!("{} {notice}", console::style("warning:").yellow().bold());
+    }
+}
@@ -0,0 +1,173 @@
+//! Turning a public fork into a standalone private repository.
+//!
+//! GitHub doesn't let forks of public repositories become private. Its documented workaround is
+//! automated here: create a new private repository, push a bare clone of the fork into it, then
+//! keep, archive or delete the fork. Creating, archiving and deleting are in the audit trail.
+
+use crate::audit::{self, AuditRecord};
+use crate::backup;
+use crate::cli::{AfterDetach, DetachForkArgs};
+use crate::github::{self, NewRepository, Repository, UpdateRepository};
+use crate::i18n::t;
+use crate::output::status;
+use crate::{prompter, validate, Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::anyhow;
+use serde_json::Value;
+
+/// Detaches the fork of `args` after showing what will happen and asking to go ahead.
+pub(crate) async fn run(pat_token: &str, args: &DetachForkArgs) -> Result<()> {
+    let username = github::get_authenticated_user(pat_token).await?;
+    let input = validate::repo(&args.repo)?;
+    let owner = input.owner.as_deref().unwrap_or(&username);
+    let fork = github::get_repo(owner, &input.name, pat_token).await?;
+    let full_name = format!("{}/{}", fork.owner.login, fork.name);
+    if !fork.fork {
+        return Err(anyhow!(
+            "{ERROR_ICON} `{full_name}` is not a fork. Hint! Make it private with `privateer --repo {full_name} --private true`"
+        ));
+    }
+    let name = args
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("{}-private", fork.name));
+
+    let copy = format!("{}/{name}", fork.owner.login);
+    let fork_name = full_name.as_str();
+    status!("{}", t!("detach-plan"));
+    status!("  1. {}", t!("detach-plan-create", repo = copy));
+    status!("  2. {}", t!("detach-plan-push", repo = fork_name));
+    match args.then {
+        AfterDetach::Keep => status!("  3. {}", t!("detach-plan-keep", repo = fork_name)),
+        AfterDetach::Archive => status!("  3. {}", t!("detach-plan-archive", repo = fork_name)),
+        AfterDetach::Delete => status!("  3. {}", t!("detach-plan-delete", repo = fork_name)),
+    }
+    if !args.yes {
+        if !prompter::is_interactive() {
+            return Err(anyhow!(
+                "{ERROR_ICON} Not confirmed. Hint! Pass `--yes` to detach without a terminal"
+            ));
+        }
+        if !prompter::prompt_yes_no(&t!("go-ahead-prompt"))? {
+            return Err(anyhow!("{ERROR_ICON} {}", t!("nothing-changed")));
+        }
+    }
+
+    let created = create_copy(&fork, &name, &username, pat_token).await?;
+    status!(
+        "{SUCCESS_ICON} {}",
+        t!("detach-created", repo = created.full_name.as_str())
+    );
+
+    let dir = std::env::temp_dir().join(format!(
+        "privateer-detach-{}-{}.git",
+        std::process::id(),
+        fork.name
+    ));
+    let (source, target) = (
+        backup::clone_url(&fork.owner.login, &fork.name),
+        backup::clone_url(&created.owner.login, &created.name),
+    );
+    let token = pat_token.to_owned();
+    let clone_dir = dir.clone();
+    let pushed = tokio::task::spawn_blocking(move || {
+        backup::bare_clone(&source, &clone_dir, &token)?;
+        backup::push_mirror(&clone_dir, &target, &token)
+    })
+    .await?;
+    let _ = std::fs::remove_dir_all(&dir);
+    if let Err(err) = pushed {
+        return Err(anyhow!(
+            "{err}\n{ERROR_ICON} `{created}` was created but is incomplete, and `{full_name}` was left as it is. Hint! Delete `{created}` and try again",
+            created = created.full_name
+        ));
+    }
+    status!(
+        "{SUCCESS_ICON} {}",
+        t!(
+            "detach-pushed",
+            fork = full_name.as_str(),
+            repo = created.full_name.as_str()
+        )
+    );
+
+    finish(&fork, args.then, &username, pat_token).await?;
+    status!(
+        "{SUCCESS_ICON} {}",
+        t!(
+            "detach-done",
+            fork = full_name.as_str(),
+            repo = created.full_name.as_str()
+        )
+    );
+
+    Ok(())
+}
+
+/// Creates the private repository `name` next to `fork`, with its description, in the
+/// organization owning the fork if that isn't `username`.
+pub(crate) async fn create_copy(
+    fork: &Repository,
+    name: &str,
+    username: &str,
+    pat_token: &str,
+) -> Result<Repository> {
+    let owner = fork.owner.login.as_str();
+    let org = (!owner.eq_ignore_ascii_case(username)).then_some(owner);
+    let new = NewRepository {
+        name: name.to_owned(),
+        private: true,
+        description: fork.description.clone(),
+    };
+    let created = github::create_repo(org, &new, pat_token).await?;
+    audit::append(&AuditRecord {
+        timestamp: chrono::Utc::now(),
+        actor: username.to_owned(),
+        repo: format!("{owner}/{name}"),
+        node_id: audit::node_id(&created),
+        field: "created".to_owned(),
+        old: Value::Null,
+        new: Value::String(format!("detached from {owner}/{}", fork.name)),
+        request_id: None,
+        backup: None,
+    })?;
+
+    Ok(created)
+}
+
+/// Keeps, archives or deletes `fork` once its contents are in the new repository.
+pub(crate) async fn finish(
+    fork: &Repository,
+    then: AfterDetach,
+    actor: &str,
+    pat_token: &str,
+) -> Result<()> {
+    let (owner, name) = (fork.owner.login.as_str(), fork.name.as_str());
+    let (field, request_id) = match then {
+        AfterDetach::Keep => return Ok(()),
+        AfterDetach::Archive => {
+            let update = UpdateRepository {
+                archived: Some(true),
+                ..UpdateRepository::default()
+            };
+            (
+                "archived",
+                github::update_repo(owner, name, &update, pat_token).await?,
+            )
+        }
+        AfterDetach::Delete => (
+            "deleted",
+            github::delete_repo(owner, name, pat_token).await?,
+        ),
+    };
+    audit::append(&AuditRecord {
+        timestamp: chrono::Utc::now(),
+        actor: actor.to_owned(),
+        repo: format!("{owner}/{name}"),
+        node_id: audit::node_id(fork),
+        field: field.to_owned(),
+        old: Value::Bool(false),
+        new: Value::Bool(true),
+        request_id,
+        backup: None,
+    })
+}
@@ -0,0 +1,109 @@
+//! Rendering of the difference between a repository's current and desired state.
+
+use console::style;
+use serde_json::Value;
+
+use crate::i18n::t;
+
+/// A single field of a repository going from its current value to a desired value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FieldChange {
+    pub field: String,
+    /// Current value, `None` if it is not known.
+    pub old: Option<Value>,
+    pub new: Value,
+}
+
+impl FieldChange {
+    /// A change of the `visibility` field, derived from the repository's `private` flag.
+    pub(crate) fn visibility(old_private: Option<bool>, new_private: bool) -> Self {
+        let visibility = |private: bool| match private {
+            true => Value::from("private"),
+            false => Value::from("public"),
+        };
+
+        Self {
+            field: "visibility".to_owned(),
+            old: old_private.map(visibility),
+            new: visibility(new_private),
+        }
+    }
+
+    /// Returns `true` if the desired value is already the current value.
+    pub(crate) fn is_unchanged(&self) -> bool {
+        self.old.as_ref() == Some(&self.new)
+    }
+}
+
+/// Renders `changes` of the repository `name` as a colored, line-per-field diff.
+///
+/// ```text
+/// gittidy
+///   visibility: public → private
+///   has_wiki: true (unchanged)
+/// ```
+pub(crate) fn render(name: &str, changes: &[FieldChange]) -> String {
+    let mut lines = vec![style(name).yellow().bold().to_string()];
+    for change in changes {
+        let line = match &change.old {
+            Some(old) if change.is_unchanged() => style(format!(
+                "  {field}: {old} (unchanged)",
+                field = change.field,
+                old = display(old)
+            ))
+            .dim()
+            .to_string(),
+            old => format!(
+                "  {field}: {old} \u{2192} {new}",
+                field = change.field,
+                old = style(old.as_ref().map_or("unknown".to_owned(), display)).red(),
+                new = style(display(&change.new)).green(),
+            ),
+        };
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+/// Summarizes `changes` in one line for the final review, grouped by their desired value.
+///
+/// ```text
+/// 12 repos will become private, 3 already private will be skipped
+/// ```
+pub(crate) fn summarize(changes: &[FieldChange]) -> String {
+    // (unchanged, value, count) in order of first appearance.
+    let mut groups: Vec<(bool, String, usize)> = Vec::new();
+    for change in changes {
+        let key = (change.is_unchanged(), display(&change.new));
+        match groups
+            .iter_mut()
+            .find(|(unchanged, value, _)| (*unchanged, value) == (key.0, &key.1))
+        {
+            Some((_, _, count)) => *count += 1,
+            None => groups.push((key.0, key.1, 1)),
+        }
+    }
+    // Changes first, so the summary leads with what will actually happen.
+    groups.sort_by_key(|(unchanged, _, _)| *unchanged);
+    if groups.iter().all(|(unchanged, _, _)| *unchanged) {
+        return t!("diff-nothing-to-change", count = changes.len());
+    }
+
+    groups
+        .into_iter()
+        .map(|(unchanged, value, count)| match unchanged {
+            true => t!("diff-already-skipped", count = count, value = value),
+            false => t!("diff-will-become", count = count, value = value),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Displays strings without the quotes JSON would add.
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,285 @@
+//! `privateer doctor`: diagnoses the environment privateer runs in.
+//!
+//! Each check prints a line that passes, warns or fails, and each problem comes with its fix.
+//! Only failures make the command fail; warnings point at things that may bite later, such as a
+//! drifting clock or a proxy that is the only way out.
+
+use crate::github;
+use crate::transport::HttpResponse;
+use crate::urls;
+use crate::{
+    config, credentials, output, paths, prompter, Result, ERROR_ICON, SUCCESS_ICON, WARNING_ICON,
+};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use std::time::Duration;
+
+/// Largest difference from GitHub's clock tolerated before warning.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// How a check went.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Outcome {
+    Pass(String),
+    /// A possible problem, with its fix.
+    Warn(String, String),
+    /// A problem that stops privateer from working, with its fix.
+    Fail(String, String),
+}
+
+/// A named check and how it went.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Check {
+    pub name: &'static str,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    fn new(name: &'static str, outcome: Outcome) -> Self {
+        Self { name, outcome }
+    }
+
+    fn print(&self) {
+        let name = self.name;
+        match &self.outcome {
+            Outcome::Pass(detail) => println!("{SUCCESS_ICON} {name}: {detail}"),
+            Outcome::Warn(detail, fix) => {
+                println!("{WARNING_ICON} {name}: {detail}\n    Fix: {fix}")
+            }
+            Outcome::Fail(detail, fix) => println!("{ERROR_ICON} {name}: {detail}\n    Fix: {fix}"),
+        }
+    }
+}
+
+/// Runs every check, printing each as it finishes, and fails if any of them failed.
+pub(crate) async fn run() -> Result<()> {
+    let mut checks = Vec::new();
+    let mut report = |check: Check| {
+        check.print();
+        checks.push(check);
+    };
+
+    let host = urls::host();
+    let env_token = std::env::var("PAT_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty());
+    let saved_token = credentials::load(&host);
+    report(Check::new(
+        "token",
+        match (&env_token, &saved_token) {
+            (Some(_), _) => Outcome::Pass("`PAT_TOKEN` is set".to_owned()),
+            (None, Some(_)) => Outcome::Pass(format!(
+                "using the token saved in {}",
+                credentials::storage(&host)?
+            )),
+            (None, None) => Outcome::Warn(
+                "`PAT_TOKEN` is not set, so every run prompts for it".to_owned(),
+                "set it in the environment or `.env`, or run `privateer init`".to_owned(),
+            ),
+        },
+    ));
+    let pat_token = env_token.or(saved_token);
+
+    let url = match pat_token {
+        Some(_) => host.api_url(&["user"]),
+        None => host.api.clone(),
+    };
+    let request = github::api_request(Method::GET, url, pat_token.as_deref().unwrap_or(""))?;
+    match github::send(request).await {
+        Ok(response) => {
+            report(Check::new(
+                "api",
+                Outcome::Pass(format!("{} answered", host.api)),
+            ));
+            if pat_token.is_some() {
+                report(token_check(&response));
+                if response.status.is_success() {
+                    report(scopes_check(response.header_str("x-oauth-scopes")));
+                }
+            }
+            if let Some(date) = response.header_str("date") {
+                report(clock_check(date, Utc::now()));
+            }
+        }
+        Err(err) => report(Check::new(
+            "api",
+            Outcome::Fail(
+                format!("{} is unreachable: {err}", host.api),
+                "check the network, `GH_HOST`, and `HTTPS_PROXY` if a proxy is needed".to_owned(),
+            ),
+        )),
+    }
+    if let Some(proxy) = proxy_from_env() {
+        report(direct_check(&proxy, &host.api).await);
+    }
+
+    let config_path = paths::config_file()?;
+    report(Check::new(
+        "config",
+        match config_path.exists() {
+            false => Outcome::Pass(format!("{config_path:?} doesn't exist; defaults apply")),
+            true => match config::load(&config_path) {
+                Ok(_) => Outcome::Pass(format!("{config_path:?} is valid")),
+                Err(err) => Outcome::Fail(
+                    format!("{err:#}"),
+                    "fix the file, or write a new one with `privateer init`".to_owned(),
+                ),
+            },
+        },
+    ));
+    report(state_check());
+    report(Check::new(
+        "terminal",
+        Outcome::Pass(format!(
+            "interactive: {}, colors: {}, emoji: {}",
+            yes_no(prompter::is_interactive()),
+            yes_no(console::colors_enabled()),
+            yes_no(!output::plain_icons()),
+        )),
+    ));
+
+    let failed = checks
+        .iter()
+        .filter(|check| matches!(check.outcome, Outcome::Fail(..)))
+        .count();
+    match failed {
+        0 => Ok(()),
+        _ => Err(anyhow!("{ERROR_ICON} `{failed}` checks failed")),
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    match value {
+        true => "yes",
+        false => "no",
+    }
+}
+
+/// Whether GitHub accepted the token, from its answer to `GET /user`.
+pub(crate) fn token_check(response: &HttpResponse) -> Check {
+    Check::new(
+        "token valid",
+        match response.status.as_u16() {
+            200..=299 => Outcome::Pass("GitHub accepted the token".to_owned()),
+            401 => Outcome::Fail(
+                "GitHub rejected the token: it is wrong, expired or revoked".to_owned(),
+                "create a new token and update `PAT_TOKEN`".to_owned(),
+            ),
+            status => Outcome::Fail(
+                format!(
+                    "GitHub answered `{status}`: {}",
+                    github::api_error(response)
+                ),
+                "see https://www.githubstatus.com, or try again later".to_owned(),
+            ),
+        },
+    )
+}
+
+/// Whether the scopes of a classic token, listed in `X-OAuth-Scopes`, allow changing visibility.
+/// Fine-grained tokens don't list scopes, so they can't be checked.
+pub(crate) fn scopes_check(scopes: Option<&str>) -> Check {
+    let Some(scopes) = scopes else {
+        return Check::new(
+            "scopes",
+            Outcome::Warn(
+                "a fine-grained token, whose permissions can't be checked".to_owned(),
+                "make sure it has read and write access to `Administration`".to_owned(),
+            ),
+        );
+    };
+    let granted: Vec<&str> = scopes.split(',').map(str::trim).collect();
+    Check::new(
+        "scopes",
+        match granted.contains(&"repo") {
+            true => Outcome::Pass(format!("`{scopes}`")),
+            false => Outcome::Fail(
+                format!("`repo` is missing from `{scopes}`"),
+                "create a token with the `repo` scope".to_owned(),
+            ),
+        },
+    )
+}
+
+/// How far the local clock is from GitHub's, as given by the `Date` header of its response.
+pub(crate) fn clock_check(date: &str, now: DateTime<Utc>) -> Check {
+    let Ok(theirs) = DateTime::parse_from_rfc2822(date) else {
+        return Check::new(
+            "clock",
+            Outcome::Warn(
+                format!("GitHub's `Date: {date}` can't be read"),
+                "none needed unless times look wrong".to_owned(),
+            ),
+        );
+    };
+    let skew = (now - theirs.with_timezone(&Utc))
+        .abs()
+        .to_std()
+        .unwrap_or_default();
+    Check::new(
+        "clock",
+        match skew <= MAX_CLOCK_SKEW {
+            true => Outcome::Pass(format!("{}s from GitHub's", skew.as_secs())),
+            false => Outcome::Warn(
+                format!(
+                    "{} off from GitHub's; rate limit resets and `--since` dates will be off",
+                    humantime::format_duration(Duration::from_secs(skew.as_secs()))
+                ),
+                "synchronize the clock, e.g. with NTP".to_owned(),
+            ),
+        },
+    )
+}
+
+/// The proxy the network requests go through, from the variables `reqwest` reads.
+pub(crate) fn proxy_from_env() -> Option<String> {
+    ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+        .into_iter()
+        .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))
+}
+
+/// Whether the API is also reachable without going through `proxy`.
+async fn direct_check(proxy: &str, api: &url::Url) -> Check {
+    let direct = reqwest::Client::builder()
+        .no_proxy()
+        .timeout(Duration::from_secs(10))
+        .build();
+    let reached = match direct {
+        Ok(client) => client.get(api.clone()).send().await.is_ok(),
+        Err(_) => false,
+    };
+    Check::new(
+        "proxy",
+        match reached {
+            true => Outcome::Pass(format!(
+                "requests go through `{proxy}`; direct access works too"
+            )),
+            false => Outcome::Warn(
+                format!("requests go through `{proxy}`, the only way to reach the API"),
+                "keep `HTTPS_PROXY` set wherever privateer runs, such as its daemon service"
+                    .to_owned(),
+            ),
+        },
+    )
+}
+
+/// Whether the state directory, with the audit trail, lock and snapshots, is writable.
+fn state_check() -> Check {
+    let written = paths::state_dir().and_then(|dir| {
+        let probe = dir.join(format!(".doctor-{}", std::process::id()));
+        std::fs::write(&probe, b"")?;
+        std::fs::remove_file(&probe)?;
+        Ok(dir)
+    });
+    Check::new(
+        "state",
+        match written {
+            Ok(dir) => Outcome::Pass(format!("{dir:?} is writable")),
+            Err(err) => Outcome::Fail(
+                format!("the state directory isn't writable: {err}"),
+                "set `XDG_STATE_HOME` to a writable directory".to_owned(),
+            ),
+        },
+    )
+}
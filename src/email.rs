@@ -0,0 +1,124 @@
+//! Email notifications sent over SMTP, for teams without chat webhooks.
+//!
+//! ```toml
+//! [notifications.email]
+//! server = "smtp.example.com"
+//! from = "privateer <privateer@example.com>"
+//! to = ["security@example.com"]
+//! username = "privateer@example.com"
+//! password_command = "secret-tool lookup service privateer-smtp"
+//! ```
+//!
+//! The password is never stored in the configuration itself: it is read from the environment
+//! variable named by `password_env`, or printed by `password_command`, such as a lookup in the
+//! system keychain.
+
+use crate::{shell, Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Security {
+    /// Upgrade the connection with `STARTTLS`, on port 587 by default.
+    #[default]
+    StartTls,
+    /// Connect with TLS from the start, on port 465 by default.
+    Tls,
+    /// Send in the clear, on port 25 by default; only for relays on the local machine.
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Email {
+    pub server: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: Security,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Name of the environment variable holding the password.
+    #[serde(default)]
+    pub password_env: Option<String>,
+    /// Shell command printing the password.
+    #[serde(default)]
+    pub password_command: Option<String>,
+}
+
+impl Email {
+    /// Builds the message with `subject` and `body`, checking every address.
+    pub(crate) fn message(&self, subject: &str, body: String) -> Result<Message> {
+        let mailbox = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .with_context(|| format!("{ERROR_ICON} `{address}` is not a valid email address"))
+        };
+        let mut builder = Message::builder()
+            .from(mailbox(&self.from)?)
+            .subject(subject);
+        for to in &self.to {
+            builder = builder.to(mailbox(to)?);
+        }
+
+        Ok(builder.body(body)?)
+    }
+
+    fn password(&self) -> Result<Option<String>> {
+        match (&self.password_env, &self.password_command) {
+            (Some(var), _) => std::env::var(var).map(Some).with_context(|| {
+                format!("{ERROR_ICON} `{var}` is not set. Hint! It should hold the SMTP password")
+            }),
+            (None, Some(command)) => shell::output_of(command).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn transport(&self) -> Result<SmtpTransport> {
+        let mut builder = match self.security {
+            Security::StartTls => SmtpTransport::starttls_relay(&self.server)?,
+            Security::Tls => SmtpTransport::relay(&self.server)?,
+            Security::None => SmtpTransport::builder_dangerous(&self.server),
+        };
+        if let Some(port) = self.port {
+            builder = builder.port(port);
+        }
+        match (&self.username, self.password()?) {
+            (Some(username), Some(password)) => {
+                builder = builder.credentials(Credentials::new(username.clone(), password));
+            }
+            (None, Some(_)) => {
+                return Err(anyhow!(
+                    "{ERROR_ICON} An SMTP password is configured without a `username`"
+                ))
+            }
+            _ => {}
+        }
+
+        Ok(builder.build())
+    }
+
+    /// Sends `subject` and `body` to every recipient, blocking until the server accepts it.
+    pub(crate) fn send(&self, subject: &str, body: String) -> Result<()> {
+        if self.to.is_empty() {
+            return Err(anyhow!(
+                "{ERROR_ICON} No recipients in `notifications.email.to`"
+            ));
+        }
+        let message = self.message(subject, body)?;
+        self.transport()?.send(&message).with_context(|| {
+            format!(
+                "{ERROR_ICON} Failed to send email through `{}`",
+                self.server
+            )
+        })?;
+
+        Ok(())
+    }
+}
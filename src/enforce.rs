@@ -0,0 +1,393 @@
+//! `privateer enforce`: applies the configured policy once, or only reports on it.
+//!
+//! With `--check-only` nothing is changed, which suits cron jobs and CI pipelines that gate on
+//! the exit status. Either way a JSON report of every violation is written for later review,
+//! with the open Dependabot and code scanning alerts of each repository the token can see them
+//! for, since they often decide whether a repository can be public. Violations that can't be
+//! fixed are reported too, with the reason `no_admin`, `fork` or `archived`, and fail the check
+//! like any other, whether or not `--check-only` is given.
+
+use crate::apply::{self, Applied};
+use crate::cli::{EnforceArgs, SortKey, SortOrder};
+use crate::exit::{self, Exit};
+use crate::github::{self, Repository};
+use crate::i18n::t;
+use crate::output::verbose;
+use crate::plan::{self, SkipReason, Wanted};
+use crate::policy::Policy;
+use crate::{
+    cancel, config, heartbeat, lock, notify, paths, schema, Result, ERROR_ICON, SUCCESS_ICON,
+};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use console::style;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Status {
+    /// Found, and left as it is.
+    Violation,
+    Changed,
+    /// Already changed by someone else before privateer got to it.
+    AlreadySet,
+    /// Left alone for the entry's `reason`, such as being exempt by the policy.
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Entry {
+    pub full_name: String,
+    pub visibility: &'static str,
+    pub url: String,
+    pub status: Status,
+    /// Why the repository was left alone, if it was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<SkipReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "SecurityAlerts::is_unknown")]
+    pub alerts: SecurityAlerts,
+}
+
+/// Numbers of open security alerts of a repository; `None` where the token can't see them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct SecurityAlerts {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependabot: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_scanning: Option<usize>,
+}
+
+impl SecurityAlerts {
+    /// Fetches the numbers of open alerts of `owner/name`, leaving out those that can't be listed.
+    pub(crate) async fn fetch(owner: &str, name: &str, pat_token: &str) -> Self {
+        let count = |tool: &'static str| async move {
+            github::count_open_alerts(owner, name, tool, pat_token)
+                .await
+                .unwrap_or_else(|err| {
+                    verbose!("{err}");
+                    None
+                })
+        };
+
+        Self {
+            dependabot: count("dependabot").await,
+            code_scanning: count("code-scanning").await,
+        }
+    }
+
+    fn is_unknown(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The open alerts, such as `3 Dependabot, 1 code scanning alerts`, or `None` if there
+    /// are none or they are unknown.
+    pub(crate) fn describe(&self) -> Option<String> {
+        let counts: Vec<String> = [
+            (self.dependabot, "Dependabot"),
+            (self.code_scanning, "code scanning"),
+        ]
+        .into_iter()
+        .filter_map(|(count, tool)| Some((count.filter(|count| *count > 0)?, tool)))
+        .map(|(count, tool)| format!("{count} {tool}"))
+        .collect();
+        match counts.is_empty() {
+            true => None,
+            false => Some(t!("enforce-alerts", counts = counts.join(", "))),
+        }
+    }
+}
+
+impl Entry {
+    fn new(repo: &Repository) -> Self {
+        Self {
+            full_name: format!("{}/{}", repo.owner.login, repo.name),
+            visibility: match repo.private {
+                Some(true) => "private",
+                Some(false) => "public",
+                None => "unknown",
+            },
+            url: repo.html_url.clone(),
+            status: Status::Violation,
+            reason: None,
+            error: None,
+            alerts: SecurityAlerts::default(),
+        }
+    }
+
+    /// A violation that can't be fixed for `reason`, see [`is_unfixable`].
+    fn unfixable(repo: &Repository, reason: SkipReason) -> Self {
+        Self {
+            reason: Some(reason),
+            ..Self::new(repo)
+        }
+    }
+
+    /// A violation, tagged with why it can't be fixed if it can't.
+    fn violation(repo: &Repository, private: bool) -> Self {
+        match plan::skip_reason(repo, private).filter(|reason| is_unfixable(*reason)) {
+            Some(reason) => Self::unfixable(repo, reason),
+            None => Self::new(repo),
+        }
+    }
+
+    fn skipped(repo: &Repository, reason: SkipReason) -> Self {
+        Self {
+            status: Status::Skipped,
+            reason: Some(reason),
+            ..Self::new(repo)
+        }
+    }
+
+    /// The status as shown in the summary table, with the reason of a skip or of a violation
+    /// left as it is.
+    fn describe(&self) -> String {
+        let status = match self.status {
+            Status::Violation => t!("enforce-violation"),
+            Status::Changed => t!("enforce-changed"),
+            Status::AlreadySet => t!("enforce-already-set"),
+            Status::Skipped => t!("enforce-skipped"),
+            Status::Failed => t!("enforce-failed"),
+        };
+        match self.reason {
+            Some(reason) if matches!(self.status, Status::Skipped | Status::Violation) => {
+                format!("{status} ({})", reason.name())
+            }
+            _ => status,
+        }
+    }
+}
+
+/// Returns `true` if a violation skipped for `reason` can't be fixed by privateer: the token
+/// can't administer the repository, or GitHub refuses to change it.
+fn is_unfixable(reason: SkipReason) -> bool {
+    matches!(
+        reason,
+        SkipReason::NoAdmin | SkipReason::Fork | SkipReason::Archived
+    )
+}
+
+/// The report artifact written by `privateer enforce`.
+#[derive(Debug, Serialize)]
+pub(crate) struct Report {
+    pub generated_at: DateTime<Utc>,
+    pub default_visibility: &'static str,
+    pub allow: Vec<String>,
+    /// Number of repositories checked against the policy.
+    pub checked: usize,
+    /// Every violation, including those that can't be fixed, which have the reason `no_admin`,
+    /// `fork` or `archived`.
+    pub violations: Vec<Entry>,
+    /// Repositories without the default visibility that are left alone, such as those exempt.
+    pub skipped: Vec<Entry>,
+}
+
+impl Report {
+    /// Reports the repositories among `repos` that violate `policy`, without changing any.
+    pub(crate) fn check(
+        policy: &Policy,
+        repos: &[Repository],
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        // A repository the token can't change still violates the policy, and hiding it would
+        // pass a compliance check it fails.
+        let private = policy.default_visibility.is_private();
+        let (unfixable, skipped): (Vec<_>, Vec<_>) = policy
+            .exempt(repos)
+            .into_iter()
+            .partition(|(_, reason)| *reason == SkipReason::NoAdmin);
+        Self {
+            generated_at,
+            default_visibility: policy.default_visibility.name(),
+            allow: policy.allow.clone(),
+            checked: repos.len(),
+            violations: policy
+                .violations(repos)
+                .into_iter()
+                .map(|repo| Entry::violation(repo, private))
+                .chain(
+                    unfixable
+                        .into_iter()
+                        .map(|(repo, reason)| Entry::unfixable(repo, reason)),
+                )
+                .collect(),
+            skipped: skipped
+                .into_iter()
+                .map(|(repo, reason)| Entry::skipped(repo, reason))
+                .collect(),
+        }
+    }
+
+    /// The violations that can't be fixed.
+    pub(crate) fn unfixable(&self) -> usize {
+        self.violations
+            .iter()
+            .filter(|entry| entry.reason.is_some_and(is_unfixable))
+            .count()
+    }
+
+    /// Adds the open security alerts of each repository in the report.
+    pub(crate) async fn add_alerts(&mut self, pat_token: &str) {
+        for entry in &mut self.violations {
+            if let Some((owner, name)) = entry.full_name.split_once('/') {
+                entry.alerts = SecurityAlerts::fetch(owner, name, pat_token).await;
+            }
+        }
+    }
+
+    pub(crate) fn count(&self, status: Status) -> usize {
+        self.violations
+            .iter()
+            .chain(&self.skipped)
+            .filter(|entry| entry.status == status)
+            .count()
+    }
+}
+
+/// Changes each repository of `wanted` to the visibility wanted for it, one at a time.
+///
+/// Stops before the next repository once the token in scope is cancelled; the repositories not
+/// reached are left out of the entries.
+pub(crate) async fn fix(wanted: &[Wanted], pat_token: &str, actor: &str) -> Vec<Entry> {
+    let started = Utc::now();
+    let mut entries = Vec::with_capacity(wanted.len());
+    for (repo, private) in wanted {
+        // Archived repositories and forks can't be changed, so they aren't tried, and remain
+        // violations.
+        if let Some(reason) = plan::skip_reason(repo, *private) {
+            entries.push(match is_unfixable(reason) {
+                true => Entry::unfixable(repo, reason),
+                false => Entry::skipped(repo, reason),
+            });
+            continue;
+        }
+        let mut entry = Entry::new(repo);
+        let owner = repo.owner.login.as_str();
+        let privacy = private.to_string();
+        match apply::apply_one(repo, &privacy, owner, pat_token, actor, &|_| {}).await {
+            Ok(Applied::Changed) => entry.status = Status::Changed,
+            Ok(Applied::AlreadySet) => {
+                entry.status = Status::AlreadySet;
+                entry.reason = Some(SkipReason::AlreadyDesiredState);
+            }
+            Ok(Applied::Skipped) => entry = Entry::skipped(repo, SkipReason::ChangedSincePlanned),
+            Err(err) if cancel::is_cancelled(&err) => break,
+            Err(err) => {
+                entry.status = Status::Failed;
+                entry.error = Some(err.to_string());
+            }
+        }
+        entries.push(entry);
+    }
+
+    let failures = entries
+        .iter()
+        .filter_map(|entry| Some((entry.full_name.clone(), entry.error.clone()?)))
+        .collect();
+    match notify::Summary::since(started, actor, failures) {
+        Ok(summary) => notify::send(&summary).await,
+        Err(err) => eprintln!(
+            "{ERROR_ICON} {}",
+            t!("summary-failed", error = err.to_string())
+        ),
+    }
+
+    entries
+}
+
+/// Checks every repository against the policy, changing the violations unless `--check-only`.
+pub(crate) async fn run(pat_token: &str, args: &EnforceArgs, force: bool) -> Result<()> {
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => paths::config_file()?,
+    };
+    let config = config::load(&path)?;
+    notify::configure(&config.notifications);
+    let policy = config.policy;
+    let repos = github::get_repos_request("", pat_token, SortKey::Name, SortOrder::Asc).await?;
+    let mut report = Report::check(&policy, &repos, Utc::now());
+
+    let wanted = plan::for_policy(&policy, &repos);
+    if !args.check_only && !wanted.is_empty() {
+        let _lock = lock::acquire(force)?;
+        let actor = github::get_authenticated_user(pat_token).await?;
+        let unfixable = report
+            .violations
+            .iter()
+            .filter(|entry| entry.reason == Some(SkipReason::NoAdmin))
+            .cloned()
+            .collect::<Vec<_>>();
+        report.violations = fix(&wanted, pat_token, &actor).await;
+        report.violations.extend(unfixable);
+    }
+    report.add_alerts(pat_token).await;
+
+    std::fs::write(
+        &args.report,
+        serde_json::to_string_pretty(&schema::versioned(&report))?,
+    )
+    .with_context(|| format!("Failed to write the report {:?}", args.report))?;
+    for entry in report.violations.iter().chain(&report.skipped) {
+        let status = style(entry.describe());
+        let status = match entry.status {
+            Status::Violation => status.yellow(),
+            Status::Changed => status.green(),
+            Status::AlreadySet | Status::Skipped => status.dim(),
+            Status::Failed => status.red(),
+        };
+        match entry.alerts.describe() {
+            Some(alerts) => println!(
+                "  {status} {} ({}, {})",
+                entry.full_name,
+                entry.visibility,
+                style(alerts).yellow()
+            ),
+            None => println!("  {status} {} ({})", entry.full_name, entry.visibility),
+        }
+    }
+
+    if args.check_only {
+        notify::send(&notify::Summary {
+            violations: report
+                .violations
+                .iter()
+                .map(|entry| entry.full_name.clone())
+                .collect(),
+            ..notify::Summary::default()
+        })
+        .await;
+    }
+    let violations = report.count(Status::Violation);
+    let failed = report.count(Status::Failed);
+    let summary = t!(
+        "enforce-summary",
+        violations = violations,
+        checked = report.checked,
+        unfixable = report.unfixable(),
+        changed = report.count(Status::Changed),
+        skipped = report.count(Status::Skipped),
+        failed = failed,
+        path = format!("{:?}", args.report)
+    );
+    match (violations, failed) {
+        (0, 0) => {
+            if !args.check_only {
+                heartbeat::beat("enforce")?;
+            }
+            println!("{SUCCESS_ICON} {summary}");
+            Ok(())
+        }
+        (_, 0) => Err(Exit {
+            code: exit::POLICY_VIOLATIONS,
+            message: summary,
+        }
+        .into()),
+        _ => Err(Exit {
+            code: exit::PARTIAL_FAILURE,
+            message: summary,
+        }
+        .into()),
+    }
+}
@@ -0,0 +1,347 @@
+//! Progress of listings, changes and clones as events, shown by a pluggable frontend.
+//!
+//! Listing and applying only [`emit`] what happened; how it is shown is up to the [`Frontend`]
+//! in scope. `--progress` picks plain lines, JSON lines or progress bars for the command, and the
+//! TUI collects the events of its changes over a channel to draw them itself.
+
+use crate::i18n::t;
+use crate::output::{self, progress, status};
+use crate::plan::SkipReason;
+use crate::{schema, Result, SUCCESS_ICON};
+use clap::ValueEnum;
+use console::style;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Something that happened while listing or changing repositories.
+///
+/// Repositories are named `owner/name`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum Event {
+    /// Another page of the repository listing arrived.
+    RepoFetched { pages: usize, fetched: usize },
+    /// The repository listing is complete.
+    ReposListed { total: usize },
+    /// A batch of `total` changes starts.
+    ApplyStarted { total: usize },
+    /// A change waits for its turn.
+    ChangeQueued { repo: String },
+    /// A change reached `step`: `"checking"`, `"scanning history"`, `"backing up"`,
+    /// `"patching"` or `"verifying"`, or `"editing"` for the other settings changed from the
+    /// terminal UI.
+    ChangeProgress { repo: String, step: &'static str },
+    /// The repository was changed, or already had the visibility wanted if `already`.
+    ChangeApplied {
+        repo: String,
+        private: bool,
+        already: bool,
+    },
+    /// The repository was left alone for `reason`, such as having changed since it was planned.
+    ChangeSkipped { repo: String, reason: SkipReason },
+    /// Changing the repository failed.
+    ChangeFailed { repo: String, error: String },
+    /// The change was never attempted, because the batch was interrupted.
+    ChangeNotStarted { repo: String },
+    /// The batch was interrupted; the changes in flight still finish.
+    Interrupted,
+    /// Every change of the batch finished, failed or was not started.
+    ApplyFinished,
+    /// GitHub rejected a request for the rate limit; it is sent again after the wait.
+    RateLimited { retry_in_secs: u64 },
+    /// `git clone` of the repository started.
+    CloneStarted { repo: String },
+    /// The repository was cloned, or skipped if `skipped` because its directory already exists.
+    CloneFinished { repo: String, skipped: bool },
+    /// Cloning the repository failed.
+    CloneFailed { repo: String, error: String },
+}
+
+/// Shows events as they happen.
+pub(crate) trait Frontend: Send + Sync {
+    fn handle(&self, event: &Event);
+
+    /// Runs `f`, such as a prompt, with nothing drawn meanwhile.
+    fn suspend(&self, f: &mut dyn FnMut()) {
+        f()
+    }
+}
+
+/// How the command shows its progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub(crate) enum ProgressFormat {
+    /// Progress bars on a terminal, plain lines otherwise.
+    #[default]
+    Auto,
+    /// One line of text per repository.
+    Plain,
+    /// One JSON object per event on standard error.
+    Json,
+}
+
+tokio::task_local! {
+    static FRONTEND: Arc<dyn Frontend>;
+}
+
+static DEFAULT: OnceLock<Arc<dyn Frontend>> = OnceLock::new();
+
+/// Picks the frontend of this run; called once after [`output::init`].
+pub(crate) fn init(format: ProgressFormat) -> Result<()> {
+    let frontend: Arc<dyn Frontend> = match format {
+        ProgressFormat::Json => Arc::new(JsonLines),
+        ProgressFormat::Plain => Arc::new(Plain),
+        ProgressFormat::Auto => {
+            match console::Term::stderr().is_term() && !output::is_quiet() && !output::is_plain() {
+                true => Arc::new(Bars::new()?),
+                false => Arc::new(Plain),
+            }
+        }
+    };
+    let _ = DEFAULT.set(frontend);
+
+    Ok(())
+}
+
+/// Runs `future` with its events shown by `frontend` instead of the one of the run.
+pub(crate) async fn with_frontend<F: Future>(frontend: Arc<dyn Frontend>, future: F) -> F::Output {
+    FRONTEND.scope(frontend, future).await
+}
+
+/// The frontend in scope, to hand on to spawned tasks.
+pub(crate) fn frontend() -> Arc<dyn Frontend> {
+    FRONTEND
+        .try_with(Arc::clone)
+        .unwrap_or_else(|_| DEFAULT.get().cloned().unwrap_or_else(|| Arc::new(Plain)))
+}
+
+/// Shows `event` with the frontend in scope.
+pub(crate) fn emit(event: Event) {
+    frontend().handle(&event);
+}
+
+/// Prints a line per finished repository, honoring `--quiet`.
+pub(crate) struct Plain;
+
+impl Frontend for Plain {
+    fn handle(&self, event: &Event) {
+        match event {
+            Event::ReposListed { total } => {
+                progress!("{SUCCESS_ICON} {}", t!("repos-listed", total = *total))
+            }
+            Event::ChangeApplied {
+                repo,
+                private,
+                already,
+            } => match already {
+                true => status!("{repo}: {}", crate::apply::already(*private)),
+                false => status!("{repo}: {}", t!("change-done")),
+            },
+            Event::ChangeSkipped { repo, reason } => status!(
+                "{repo}: {}",
+                t!("change-skipped", reason = reason.describe())
+            ),
+            Event::ChangeFailed { repo, error } => {
+                status!("{repo}: {}", t!("change-failed", error = error.as_str()))
+            }
+            Event::Interrupted => status!("{}", t!("interrupted")),
+            Event::RateLimited { retry_in_secs } => {
+                status!("{}", t!("rate-limited", seconds = *retry_in_secs))
+            }
+            Event::CloneFinished { repo, skipped } => match skipped {
+                true => status!("{repo}: {}", t!("already-cloned")),
+                false => status!("{repo}: {}", t!("cloned")),
+            },
+            Event::CloneFailed { repo, error } => {
+                status!("{repo}: {}", t!("change-failed", error = error.as_str()))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Writes every event as a JSON object on its own line of standard error, for other programs.
+pub(crate) struct JsonLines;
+
+impl Frontend for JsonLines {
+    fn handle(&self, event: &Event) {
+        if let Ok(line) = serde_json::to_string(&schema::versioned(event)) {
+            let _ = writeln!(std::io::stderr().lock(), "{line}");
+        }
+    }
+}
+
+/// Sends events to a receiver that shows them, such as the TUI.
+impl Frontend for mpsc::UnboundedSender<Event> {
+    fn handle(&self, event: &Event) {
+        let _ = self.send(event.clone());
+    }
+}
+
+#[derive(Default)]
+struct BarsState {
+    listing: Option<ProgressBar>,
+    overall: Option<ProgressBar>,
+    repos: HashMap<String, ProgressBar>,
+}
+
+/// A spinner while listing, and while applying an overall bar plus one line per repository
+/// moving through `queued → checking → patching → verifying → done/failed`.
+pub(crate) struct Bars {
+    multi: MultiProgress,
+    spinner: ProgressStyle,
+    overall: ProgressStyle,
+    repo: ProgressStyle,
+    state: Mutex<BarsState>,
+}
+
+impl Bars {
+    pub(crate) fn new() -> Result<Self> {
+        let multi = match output::is_quiet() {
+            true => MultiProgress::with_draw_target(ProgressDrawTarget::hidden()),
+            false => MultiProgress::new(),
+        };
+
+        Ok(Self {
+            multi,
+            spinner: ProgressStyle::default_spinner()
+                .tick_chars("/|\\- ")
+                .template("{spinner:.green} {msg}")?,
+            overall: ProgressStyle::with_template("{bar:30.green/dim} {pos}/{len} repos")?,
+            repo: ProgressStyle::with_template("  {spinner:.green} {prefix:30} {msg}")?
+                .tick_chars("/|\\- "),
+            state: Mutex::new(BarsState::default()),
+        })
+    }
+
+    /// Finishes the line of `repo` with `message`, counting it on the overall bar.
+    fn finish(&self, state: &mut BarsState, repo: &str, message: String) {
+        if let Some(bar) = state.repos.get(repo) {
+            bar.finish_with_message(message);
+        }
+        if let Some(overall) = &state.overall {
+            overall.inc(1);
+        }
+    }
+}
+
+impl Frontend for Bars {
+    fn suspend(&self, f: &mut dyn FnMut()) {
+        self.multi.suspend(f)
+    }
+
+    fn handle(&self, event: &Event) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        match event {
+            Event::RepoFetched { pages, fetched } => {
+                let listing = state.listing.get_or_insert_with(|| {
+                    let bar = self.multi.add(ProgressBar::new_spinner());
+                    bar.set_style(self.spinner.clone());
+                    bar
+                });
+                listing.inc(1);
+                listing.set_message(t!("fetching-page", fetched = *fetched, page = pages + 1));
+            }
+            Event::ReposListed { total } => {
+                if let Some(listing) = state.listing.take() {
+                    listing
+                        .finish_with_message(format!("{SUCCESS_ICON} {}", t!("all-repos-fetched")));
+                }
+                progress!("{SUCCESS_ICON} {}", t!("repos-listed", total = *total));
+            }
+            Event::ApplyStarted { total } => {
+                let overall = self.multi.add(ProgressBar::new(*total as u64));
+                overall.set_style(self.overall.clone());
+                state.overall = Some(overall);
+            }
+            Event::ChangeQueued { repo } => {
+                let bar = self.multi.add(ProgressBar::new_spinner());
+                bar.set_style(self.repo.clone());
+                bar.set_prefix(repo.clone());
+                bar.set_message(style(t!("change-queued")).dim().to_string());
+                state.repos.insert(repo.clone(), bar);
+            }
+            Event::ChangeProgress { repo, step } => {
+                if let Some(bar) = state.repos.get(repo) {
+                    bar.enable_steady_tick(Duration::from_millis(100));
+                    bar.set_message(crate::apply::describe_step(step));
+                }
+            }
+            Event::ChangeApplied {
+                repo,
+                private,
+                already,
+            } => {
+                let message = match already {
+                    true => style(crate::apply::already(*private)).dim().to_string(),
+                    false => style(t!("change-done")).green().to_string(),
+                };
+                self.finish(&mut state, repo, message);
+            }
+            Event::ChangeSkipped { repo, reason } => {
+                let message = style(t!("change-skipped", reason = reason.describe()))
+                    .dim()
+                    .to_string();
+                self.finish(&mut state, repo, message);
+            }
+            Event::ChangeFailed { repo, error } => {
+                let message = style(t!("change-failed", error = error.as_str()))
+                    .red()
+                    .to_string();
+                self.finish(&mut state, repo, message);
+            }
+            Event::ChangeNotStarted { repo } => {
+                if let Some(bar) = state.repos.get(repo) {
+                    bar.finish_with_message(style(t!("change-not-started")).dim().to_string());
+                }
+            }
+            Event::Interrupted => {
+                let _ = self
+                    .multi
+                    .println(style(t!("interrupted")).yellow().to_string());
+            }
+            Event::ApplyFinished => {
+                if let Some(overall) = state.overall.take() {
+                    overall.finish();
+                }
+                state.repos.clear();
+            }
+            Event::RateLimited { retry_in_secs } => {
+                let _ = self
+                    .multi
+                    .println(t!("rate-limited", seconds = *retry_in_secs));
+            }
+            Event::CloneStarted { repo } => {
+                let bar = self.multi.add(ProgressBar::new_spinner());
+                bar.set_style(self.repo.clone());
+                bar.set_prefix(repo.clone());
+                bar.set_message(t!("cloning"));
+                bar.enable_steady_tick(Duration::from_millis(100));
+                state.repos.insert(repo.clone(), bar);
+            }
+            Event::CloneFinished { repo, skipped } => {
+                let message = match skipped {
+                    true => style(t!("already-cloned")).dim().to_string(),
+                    false => style(t!("cloned")).green().to_string(),
+                };
+                if let Some(bar) = state.repos.remove(repo) {
+                    bar.finish_with_message(message);
+                }
+            }
+            Event::CloneFailed { repo, error } => {
+                if let Some(bar) = state.repos.remove(repo) {
+                    bar.finish_with_message(
+                        style(t!("change-failed", error = error.as_str()))
+                            .red()
+                            .to_string(),
+                    );
+                }
+            }
+        }
+    }
+}
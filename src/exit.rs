@@ -0,0 +1,32 @@
+//! Process exit codes beyond success (`0`) and a general error (`1`).
+//!
+//! Scripts can tell a run that changed some repositories but not all apart from one that changed
+//! nothing, by returning an [`Exit`] error from deep inside a command.
+
+use std::fmt;
+
+/// Not every repository was changed, because of failures or an interruption.
+pub(crate) const PARTIAL_FAILURE: u8 = 2;
+
+/// `privateer enforce --check-only` found repositories that violate the policy.
+pub(crate) const POLICY_VIOLATIONS: u8 = 3;
+
+/// An error that makes privateer exit with `code` instead of `1`.
+#[derive(Debug)]
+pub(crate) struct Exit {
+    pub code: u8,
+    pub message: String,
+}
+
+impl fmt::Display for Exit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Exit {}
+
+/// The exit code for `err`: its own if it is an [`Exit`], otherwise `1`.
+pub(crate) fn code(err: &anyhow::Error) -> u8 {
+    err.downcast_ref::<Exit>().map_or(1, |exit| exit.code)
+}
@@ -0,0 +1,115 @@
+//! Shared formatting of values for terminal output.
+//!
+//! Dates, such as when a repository was last pushed to, read as the time since by default, in
+//! the list, the selection and the terminal UI alike, in the language of the run;
+//! `--absolute-dates` shows timestamps instead.
+
+use crate::i18n::t;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ABSOLUTE_DATES: AtomicBool = AtomicBool::new(false);
+
+/// Shows dates as ISO 8601 timestamps instead of the time since, for `--absolute-dates`.
+pub(crate) fn configure(absolute_dates: bool) {
+    ABSOLUTE_DATES.store(absolute_dates, Ordering::Relaxed);
+}
+
+/// Formats `then` as the time since, as of `now`, or as a timestamp with `--absolute-dates`.
+pub(crate) fn date(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    match ABSOLUTE_DATES.load(Ordering::Relaxed) {
+        true => absolute_date(then),
+        false => relative_time(then, now),
+    }
+}
+
+/// Formats `then` as an ISO 8601 timestamp in UTC, e.g. `"2023-05-11T09:30:00Z"`.
+pub(crate) fn absolute_date(then: DateTime<Utc>) -> String {
+    then.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Formats the time from `then` to `now` in its largest whole unit in the language of the run,
+/// e.g. `"3 weeks ago"`, `"1 year ago"`, or `"in 2 days"` for a `then` after `now`. Less than a
+/// minute either way is `"just now"`.
+pub(crate) fn relative_time(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - then).num_seconds();
+    if seconds.abs() < 60 {
+        return t!("time-just-now");
+    }
+    let time = match seconds.abs() {
+        s if s < 3_600 => t!("time-minutes", count = s / 60),
+        s if s < 86_400 => t!("time-hours", count = s / 3_600),
+        s if s < 7 * 86_400 => t!("time-days", count = s / 86_400),
+        s if s < 30 * 86_400 => t!("time-weeks", count = s / (7 * 86_400)),
+        s if s < 365 * 86_400 => t!("time-months", count = s / (30 * 86_400)),
+        s => t!("time-years", count = s / (365 * 86_400)),
+    };
+
+    match seconds > 0 {
+        true => t!("time-ago", time = time),
+        false => t!("time-in", time = time),
+    }
+}
+
+/// The width of the widest date [`date`] formats, to size the columns showing them.
+pub(crate) fn date_width() -> usize {
+    let now = DateTime::<Utc>::UNIX_EPOCH;
+    if ABSOLUTE_DATES.load(Ordering::Relaxed) {
+        return absolute_date(now).len();
+    }
+    // The first and the largest count of each unit, either way.
+    const DAY: i64 = 86_400;
+    [
+        0,
+        60,
+        59 * 60,
+        3_600,
+        23 * 3_600,
+        DAY,
+        6 * DAY,
+        7 * DAY,
+        4 * 7 * DAY,
+        30 * DAY,
+        12 * 30 * DAY,
+        365 * DAY,
+        99 * 365 * DAY,
+    ]
+    .into_iter()
+    .flat_map(|seconds| [seconds, -seconds])
+    .map(|seconds| {
+        let then = now - chrono::Duration::seconds(seconds);
+        console::measure_text_width(&relative_time(then, now))
+    })
+    .max()
+    .unwrap_or_default()
+}
+
+/// Formats `n` with `,` thousands separators, e.g. `"1,820"`.
+pub(crate) fn thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+
+    formatted
+}
+
+/// Formats a size GitHub reports in kilobytes with the largest fitting unit, e.g. `"1.2 GB"`.
+pub(crate) fn size(kb: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    let mut value = kb as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{kb} KB"),
+        _ => format!("{value:.1} {}", UNITS[unit]),
+    }
+}
@@ -0,0 +1,1290 @@
+//! Requests to the GitHub REST API.
+
+use crate::cli::{SortKey, SortOrder};
+use crate::events::{self, Event};
+use crate::output::verbose;
+use crate::pagination::{self, Paginator};
+use crate::transport::{self, HttpRequest, HttpResponse};
+use crate::{lists, ratelimit, urls};
+use crate::{Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use reqwest::header::{self, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use url::Url;
+
+/// Redirects followed before giving up on a request.
+const MAX_REDIRECTS: usize = 5;
+
+/// Header naming the REST API version a request is written against.
+const API_VERSION: &str = "x-github-api-version";
+
+/// A repository as GitHub reports it, limited to the fields privateer uses.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct Repository {
+    /// Identifier of the repository, which survives renames and transfers; `0` if unknown.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub id: u64,
+    /// Global identifier of the repository in GraphQL, which survives renames and transfers too.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub node_id: String,
+    /// Name of the repository, without the owner.
+    pub name: String,
+    /// `owner/name`, which changes when the repository is renamed or transferred.
+    #[serde(default)]
+    pub full_name: String,
+    /// API URL of the repository, replaced by the web page URL for display.
+    pub url: String,
+    /// Web page of the repository.
+    #[serde(default)]
+    pub html_url: String,
+    /// HTTPS URL to clone the repository from.
+    #[serde(default)]
+    pub clone_url: String,
+    /// Whether the repository is private, if known.
+    #[serde(rename = "private", skip_serializing_if = "Option::is_none")]
+    pub private: Option<bool>,
+    /// Whether the repository is a fork.
+    #[serde(default)]
+    pub fork: bool,
+    /// Whether the repository is archived, and so read-only.
+    #[serde(default)]
+    pub archived: bool,
+    /// When something was last pushed to the repository.
+    #[serde(default)]
+    pub pushed_at: Option<DateTime<Utc>>,
+    /// When the repository was created.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Number of stars.
+    #[serde(default)]
+    pub stargazers_count: u32,
+    /// The user or organization owning the repository.
+    #[serde(default)]
+    pub owner: Owner,
+    /// Short description, if any.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Topics the repository is tagged with.
+    #[serde(default)]
+    pub topics: Vec<String>,
+    /// Whether issues are enabled.
+    #[serde(default)]
+    pub has_issues: bool,
+    /// Whether projects are enabled.
+    #[serde(default)]
+    pub has_projects: bool,
+    /// Whether the wiki is enabled.
+    #[serde(default)]
+    pub has_wiki: bool,
+    /// Whether the repository may be forked, if GitHub reported it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_forking: Option<bool>,
+    /// Security features, reported to administrators only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_and_analysis: Option<SecurityAndAnalysis>,
+    /// The license GitHub found in the repository, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<License>,
+    /// Number of forks.
+    #[serde(default)]
+    pub forks_count: u32,
+    /// Number of open issues and pull requests.
+    #[serde(default)]
+    pub open_issues_count: u32,
+    /// Name of the default branch, if any.
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// Size in kilobytes.
+    #[serde(default)]
+    pub size: u64,
+    /// Main language of the repository as GitHub detected it, if any.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// The repository this one was forked from; only included when fetched individually.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Parent>,
+    /// What the token's user may do with the repository, if GitHub reported it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Permissions>,
+}
+
+/// What the token's user may do with a repository.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Permissions {
+    /// May change settings, including the visibility.
+    #[serde(default)]
+    pub admin: bool,
+    /// May push commits.
+    #[serde(default)]
+    pub push: bool,
+    /// May read the repository.
+    #[serde(default)]
+    pub pull: bool,
+}
+
+fn is_zero(id: &u64) -> bool {
+    *id == 0
+}
+
+impl Repository {
+    /// Returns `true` if `other` is this repository, known by its id, or `None` if either id is
+    /// unknown.
+    pub(crate) fn same_as(&self, other: &Repository) -> Option<bool> {
+        match (self.id, other.id) {
+            (0, _) | (_, 0) => None,
+            (id, other) => Some(id == other),
+        }
+    }
+
+    /// Returns `true` if nothing was ever pushed to the repository.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// How `login` accesses the repository. Listings don't tell a collaborator of an
+    /// organization's repository from a team member, so both count as [`Access::Team`].
+    pub(crate) fn access(&self, login: &str) -> Access {
+        match (
+            self.owner.login.is_empty() || self.owner.login.eq_ignore_ascii_case(login),
+            self.owner.kind == "Organization",
+        ) {
+            (true, _) => Access::Owner,
+            (false, true) => Access::Team,
+            (false, false) => Access::Collaborator,
+        }
+    }
+
+    /// Returns `true` unless GitHub reported the user lacks admin access, which changing the
+    /// visibility requires.
+    pub fn can_administer(&self) -> bool {
+        self.permissions
+            .as_ref()
+            .is_none_or(|permissions| permissions.admin)
+    }
+}
+
+/// Security features of a repository; those GitHub didn't report are `None`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SecurityAndAnalysis {
+    /// Secret scanning, which reports credentials found in the repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_scanning: Option<FeatureStatus>,
+    /// Push protection, which rejects pushes containing credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_scanning_push_protection: Option<FeatureStatus>,
+}
+
+/// Whether a security feature is on, as GitHub writes it: `{"status": "enabled"}`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FeatureStatus {
+    /// `enabled` or `disabled`.
+    pub status: String,
+}
+
+impl FeatureStatus {
+    /// The status of a feature turned on if `enabled`, or off.
+    pub fn new(enabled: bool) -> Self {
+        let status = match enabled {
+            true => "enabled",
+            false => "disabled",
+        };
+        Self {
+            status: status.to_owned(),
+        }
+    }
+
+    /// Returns `true` if the feature is on.
+    pub fn is_enabled(&self) -> bool {
+        self.status == "enabled"
+    }
+}
+
+/// A license GitHub recognized in a repository.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct License {
+    /// Name of the license, such as `MIT License`, or `Other` if it wasn't recognized.
+    pub name: String,
+    /// SPDX identifier of the license, such as `MIT`, or `NOASSERTION` if it wasn't recognized.
+    #[serde(default)]
+    pub spdx_id: Option<String>,
+}
+
+/// A secret, such as a credential, that secret scanning found in a repository.
+///
+/// [See docs] https://docs.github.com/en/rest/secret-scanning/secret-scanning?apiVersion=2022-11-28#list-secret-scanning-alerts-for-a-repository
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub(crate) struct SecretAlert {
+    pub number: u64,
+    /// Kind of secret, such as `GitHub Personal Access Token`.
+    #[serde(default)]
+    pub secret_type_display_name: Option<String>,
+    #[serde(default)]
+    pub html_url: String,
+}
+
+/// Whether GitHub Actions run in a repository, and which of them.
+///
+/// [See docs] https://docs.github.com/en/rest/actions/permissions?apiVersion=2022-11-28#get-github-actions-permissions-for-a-repository
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub(crate) struct ActionsPermissions {
+    pub enabled: bool,
+    /// `all`, `local_only` or `selected`, if enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_actions: Option<String>,
+}
+
+impl ActionsPermissions {
+    /// `disabled`, or which actions are allowed, such as `local_only`.
+    pub(crate) fn describe(&self) -> &str {
+        match (self.enabled, &self.allowed_actions) {
+            (false, _) => "disabled",
+            (true, Some(allowed)) => allowed,
+            (true, None) => "all",
+        }
+    }
+}
+
+/// The repository a fork was made from.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Parent {
+    /// `owner/name` of the parent repository.
+    pub full_name: String,
+}
+
+/// The user or organization owning a repository.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Owner {
+    /// Login of the owner.
+    pub login: String,
+    /// `User` or `Organization`, if GitHub reported it.
+    #[serde(default, rename = "type", skip_serializing_if = "String::is_empty")]
+    pub kind: String,
+}
+
+/// How the token's user came to access a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Access {
+    Owner,
+    /// Invited to a repository of another user, or of an organization.
+    Collaborator,
+    /// Through membership of the organization owning the repository, usually one of its teams.
+    Team,
+}
+
+impl Access {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Access::Owner => "owner",
+            Access::Collaborator => "collaborator",
+            Access::Team => "team",
+        }
+    }
+}
+
+/// The body of a request to update a repository. Fields left `None` are left unchanged.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#update-a-repository
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct UpdateRepository {
+    /// New name of the repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Whether the repository should be private.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private: Option<bool>,
+    /// New short description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the repository should be archived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+    /// Whether issues should be enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_issues: Option<bool>,
+    /// Whether projects should be enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_projects: Option<bool>,
+    /// Whether the wiki should be enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_wiki: Option<bool>,
+    /// Whether the repository may be forked; only organizations' repositories can forbid it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_forking: Option<bool>,
+    /// Security features to turn on or off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_and_analysis: Option<SecurityAndAnalysis>,
+}
+
+/// The body of a request to create a repository.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#create-a-repository-for-the-authenticated-user
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub(crate) struct NewRepository {
+    pub name: String,
+    pub private: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// The body of a request to replace the topics of a repository.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#replace-all-repository-topics
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(crate) struct ReplaceTopics<'a> {
+    pub names: &'a [String],
+}
+
+/// The body GitHub answers a failed request with.
+///
+/// [See docs] https://docs.github.com/en/rest/using-the-rest-api/troubleshooting-the-rest-api
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ErrorResponse {
+    /// What went wrong, such as `Not Found`.
+    pub message: String,
+    /// Page of the documentation about the endpoint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub documentation_url: Option<String>,
+    /// Details of validation failures.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ErrorDetail>,
+}
+
+/// One validation failure of an [`ErrorResponse`].
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ErrorDetail {
+    /// Kind of object that failed validation, such as `Repository`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// Field that failed validation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Why the field failed, such as `already_exists`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Explanation of a `custom` code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl std::fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        for error in &self.errors {
+            let detail = match (&error.message, &error.field, &error.code) {
+                (Some(message), _, _) => message.clone(),
+                (None, Some(field), Some(code)) => format!("`{field}` {code}"),
+                (None, _, Some(code)) => code.clone(),
+                (None, _, None) => continue,
+            };
+            write!(f, "; {detail}")?;
+        }
+        if let Some(url) = &self.documentation_url {
+            write!(f, " (see {url})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An organization the token's user belongs to.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Organization {
+    /// Login of the organization.
+    pub login: String,
+    /// Short description, if any.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// An event of an organization's audit log, limited to the fields privateer uses.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub(crate) struct AuditLogEntry {
+    #[serde(rename = "_document_id")]
+    pub id: String,
+    /// Such as `repo.access` for a change of visibility.
+    pub action: String,
+    #[serde(default)]
+    pub actor: Option<String>,
+    /// `owner/name` of the repository the event is about, if any.
+    #[serde(default)]
+    pub repo: Option<String>,
+    /// The visibility a `repo.access` event changed the repository to.
+    #[serde(default)]
+    pub visibility: Option<String>,
+    /// When the event happened, in milliseconds since the Unix epoch.
+    #[serde(rename = "@timestamp")]
+    pub timestamp: i64,
+}
+
+/// A user with access to a repository.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Collaborator {
+    /// Login of the user.
+    pub login: String,
+    /// What the user may do with the repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Permissions>,
+}
+
+/// A gist of the token's user.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Gist {
+    /// Identifier of the gist.
+    pub id: String,
+    /// Web page of the gist.
+    #[serde(default)]
+    pub html_url: String,
+    /// Whether the gist is listed publicly; secret gists are not.
+    #[serde(default)]
+    pub public: bool,
+    /// Short description, if any.
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// The login of a user.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub(crate) struct User {
+    pub login: String,
+}
+
+/// Requests of one kind left until the quota resets.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub(crate) struct Quota {
+    pub limit: u64,
+    pub used: u64,
+    pub remaining: u64,
+    /// When the quota resets, in seconds since the Unix epoch.
+    pub reset: i64,
+}
+
+/// The quotas of the REST and the GraphQL API.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub(crate) struct RateLimits {
+    pub core: Quota,
+    /// Missing on GitHub Enterprise Servers without GraphQL rate limiting.
+    pub graphql: Option<Quota>,
+    /// The time of the server when it sent the quotas, to measure their resets against instead
+    /// of the local clock.
+    #[serde(skip)]
+    pub server_now: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct RateLimitResponse {
+    pub resources: RateLimits,
+}
+
+/// What went wrong with the failed `response`: GitHub's message if it sent an
+/// [`ErrorResponse`], and the status otherwise, followed by its `X-GitHub-Request-Id` to quote
+/// to GitHub Support.
+pub(crate) fn api_error(response: &HttpResponse) -> String {
+    let error = match serde_json::from_str::<ErrorResponse>(&response.body) {
+        Ok(error) => error.to_string(),
+        Err(_) => response.status.to_string(),
+    };
+    match response.header_str("x-github-request-id") {
+        Some(request_id) => format!("{error} (request ID `{request_id}`)"),
+        None => error,
+    }
+}
+
+/// Lists the organizations the token's user belongs to.
+///
+/// [See docs] https://docs.github.com/en/rest/orgs/orgs?apiVersion=2022-11-28#list-organizations-for-the-authenticated-user
+pub(crate) async fn list_orgs(pat_token: &str) -> Result<Vec<Organization>> {
+    let url = urls::host().api_url(&["user", "orgs"]);
+    Paginator::new(url, pat_token).collect(|_, _| {}).await
+}
+
+/// Lists the repositories the team `team` of `org` has access to, ordered by `sort` in `order`.
+///
+/// [See docs] https://docs.github.com/en/rest/teams/teams?apiVersion=2022-11-28#list-team-repositories
+pub(crate) async fn get_team_repos(
+    org: &str,
+    team: &str,
+    pat_token: &str,
+    sort: SortKey,
+    order: SortOrder,
+) -> Result<Vec<Repository>> {
+    let url = urls::host().api_url(&["orgs", org, "teams", team, "repos"]);
+    let mut repositories = Paginator::<Repository>::new(url, pat_token)
+        .collect(|paginator, fetched| {
+            events::emit(Event::RepoFetched {
+                pages: paginator.pages(),
+                fetched,
+            })
+        })
+        .await
+        .with_context(|| {
+            format!("{ERROR_ICON} Failed to list the repositories of the team `{org}/{team}`. Hint! Use the team's name as in its web page, and a token with the `read:org` scope")
+        })?;
+    lists::retain(&mut repositories);
+    events::emit(Event::ReposListed {
+        total: repositories.len(),
+    });
+    sort_repos(&mut repositories, sort, order);
+
+    Ok(repositories)
+}
+
+/// Lists the collaborators of the repository `owner/name`.
+///
+/// [See docs] https://docs.github.com/en/rest/collaborators/collaborators?apiVersion=2022-11-28#list-repository-collaborators
+pub(crate) async fn list_collaborators(
+    owner: &str,
+    name: &str,
+    pat_token: &str,
+) -> Result<Vec<Collaborator>> {
+    let url = urls::host().api_url(&["repos", owner, name, "collaborators"]);
+    Paginator::new(url, pat_token).collect(|_, _| {}).await
+}
+
+/// Lists the gists of the token's user.
+///
+/// [See docs] https://docs.github.com/en/rest/gists/gists?apiVersion=2022-11-28#list-gists-for-the-authenticated-user
+pub(crate) async fn list_gists(pat_token: &str) -> Result<Vec<Gist>> {
+    let url = urls::host().api_url(&["gists"]);
+    Paginator::new(url, pat_token).collect(|_, _| {}).await
+}
+
+/// A `method` request to the API at `url`, authenticated with `pat_token`, with the headers and
+/// timeout of the current client.
+pub(crate) fn api_request(method: Method, url: Url, pat_token: &str) -> Result<HttpRequest> {
+    let settings = transport::settings();
+    let mut request = HttpRequest::new(method, url)
+        .header(
+            header::ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        )
+        .header(
+            header::USER_AGENT,
+            HeaderValue::from_str(&settings.user_agent)?,
+        )
+        .header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", pat_token))?,
+        );
+    if let Some(version) = &settings.api_version {
+        request = request.header(
+            HeaderName::from_static(API_VERSION),
+            HeaderValue::from_str(version)?,
+        );
+    }
+    request.timeout = settings.timeout;
+
+    Ok(request)
+}
+
+/// Sends `request`, following redirects with the same method: a renamed repository answers with
+/// `301 Moved Permanently`, and a redirected POST must not turn into a GET. The token is only
+/// sent on to the host it was meant for, and at most [`MAX_REDIRECTS`] redirects are followed.
+///
+/// Anything but a `GET` drops the cached copy of the repository it was sent to, even if it
+/// failed, since the change may have been made anyway.
+pub(crate) async fn send(request: HttpRequest) -> Result<HttpResponse> {
+    let (method, url) = (request.method.clone(), request.url.clone());
+    let result = send_following(request).await;
+    if method != Method::GET {
+        if let Some(cache) = &transport::settings().cache {
+            cache.invalidate(&url);
+        }
+    }
+
+    result
+}
+
+async fn send_following(mut request: HttpRequest) -> Result<HttpResponse> {
+    for _ in 0..MAX_REDIRECTS {
+        let response = ratelimit::send(request.clone()).await?;
+        if is_unsupported_version(&response) {
+            return Err(unsupported_version(&request, &response).await);
+        }
+        let location = match response.status.is_redirection() {
+            true => response.header_str("location"),
+            false => None,
+        };
+        let Some(location) = location else {
+            return Ok(response);
+        };
+        let next = response.url.join(location)?;
+        if next.origin() != request.url.origin() {
+            verbose!(
+                "Not sending the token on to {next}, another host than {}",
+                request.url
+            );
+            request.headers.remove(header::AUTHORIZATION);
+        }
+        request.url = next;
+    }
+
+    Err(anyhow!(
+        "{ERROR_ICON} Too many redirects for {url}",
+        url = request.url
+    ))
+}
+
+/// Returns `true` if GitHub rejected the `X-GitHub-Api-Version` of a request, as a GitHub
+/// Enterprise Server does with versions newer than its release.
+pub(crate) fn is_unsupported_version(response: &HttpResponse) -> bool {
+    response.status == StatusCode::BAD_REQUEST
+        && response
+            .body
+            .to_ascii_lowercase()
+            .contains("x-github-api-version")
+}
+
+/// The error for a `request` whose API version the server rejected, with the versions it
+/// supports if it lists them.
+async fn unsupported_version(request: &HttpRequest, response: &HttpResponse) -> anyhow::Error {
+    let version = request
+        .headers
+        .get(API_VERSION)
+        .and_then(|version| version.to_str().ok())
+        .unwrap_or("none");
+    let api = urls::host().api;
+    let hint = match supported_versions(request).await {
+        Some(versions) if !versions.is_empty() => format!(
+            "Pass `--api-version` with one it supports: {}",
+            versions
+                .iter()
+                .map(|version| format!("`{version}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => "Pass an older `--api-version`, or `--api-version none` for a GitHub Enterprise Server that predates API versions".to_owned(),
+    };
+
+    anyhow!(
+        "{ERROR_ICON} {api} doesn't support the REST API version `{version}`: {err}. Hint! {hint}",
+        err = api_error(response)
+    )
+}
+
+/// The API versions the server supports, newest first, or `None` if it doesn't list them.
+///
+/// [See docs] https://docs.github.com/en/rest/meta/meta?apiVersion=2022-11-28#get-all-api-versions
+async fn supported_versions(request: &HttpRequest) -> Option<Vec<String>> {
+    let mut versions = request.clone();
+    versions.method = Method::GET;
+    versions.url = urls::host().api_url(&["versions"]);
+    versions.body = None;
+    versions.headers.remove(API_VERSION);
+    let response = ratelimit::send(versions).await.ok()?;
+    if !response.status.is_success() {
+        return None;
+    }
+    let mut versions: Vec<String> = serde_json::from_str(&response.body).ok()?;
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+
+    Some(versions)
+}
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#list-repositories-for-the-authenticated-user
+///
+/// ```text
+/// $ curl -L \
+///  -H "Accept: application/vnd.github+json" \
+///  -H "Authorization: Bearer <YOUR-TOKEN>"\
+///  -H "X-GitHub-Api-Version: 2022-11-28" \
+/// https://api.github.com/user/repos
+/// ```
+///
+/// The `visibility` parameter can have one of the following values: `all | public | private | internal`
+///
+/// Repositories are requested and returned ordered by `sort` in `order`. GitHub can't sort by
+/// stars, so those are sorted after fetching.
+pub(crate) async fn get_repos_request(
+    _username: &str,
+    pat_token: &str,
+    sort: SortKey,
+    order: SortOrder,
+) -> Result<Vec<Repository>> {
+    let mut params = vec![
+        // Repositories reached through an organization or one of its teams are included too.
+        ("affiliation", "owner,collaborator,organization_member"),
+        (
+            "direction",
+            match order {
+                SortOrder::Asc => "asc",
+                SortOrder::Desc => "desc",
+            },
+        ),
+    ];
+    match sort {
+        SortKey::Name => params.push(("sort", "full_name")),
+        SortKey::Pushed => params.push(("sort", "pushed")),
+        SortKey::Created => params.push(("sort", "created")),
+        SortKey::Stars => {}
+    }
+    let url = urls::host().list_url(&["user", "repos"], &params);
+
+    // Fetch every page, reporting how far along we are.
+    let mut repositories = Paginator::<Repository>::new(url, pat_token)
+        .collect(|paginator, fetched| {
+            events::emit(Event::RepoFetched {
+                pages: paginator.pages(),
+                fetched,
+            })
+        })
+        .await?;
+    lists::retain(&mut repositories);
+    events::emit(Event::ReposListed {
+        total: repositories.len(),
+    });
+    sort_repos(&mut repositories, sort, order);
+
+    Ok(repositories)
+}
+
+/// The repositories of [`get_repos_request`] offered for picking by hand, leaving out those of
+/// organizations whose visibility the user can't change.
+pub(crate) async fn get_selectable_repos(
+    pat_token: &str,
+    sort: SortKey,
+    order: SortOrder,
+) -> Result<Vec<Repository>> {
+    let mut repos = get_repos_request("", pat_token, sort, order).await?;
+    repos.retain(is_selectable);
+
+    Ok(repos)
+}
+
+/// Whether `repo` is offered for selection: every repository of a user is, and one of an
+/// organization is if the user administers it.
+pub(crate) fn is_selectable(repo: &Repository) -> bool {
+    repo.owner.kind != "Organization" || repo.can_administer()
+}
+
+/// Sorts `repos` by `sort` in `order`; repositories never pushed to sort as the oldest.
+///
+/// Names sort by `owner/name`, the `full_name` GitHub sorts the pages by, so the pages and the
+/// listing put together agree.
+pub(crate) fn sort_repos(repos: &mut [Repository], sort: SortKey, order: SortOrder) {
+    // Names are lowercased once per repository rather than once per comparison.
+    if sort == SortKey::Name {
+        match order {
+            SortOrder::Asc => repos.sort_by_cached_key(sort_name),
+            SortOrder::Desc => repos.sort_by_cached_key(|repo| std::cmp::Reverse(sort_name(repo))),
+        }
+        return;
+    }
+    repos.sort_by(|a, b| {
+        let ordering = match sort {
+            SortKey::Name => sort_name(a).cmp(&sort_name(b)),
+            SortKey::Pushed => a.pushed_at.cmp(&b.pushed_at),
+            SortKey::Created => a.created_at.cmp(&b.created_at),
+            SortKey::Stars => a.stargazers_count.cmp(&b.stargazers_count),
+        };
+        match order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    });
+}
+
+/// What `repo` sorts by for [`SortKey::Name`]: its full name, or its name if that is unknown,
+/// lowercased.
+fn sort_name(repo: &Repository) -> String {
+    match repo.full_name.is_empty() {
+        true => repo.name.to_lowercase(),
+        false => repo.full_name.to_lowercase(),
+    }
+}
+
+/// Returns the login of the user `pat_token` belongs to.
+///
+/// [See docs] https://docs.github.com/en/rest/users/users?apiVersion=2022-11-28#get-the-authenticated-user
+pub(crate) async fn get_authenticated_user(pat_token: &str) -> Result<String> {
+    let request = api_request(Method::GET, urls::host().api_url(&["user"]), pat_token)?;
+    let response = send(request).await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to fetch the authenticated user: {err}",
+            err = api_error(&response)
+        ));
+    }
+    let user: User = serde_json::from_str(&response.body)?;
+
+    Ok(user.login)
+}
+
+/// A code for the user to enter on GitHub to sign in privateer, see [`request_device_code`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct DeviceCode {
+    /// Identifies the sign-in to [`poll_device_token`].
+    pub device_code: String,
+    /// What the user enters, such as `WDJB-MJHT`.
+    pub user_code: String,
+    /// Page to enter it on.
+    pub verification_uri: String,
+    /// Seconds until the code expires.
+    pub expires_in: u64,
+    /// Seconds to wait between polls.
+    pub interval: u64,
+}
+
+/// An answer to a poll of [`poll_device_token`]: a token once the user signed in, an error
+/// otherwise.
+#[derive(Debug, Deserialize)]
+struct DeviceToken {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+    /// Seconds to wait between polls from now on, after `slow_down`.
+    interval: Option<u64>,
+}
+
+/// A `POST` of `body` as JSON to `path` of the web host, where sign-ins are handled.
+async fn login_request(path: &str, body: serde_json::Value) -> Result<HttpResponse> {
+    let settings = transport::settings();
+    let mut request = HttpRequest::new(Method::POST, settings.host.web.join(path)?)
+        .header(header::ACCEPT, HeaderValue::from_static("application/json"))
+        .header(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        )
+        .header(
+            header::USER_AGENT,
+            HeaderValue::from_str(&settings.user_agent)?,
+        )
+        .body(body.to_string());
+    request.timeout = settings.timeout;
+
+    send(request).await
+}
+
+/// Starts signing in with the device flow of the OAuth app `client_id`, for a token with
+/// `scopes`.
+///
+/// [See docs] https://docs.github.com/en/apps/oauth-apps/building-oauth-apps/authorizing-oauth-apps#device-flow
+pub(crate) async fn request_device_code(client_id: &str, scopes: &[&str]) -> Result<DeviceCode> {
+    let response = login_request(
+        "login/device/code",
+        serde_json::json!({ "client_id": client_id, "scope": scopes.join(" ") }),
+    )
+    .await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to start signing in: {err}",
+            err = api_error(&response)
+        ));
+    }
+    serde_json::from_str(&response.body).with_context(|| {
+        format!(
+            "{ERROR_ICON} Failed to start signing in: {body}. Hint! Check that `{client_id}` is the client ID of an OAuth app with the device flow enabled",
+            body = response.body
+        )
+    })
+}
+
+/// Waits for the user to enter `code`, returning the token it grants.
+pub(crate) async fn poll_device_token(client_id: &str, code: &DeviceCode) -> Result<String> {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(code.expires_in);
+    let mut interval = Duration::from_secs(code.interval.max(1));
+    loop {
+        tokio::time::sleep(interval).await;
+        if tokio::time::Instant::now() >= deadline {
+            break;
+        }
+        let response = login_request(
+            "login/oauth/access_token",
+            serde_json::json!({
+                "client_id": client_id,
+                "device_code": code.device_code,
+                "grant_type": "urn:ietf:params:oauth:grant-type:device_code",
+            }),
+        )
+        .await?;
+        if !response.status.is_success() {
+            return Err(anyhow!(
+                "{ERROR_ICON} Failed to sign in: {err}",
+                err = api_error(&response)
+            ));
+        }
+        let answer: DeviceToken = serde_json::from_str(&response.body)?;
+        match (answer.access_token, answer.error.as_deref()) {
+            (Some(token), _) => return Ok(token),
+            (None, Some("authorization_pending")) => {}
+            (None, Some("slow_down")) => {
+                interval = answer
+                    .interval
+                    .map(Duration::from_secs)
+                    .unwrap_or(interval + Duration::from_secs(5));
+            }
+            (None, Some("expired_token")) => break,
+            (None, error) => {
+                return Err(anyhow!(
+                    "{ERROR_ICON} Failed to sign in: {reason}",
+                    reason = answer
+                        .error_description
+                        .as_deref()
+                        .or(error)
+                        .unwrap_or("no token was granted")
+                ))
+            }
+        }
+    }
+
+    Err(anyhow!(
+        "{ERROR_ICON} The code `{user_code}` expired before it was entered. Hint! Run `privateer init` again",
+        user_code = code.user_code
+    ))
+}
+
+/// Returns the quotas of the token, without using any of them.
+///
+/// [See docs] https://docs.github.com/en/rest/rate-limit/rate-limit?apiVersion=2022-11-28#get-rate-limit-status-for-the-authenticated-user
+pub(crate) async fn get_rate_limit(pat_token: &str) -> Result<RateLimits> {
+    let request = api_request(
+        Method::GET,
+        urls::host().api_url(&["rate_limit"]),
+        pat_token,
+    )?;
+    let response = send(request).await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to fetch the rate limit: {err}",
+            err = api_error(&response)
+        ));
+    }
+    let limits: RateLimitResponse = serde_json::from_str(&response.body)?;
+
+    Ok(RateLimits {
+        server_now: ratelimit::server_date(&response.headers),
+        ..limits.resources
+    })
+}
+
+/// Returns the latest events of the audit log of `org` matching the search `phrase`, such as
+/// `action:repo.access`, newest first.
+///
+/// [See docs] https://docs.github.com/en/enterprise-cloud@latest/rest/orgs/orgs#get-the-audit-log-for-an-organization
+pub(crate) async fn get_audit_log(
+    org: &str,
+    phrase: &str,
+    pat_token: &str,
+) -> Result<Vec<AuditLogEntry>> {
+    let url = urls::host().list_url(
+        &["orgs", org, "audit-log"],
+        &[("phrase", phrase), ("order", "desc"), ("per_page", "100")],
+    );
+    let response = send(api_request(Method::GET, url, pat_token)?).await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to read the audit log of `{org}`: {err}. Hint! The audit log API needs GitHub Enterprise Cloud and a token with the `read:audit_log` scope",
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(serde_json::from_str(&response.body)?)
+}
+
+/// Fetches a single repository, or reuses it if it was fetched earlier in the session.
+///
+/// For showing and planning only: a repository about to be changed is fetched with
+/// [`get_repo_fresh`], so the change is never checked against a stale copy.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#get-a-repository
+pub(crate) async fn get_repo(owner: &str, name: &str, pat_token: &str) -> Result<Repository> {
+    let url = urls::host().api_repo_url(owner, name);
+    let cached = transport::settings()
+        .cache
+        .as_ref()
+        .and_then(|cache| cache.get(&url));
+    if let Some(repo) = cached {
+        verbose!("Reusing `{owner}/{name}` fetched earlier in the session");
+        return Ok(repo);
+    }
+
+    get_repo_fresh(owner, name, pat_token).await
+}
+
+/// Fetches a single repository from GitHub even if it was fetched earlier in the session, and
+/// keeps it in place of the earlier copy.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#get-a-repository
+pub(crate) async fn get_repo_fresh(owner: &str, name: &str, pat_token: &str) -> Result<Repository> {
+    let url = urls::host().api_repo_url(owner, name);
+    let request = api_request(Method::GET, url.clone(), pat_token)?;
+    let response = send(request).await?;
+    if matches!(
+        response.status,
+        StatusCode::NOT_FOUND | StatusCode::MOVED_PERMANENTLY
+    ) {
+        return Err(RepoNotFound(format!("{owner}/{name}")).into());
+    }
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to fetch repository `{owner}/{name}`: {err}",
+            err = api_error(&response)
+        ));
+    }
+    let repo: Repository = serde_json::from_str(&response.body)?;
+    if let Some(cache) = &transport::settings().cache {
+        cache.insert(&url, &repo);
+    }
+
+    Ok(repo)
+}
+
+/// The error of a repository that isn't at `owner/name`: not found, hidden from the token, or
+/// moved without saying where.
+#[derive(Debug)]
+pub(crate) struct RepoNotFound(String);
+
+impl fmt::Display for RepoNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{ERROR_ICON} Repository `{}` was not found, or the token can't access it",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for RepoNotFound {}
+
+/// Fetches a single repository by its id, wherever it was renamed or transferred to.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#get-a-repository
+pub(crate) async fn get_repo_by_id(id: u64, pat_token: &str) -> Result<Repository> {
+    let url = urls::host().api_url(&["repositories", &id.to_string()]);
+    let response = send(api_request(Method::GET, url, pat_token)?).await?;
+    if response.status == StatusCode::NOT_FOUND {
+        return Err(anyhow!(
+            "{ERROR_ICON} Repository `{id}` was not found, or the token can't access it",
+        ));
+    }
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to fetch repository `{id}`: {err}",
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(serde_json::from_str(&response.body)?)
+}
+
+/// Creates a repository for the token's user, or in the organization `org`.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#create-an-organization-repository
+pub(crate) async fn create_repo(
+    org: Option<&str>,
+    repo: &NewRepository,
+    pat_token: &str,
+) -> Result<Repository> {
+    let url = match org {
+        Some(org) => urls::host().api_url(&["orgs", org, "repos"]),
+        None => urls::host().api_url(&["user", "repos"]),
+    };
+    let request = api_request(Method::POST, url, pat_token)?.body(serde_json::to_string(repo)?);
+    let response = send(request).await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to create repository `{name}`: {err}",
+            name = repo.name,
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(serde_json::from_str(&response.body)?)
+}
+
+/// Applies `update` to the repository `owner/name`, returning the `X-GitHub-Request-Id` of the
+/// response, if any, for the audit trail.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#update-a-repository
+pub(crate) async fn update_repo(
+    owner: &str,
+    name: &str,
+    update: &UpdateRepository,
+    pat_token: &str,
+) -> Result<Option<String>> {
+    let url = urls::host().api_repo_url(owner, name);
+    let request = api_request(Method::PATCH, url, pat_token)?.body(serde_json::to_string(update)?);
+    let response = send(request).await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to update `{owner}/{name}`: {err}",
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(response
+        .header_str("x-github-request-id")
+        .map(str::to_owned))
+}
+
+/// Replaces the topics of the repository `owner/name` with `topics`, returning the
+/// `X-GitHub-Request-Id` of the response, if any, for the audit trail.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#replace-all-repository-topics
+pub(crate) async fn replace_topics(
+    owner: &str,
+    name: &str,
+    topics: &[String],
+    pat_token: &str,
+) -> Result<Option<String>> {
+    let url = urls::host().api_url(&["repos", owner, name, "topics"]);
+    let body = ReplaceTopics { names: topics };
+    let request = api_request(Method::PUT, url, pat_token)?.body(serde_json::to_string(&body)?);
+    let response = send(request).await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to set the topics of `{owner}/{name}`: {err}",
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(response
+        .header_str("x-github-request-id")
+        .map(str::to_owned))
+}
+
+/// Returns the first 100 open secret scanning alerts of the repository `owner/name`, or `None`
+/// if secret scanning is off, so there are none to list.
+///
+/// [See docs] https://docs.github.com/en/rest/secret-scanning/secret-scanning?apiVersion=2022-11-28#list-secret-scanning-alerts-for-a-repository
+pub(crate) async fn get_open_secret_alerts(
+    owner: &str,
+    name: &str,
+    pat_token: &str,
+) -> Result<Option<Vec<SecretAlert>>> {
+    let url = urls::host().list_url(
+        &["repos", owner, name, "secret-scanning", "alerts"],
+        &[("state", "open"), ("per_page", "100")],
+    );
+    let response = send(api_request(Method::GET, url, pat_token)?).await?;
+    if response.status == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to list the secret scanning alerts of `{owner}/{name}`: {err}. Hint! The token needs the `security_events` scope, or read access to `Secret scanning alerts`",
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(Some(serde_json::from_str(&response.body)?))
+}
+
+/// Counts the open alerts `tool` reports for the repository `owner/name`, where `tool` is
+/// `dependabot` or `code-scanning`, or returns `None` if the token can't see them or the tool is
+/// off for the repository.
+///
+/// [See docs] https://docs.github.com/en/rest/dependabot/alerts?apiVersion=2022-11-28#list-dependabot-alerts-for-a-repository
+/// [See docs] https://docs.github.com/en/rest/code-scanning/code-scanning?apiVersion=2022-11-28#list-code-scanning-alerts-for-a-repository
+pub(crate) async fn count_open_alerts(
+    owner: &str,
+    name: &str,
+    tool: &str,
+    pat_token: &str,
+) -> Result<Option<usize>> {
+    let mut next = Some(urls::host().list_url(
+        &["repos", owner, name, tool, "alerts"],
+        &[("state", "open"), ("per_page", "100")],
+    ));
+    let mut count = 0;
+    while let Some(url) = next.take() {
+        let response = send(api_request(Method::GET, url, pat_token)?).await?;
+        if matches!(
+            response.status,
+            StatusCode::FORBIDDEN | StatusCode::NOT_FOUND
+        ) {
+            return Ok(None);
+        }
+        if !response.status.is_success() {
+            return Err(anyhow!(
+                "{ERROR_ICON} Failed to list the {tool} alerts of `{owner}/{name}`: {err}",
+                err = api_error(&response)
+            ));
+        }
+        let page: Vec<serde::de::IgnoredAny> = serde_json::from_str(&response.body)?;
+        count += page.len();
+        if !page.is_empty() {
+            next = response.header_str("link").and_then(pagination::next_link);
+        }
+    }
+
+    Ok(Some(count))
+}
+
+/// Returns whether GitHub Actions run in the repository `owner/name`, and which.
+///
+/// [See docs] https://docs.github.com/en/rest/actions/permissions?apiVersion=2022-11-28#get-github-actions-permissions-for-a-repository
+pub(crate) async fn get_actions_permissions(
+    owner: &str,
+    name: &str,
+    pat_token: &str,
+) -> Result<ActionsPermissions> {
+    let url = urls::host().api_url(&["repos", owner, name, "actions", "permissions"]);
+    let response = send(api_request(Method::GET, url, pat_token)?).await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to get the Actions permissions of `{owner}/{name}`: {err}",
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(serde_json::from_str(&response.body)?)
+}
+
+/// Sets whether GitHub Actions run in the repository `owner/name`, and which, returning the
+/// `X-GitHub-Request-Id` of the response, if any, for the audit trail.
+///
+/// [See docs] https://docs.github.com/en/rest/actions/permissions?apiVersion=2022-11-28#set-github-actions-permissions-for-a-repository
+pub(crate) async fn set_actions_permissions(
+    owner: &str,
+    name: &str,
+    permissions: &ActionsPermissions,
+    pat_token: &str,
+) -> Result<Option<String>> {
+    let url = urls::host().api_url(&["repos", owner, name, "actions", "permissions"]);
+    let request =
+        api_request(Method::PUT, url, pat_token)?.body(serde_json::to_string(permissions)?);
+    let response = send(request).await?;
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to set the Actions permissions of `{owner}/{name}`: {err}",
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(response
+        .header_str("x-github-request-id")
+        .map(str::to_owned))
+}
+
+/// Deletes the repository `owner/name`, returning the `X-GitHub-Request-Id` of the response, if
+/// any, for the audit trail.
+///
+/// [See docs] https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#delete-a-repository
+pub(crate) async fn delete_repo(
+    owner: &str,
+    name: &str,
+    pat_token: &str,
+) -> Result<Option<String>> {
+    let url = urls::host().api_repo_url(owner, name);
+    let response = send(api_request(Method::DELETE, url, pat_token)?).await?;
+    if response.status == StatusCode::FORBIDDEN {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to delete `{owner}/{name}`: {err}. Hint! Deleting needs a token with the `delete_repo` scope",
+            err = api_error(&response)
+        ));
+    }
+    if !response.status.is_success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to delete `{owner}/{name}`: {err}",
+            err = api_error(&response)
+        ));
+    }
+
+    Ok(response
+        .header_str("x-github-request-id")
+        .map(str::to_owned))
+}
@@ -0,0 +1,89 @@
+//! A heartbeat written after every successful enforcement run, for external monitoring.
+//!
+//! `privateer daemon`, `privateer watch` and `privateer enforce` record when they last
+//! succeeded. `privateer health` exits with an error when that is too long ago, or never
+//! happened, so a cron job or monitoring agent notices enforcement that silently stopped.
+
+use crate::cli::HealthArgs;
+use crate::{paths, schema, Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Heartbeat {
+    pub last_success: DateTime<Utc>,
+    /// The command that succeeded, such as `daemon`.
+    pub mode: String,
+}
+
+/// Records that `mode` just finished a successful run.
+pub(crate) fn beat(mode: &str) -> Result<()> {
+    let path = paths::heartbeat_file()?;
+    let heartbeat = Heartbeat {
+        last_success: Utc::now(),
+        mode: mode.to_owned(),
+    };
+    // Written next to the file and renamed over it, so a reader never sees half a heartbeat.
+    let partial = path.with_extension("json.tmp");
+    std::fs::write(
+        &partial,
+        serde_json::to_string(&schema::versioned(&heartbeat))?,
+    )
+    .and_then(|()| std::fs::rename(&partial, &path))
+    .with_context(|| format!("Failed to write the heartbeat {path:?}"))
+}
+
+fn read(path: &Path) -> Result<Option<Heartbeat>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(path)?;
+    let heartbeat =
+        serde_json::from_str(&text).with_context(|| format!("Malformed heartbeat {path:?}"))?;
+
+    Ok(Some(heartbeat))
+}
+
+/// Returns the age of `heartbeat` at `now`, or an error if it is missing or older than `max_age`.
+pub(crate) fn check(
+    heartbeat: Option<&Heartbeat>,
+    now: DateTime<Utc>,
+    max_age: Duration,
+) -> Result<Duration> {
+    let heartbeat = heartbeat.ok_or_else(|| {
+        anyhow!("{ERROR_ICON} No enforcement run has succeeded yet. Hint! Is `privateer daemon` running?")
+    })?;
+    let age = (now - heartbeat.last_success).to_std().unwrap_or_default();
+    if age > max_age {
+        return Err(anyhow!(
+            "{ERROR_ICON} The last successful run ({mode}) was {age} ago, more than {max_age}",
+            mode = heartbeat.mode,
+            age = humantime::format_duration(Duration::from_secs(age.as_secs())),
+            max_age = humantime::format_duration(max_age)
+        ));
+    }
+
+    Ok(age)
+}
+
+/// Reports the last successful run, failing if it is older than `args.max_age`.
+pub(crate) fn health(args: &HealthArgs) -> Result<()> {
+    let path = match &args.file {
+        Some(path) => path.clone(),
+        None => paths::heartbeat_file()?,
+    };
+    let heartbeat = read(&path)?;
+    let age = check(heartbeat.as_ref(), Utc::now(), args.max_age)?;
+    println!(
+        "{SUCCESS_ICON} Last successful run ({mode}) was {age} ago",
+        mode = heartbeat
+            .map(|heartbeat| heartbeat.mode)
+            .unwrap_or_default(),
+        age = humantime::format_duration(Duration::from_secs(age.as_secs()))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+//! Translations of prompts and messages, written in Fluent (see <https://projectfluent.org>).
+//!
+//! Each locale's messages live in `locales/<locale>/privateer.ftl` and are compiled in. The
+//! locale is `--locale` if given, otherwise the first of `LC_ALL`, `LC_MESSAGES` and `LANG`
+//! that is set, as in `setlocale(3)`. A translation can also be tried out without rebuilding
+//! privateer by saving it as `$XDG_CONFIG_HOME/privateer/locales/<locale>.ftl`. Messages a
+//! translation lacks fall back to English.
+//!
+//! Prompts, dates, progress and the summaries of every command are translated. Output for
+//! programs, such as `--json` and `--progress json`, stays in English so it can be parsed, as do
+//! `--help`, written by clap, `--verbose`, the terminal UI and the details of errors.
+
+use crate::{paths, Result, ERROR_ICON};
+use anyhow::anyhow;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// The messages compiled in, by locale; the first is the fallback for every other.
+const LOCALES: &[(&str, &str)] = &[("en", include_str!("../locales/en/privateer.ftl"))];
+
+/// The bundles messages are looked up in, in order: the chosen locale's, then English.
+static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+
+/// Selects the locale of this run's messages, see the module documentation.
+///
+/// A `locale` given explicitly must have a translation; one from the environment falls back to
+/// English.
+pub(crate) fn configure(locale: Option<&str>) -> Result<()> {
+    let explicit = locale.is_some();
+    let locale = locale.map(str::to_owned).or_else(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+    });
+    let mut bundles = Vec::new();
+    if let Some(locale) = locale.as_deref().and_then(language_tag) {
+        match translation(&locale)? {
+            Some(bundle) => bundles.push(bundle),
+            None if explicit => {
+                return Err(anyhow!(
+                    "{ERROR_ICON} No translation for locale `{locale}`; available: {}",
+                    available().join(", ")
+                ))
+            }
+            None => {}
+        }
+    }
+    bundles.push(english());
+    let _ = BUNDLES.set(bundles);
+
+    Ok(())
+}
+
+/// Returns the locales with a translation compiled in.
+pub(crate) fn available() -> Vec<&'static str> {
+    LOCALES.iter().map(|(locale, _)| *locale).collect()
+}
+
+/// Turns a POSIX locale such as `pt_BR.UTF-8` into a language tag such as `pt-BR`, or `None`
+/// for the `C` and `POSIX` locales, which ask for no translation.
+pub(crate) fn language_tag(locale: &str) -> Option<String> {
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+    match locale {
+        "" | "C" | "POSIX" => None,
+        _ => Some(locale.replace('_', "-")),
+    }
+}
+
+/// The bundle for `locale` from the locales directory or compiled in, trying the language alone,
+/// such as `pt` for `pt-BR`, if the region has no translation of its own.
+fn translation(locale: &str) -> Result<Option<FluentBundle<FluentResource>>> {
+    let language = locale.split('-').next().unwrap_or(locale);
+    for tag in [locale, language] {
+        let user_file = paths::config_dir()
+            .ok()
+            .map(|dir| dir.join("locales").join(format!("{tag}.ftl")))
+            .filter(|path| path.is_file());
+        if let Some(path) = user_file {
+            let source = std::fs::read_to_string(&path)?;
+            return bundle(tag, source)
+                .map(Some)
+                .map_err(|err| anyhow!("{ERROR_ICON} Invalid translation {path:?}: {err}"));
+        }
+        if let Some((_, source)) = LOCALES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(tag))
+        {
+            return bundle(tag, source.to_string()).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+fn english() -> FluentBundle<FluentResource> {
+    let (locale, source) = LOCALES[0];
+    bundle(locale, source.to_owned()).expect("the English messages are valid")
+}
+
+/// Parses the messages of `locale` from Fluent `source`.
+pub(crate) fn bundle(locale: &str, source: String) -> Result<FluentBundle<FluentResource>> {
+    let langid: LanguageIdentifier = locale
+        .parse()
+        .map_err(|_| anyhow!("`{locale}` is not a language tag, such as `en` or `pt-BR`"))?;
+    let resource = FluentResource::try_new(source).map_err(|(_, errors)| {
+        anyhow!(
+            "{}",
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        )
+    })?;
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Unicode isolation marks around placeables show up as junk in many terminals.
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .map_err(|errors| anyhow!("duplicate messages: {errors:?}"))?;
+
+    Ok(bundle)
+}
+
+/// Looks up message `id` with `args` in `bundles`, falling back to the id itself if no bundle
+/// has it.
+pub(crate) fn format(
+    bundles: &[FluentBundle<FluentResource>],
+    id: &str,
+    args: Option<&FluentArgs>,
+) -> String {
+    bundles
+        .iter()
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .into_owned(),
+            )
+        })
+        .unwrap_or_else(|| id.to_owned())
+}
+
+/// Returns message `id` of this run's locale with `args`, see [`t!`](crate::i18n::t).
+pub(crate) fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    format(BUNDLES.get_or_init(|| vec![english()]), id, args)
+}
+
+/// Returns a translated message, by id and with named arguments, such as
+/// `t!("read-only-repos", count = 3)`.
+macro_rules! t {
+    ($id:literal) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+pub(crate) use t;
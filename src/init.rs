@@ -0,0 +1,208 @@
+//! `privateer init`: a first-run wizard writing the configuration and saving the token.
+//!
+//! It asks for the GitHub host, then signs in: with the device flow of the OAuth app given by
+//! `--client-id`, entering a code on GitHub, or else with a personal access token, explaining
+//! which scopes it needs and where to create it. It then asks for the visibility policy and the
+//! default filters of listings, and writes them to the configuration file read by every command.
+//! Last, the token can be saved where later runs find it, see [`credentials`], instead of
+//! being kept in `PAT_TOKEN` or typed at each run.
+
+use crate::cli::{InitArgs, Visibility};
+use crate::i18n::t;
+use crate::output::status;
+use crate::transport::{self, Settings};
+use crate::urls::Host;
+use crate::{
+    config, credentials, github, lists, paths, prompter, validate, Result, ERROR_ICON, SUCCESS_ICON,
+};
+use anyhow::{anyhow, Context};
+use std::sync::Arc;
+
+/// Scopes privateer needs: `repo` to change visibility, `delete_repo` for `detach-fork --then
+/// delete`.
+const SCOPES: &[&str] = &["repo", "delete_repo"];
+
+/// What the wizard asked.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Answers {
+    /// Host name of a GitHub Enterprise Server, or `None` for github.com.
+    pub hostname: Option<String>,
+    pub default_visibility: Visibility,
+    /// Repositories left alone by the policy.
+    pub allow: Vec<String>,
+    /// Owner of the repositories listed, or `None` for the user signed in.
+    pub owner: Option<String>,
+    /// `owner/name` globs of repositories never listed or changed.
+    pub deny: Vec<String>,
+}
+
+/// Walks through the setup and writes the configuration.
+pub(crate) async fn run(args: &InitArgs) -> Result<()> {
+    if !prompter::is_interactive() {
+        return Err(anyhow!("{ERROR_ICON} {}", t!("init-not-a-terminal")));
+    }
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => paths::config_file()?,
+    };
+    if path.exists()
+        && !prompter::prompt_yes_no(&t!("init-overwrite-prompt", path = format!("{path:?}")))?
+    {
+        return Err(anyhow!("{ERROR_ICON} {}", t!("nothing-changed")));
+    }
+
+    let hostname = prompter::prompt_user_input(&t!("init-host-prompt"))?
+        .trim()
+        .to_owned();
+    let (hostname, host) = match hostname.as_str() {
+        "" | "github.com" => (None, Host::default()),
+        _ => {
+            let host = Host::enterprise(&hostname).ok_or_else(|| {
+                anyhow!(
+                    "{ERROR_ICON} {}",
+                    t!("init-not-a-host", hostname = hostname.as_str())
+                )
+            })?;
+            (Some(hostname), host)
+        }
+    };
+    let settings = Arc::new(Settings {
+        host: host.clone(),
+        ..(*transport::settings()).clone()
+    });
+    let pat_token = transport::with_settings(settings.clone(), sign_in(args)).await?;
+    let login = transport::with_settings(settings, github::get_authenticated_user(&pat_token))
+        .await
+        .context(t!("init-token-rejected"))?;
+    status!(
+        "{SUCCESS_ICON} {}",
+        t!("init-signed-in", login = login.as_str())
+    );
+
+    let default_visibility = match prompter::prompt_yes_no(&t!("init-private-prompt"))? {
+        true => Visibility::Private,
+        false => Visibility::Public,
+    };
+    let allow = split_list(&prompter::prompt_user_input(&t!("init-allow-prompt"))?);
+    let owner = prompter::prompt_user_input(&t!("init-owner-prompt", login = login.as_str()))?;
+    let owner = match owner.trim() {
+        "" => None,
+        owner => Some(validate::username(owner)?),
+    };
+    let deny = split_list(&prompter::prompt_user_input(&t!("init-deny-prompt"))?);
+    lists::parse(&deny.join("\n")).map_err(|(_, glob)| {
+        anyhow!(
+            "{ERROR_ICON} {}",
+            t!("init-not-a-glob", glob = glob.as_str())
+        )
+    })?;
+    let answers = Answers {
+        hostname,
+        default_visibility,
+        allow,
+        owner,
+        deny,
+    };
+
+    let text = render_config(&answers);
+    config::parse(&text)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, text)
+        .with_context(|| format!("{ERROR_ICON} Failed to write {path:?}"))?;
+    status!(
+        "{SUCCESS_ICON} {}",
+        t!("init-wrote-config", path = format!("{path:?}"))
+    );
+
+    let storage = credentials::storage(&host)?;
+    if prompter::prompt_yes_no(&t!("init-save-token-prompt", storage = storage.as_str()))? {
+        credentials::save(&host, &pat_token)?;
+        status!(
+            "{SUCCESS_ICON} {}",
+            t!("init-saved-token", storage = storage.as_str())
+        );
+    } else {
+        status!("{}", t!("init-set-token"));
+    }
+    // The saved token is kept for its host, which later runs only know from `GH_HOST`.
+    if let Some(hostname) = &answers.hostname {
+        status!("{}", t!("init-set-host", hostname = hostname.as_str()));
+    }
+    status!("{}", t!("init-next"));
+
+    Ok(())
+}
+
+/// Returns a token for the host in scope: the one in `PAT_TOKEN` if the user wants it, otherwise
+/// one granted by the device flow if `args` name an OAuth app, otherwise one the user pastes.
+async fn sign_in(args: &InitArgs) -> Result<String> {
+    if let Ok(token) = std::env::var("PAT_TOKEN") {
+        if !token.is_empty() && prompter::prompt_yes_no(&t!("init-use-env-token-prompt"))? {
+            return Ok(token);
+        }
+    }
+    match &args.client_id {
+        Some(client_id) => {
+            let code = github::request_device_code(client_id, SCOPES).await?;
+            status!(
+                "{}",
+                t!(
+                    "init-device-code",
+                    url = code.verification_uri.as_str(),
+                    code = code.user_code.as_str()
+                )
+            );
+            github::poll_device_token(client_id, &code).await
+        }
+        None => {
+            let url = format!(
+                "{}settings/tokens/new?scopes={}&description=privateer",
+                transport::settings().host.web,
+                SCOPES.join(",")
+            );
+            status!("{}", t!("init-token-scopes", url = url));
+            prompter::prompt_for_token()
+        }
+    }
+}
+
+/// The non-empty items of `input`, separated by commas.
+fn split_list(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// The configuration file for `answers`.
+pub(crate) fn render_config(answers: &Answers) -> String {
+    let quote = |text: &str| toml::Value::String(text.to_owned()).to_string();
+    let list = |items: &[String]| {
+        items
+            .iter()
+            .map(|item| quote(item))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let owner = match &answers.owner {
+        Some(owner) => format!("owner = {}\n", quote(owner)),
+        None => String::new(),
+    };
+
+    format!(
+        "# Written by `privateer init`; see the README for every setting.\n\n\
+         [policy]\n\
+         default_visibility = \"{visibility}\"\n\
+         allow = [{allow}]\n\n\
+         [filters]\n\
+         {owner}\
+         deny = [{deny}]\n",
+        visibility = answers.default_visibility.name(),
+        allow = list(&answers.allow),
+        deny = list(&answers.deny),
+    )
+}
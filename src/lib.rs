@@ -0,0 +1,140 @@
+//! Makes GitHub repositories private or public, in bulk, from the command line or from Rust.
+//!
+//! The command lists the repositories a personal access token can reach, lets the user pick some,
+//! and changes their visibility, with backups, policies and an audit trail around each change.
+//!
+//! # Library
+//!
+//! The same functionality is available to other Rust tools through [`Client`]; the `privateer`
+//! binary is a thin wrapper around [`main`]. A [`ClientBuilder`] configures the host, user agent,
+//! timeout and retries, and can send the requests through a [`FakeTransport`] instead of the
+//! network:
+//!
+//! ```
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> privateer::Result<()> {
+//! use privateer::{Client, FakeTransport, HttpResponse};
+//! use std::sync::Arc;
+//!
+//! let github = Arc::new(FakeTransport::new());
+//! github.respond(
+//!     reqwest::Method::GET,
+//!     "/api/v3/user",
+//!     HttpResponse::new(200, r#"{"login":"octocat"}"#),
+//! );
+//! let client = Client::builder()
+//!     .token("ghp_example")
+//!     .base_url("https://github.example.com/api/v3/")
+//!     .transport(github.clone())
+//!     .build()?;
+//!
+//! assert_eq!(client.authenticated_user().await?, "octocat");
+//! assert_eq!(github.requests()[0].headers["authorization"], "Bearer ghp_example");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `mock` feature, `MockGitHub` fakes the GitHub endpoints the client uses, so code built
+//! on it can be tested end to end without a network or a token.
+
+#![deny(missing_docs)]
+
+mod app;
+mod apply;
+mod audit;
+mod audit_log;
+mod backup;
+mod bulk;
+mod cache;
+mod cancel;
+mod cassette;
+mod checkpoint;
+mod cli;
+mod client;
+mod clone;
+mod config;
+mod conflict;
+mod credentials;
+mod daemon;
+mod deprecation;
+mod detach;
+mod diff;
+mod doctor;
+mod email;
+mod enforce;
+mod events;
+mod exit;
+mod format;
+mod github;
+mod heartbeat;
+mod i18n;
+mod init;
+mod list;
+mod lists;
+mod lock;
+mod lockdown;
+mod man;
+mod metrics;
+mod mirror;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+mod notify;
+mod open_source;
+mod output;
+mod pagination;
+mod paths;
+mod picker;
+mod plan;
+mod policy;
+mod prompt_dialoguer;
+mod prompter;
+mod query;
+mod queue;
+mod ratelimit;
+mod schema;
+mod secrets;
+mod server;
+mod shell;
+mod sizes;
+mod snapshot;
+mod stats;
+mod table;
+#[cfg(test)]
+mod tests;
+mod topic;
+mod transport;
+mod tui;
+mod urls;
+mod validate;
+mod watch;
+mod webhook;
+
+use serde::Deserialize;
+
+pub use app::main;
+pub use client::{Client, ClientBuilder, PrivateerClient};
+pub use github::{
+    Collaborator, ErrorDetail, ErrorResponse, FeatureStatus, Gist, License, Organization, Owner,
+    Parent, Permissions, Repository, SecurityAndAnalysis, UpdateRepository,
+};
+#[cfg(feature = "mock")]
+pub use mock::MockGitHub;
+pub use transport::{
+    with_transport, FakeTransport, HttpRequest, HttpResponse, HttpTransport, ReqwestTransport,
+    SendFuture,
+};
+
+/// The result of fallible privateer operations.
+pub type Result<T> = anyhow::Result<T, anyhow::Error>;
+
+// Symbols for success, error and warning messages, see `output::Icon`.
+pub(crate) static SUCCESS_ICON: output::Icon = output::Icon::Success;
+pub(crate) static ERROR_ICON: output::Icon = output::Icon::Error;
+pub(crate) static WARNING_ICON: output::Icon = output::Icon::Warning;
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiResponse {
+    message: String,
+    documentation_url: String,
+}
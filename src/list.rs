@@ -0,0 +1,190 @@
+//! `privateer list`: the repositories the token's user can access, as a table.
+//!
+//! The default columns, name, visibility and last push, fit a narrow terminal; `--wide` adds the
+//! web page, stars, forks, language and size. Both are laid out by [`table::render`], so the
+//! shared columns look the same either way. `--json` prints them for scripts instead, trimmed
+//! with `--fields` or `--jq`, see [`query`].
+
+use crate::cli::{ListArgs, SortKey, SortOrder};
+use crate::github::{self, Repository};
+use crate::table;
+use crate::{format, query, schema, urls, Result, ERROR_ICON};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use console::style;
+use serde_json::{json, Value};
+
+/// A column of `privateer list`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Column {
+    Name,
+    Visibility,
+    Pushed,
+    Url,
+    Stars,
+    Forks,
+    Language,
+    Size,
+}
+
+impl Column {
+    /// The columns shown by default, or with `--wide`.
+    pub(crate) fn all(wide: bool) -> &'static [Column] {
+        match wide {
+            false => &[Column::Name, Column::Visibility, Column::Pushed],
+            true => &[
+                Column::Name,
+                Column::Visibility,
+                Column::Pushed,
+                Column::Url,
+                Column::Stars,
+                Column::Forks,
+                Column::Language,
+                Column::Size,
+            ],
+        }
+    }
+
+    fn table(self) -> table::Column {
+        let (header, numeric) = match self {
+            Column::Name => ("name", false),
+            Column::Visibility => ("visibility", false),
+            Column::Pushed => ("pushed", false),
+            Column::Url => ("url", false),
+            Column::Stars => ("stars", true),
+            Column::Forks => ("forks", true),
+            Column::Language => ("language", false),
+            Column::Size => ("size", true),
+        };
+
+        table::Column { header, numeric }
+    }
+
+    /// The cell of `repo` in this column at `now`.
+    fn cell(self, repo: &Repository, now: DateTime<Utc>) -> String {
+        match self {
+            Column::Name => full_name(repo),
+            Column::Visibility => match repo.private {
+                Some(true) => style("private").yellow().to_string(),
+                Some(false) => style("public").dim().to_string(),
+                None => style("unknown").red().to_string(),
+            },
+            Column::Pushed => match repo.pushed_at {
+                Some(pushed_at) => format::date(pushed_at, now),
+                None => style("never").dim().to_string(),
+            },
+            Column::Url => match (repo.html_url.is_empty(), full_name(repo).split_once('/')) {
+                (false, _) => repo.html_url.clone(),
+                (true, Some((owner, name))) => urls::host().html_repo_url(owner, name).to_string(),
+                (true, None) => String::new(),
+            },
+            Column::Stars => format::thousands(repo.stargazers_count as usize),
+            Column::Forks => format::thousands(repo.forks_count as usize),
+            Column::Language => repo.language.clone().unwrap_or_default(),
+            Column::Size => format::size(repo.size),
+        }
+    }
+}
+
+fn full_name(repo: &Repository) -> String {
+    match (repo.full_name.is_empty(), repo.owner.login.is_empty()) {
+        (false, _) => repo.full_name.clone(),
+        (true, false) => format!("{}/{}", repo.owner.login, repo.name),
+        (true, true) => repo.name.clone(),
+    }
+}
+
+/// Lays out `repos` in `columns` as of `now`.
+pub(crate) fn render(repos: &[Repository], columns: &[Column], now: DateTime<Utc>) -> String {
+    let rows: Vec<Vec<String>> = repos
+        .iter()
+        .map(|repo| {
+            columns
+                .iter()
+                .map(|column| column.cell(repo, now))
+                .collect()
+        })
+        .collect();
+    let columns: Vec<table::Column> = columns.iter().map(|column| column.table()).collect();
+
+    table::render(&columns, &rows)
+}
+
+/// The fields of each repository in the JSON output, for `--fields`.
+pub(crate) const FIELDS: [&str; 16] = [
+    "schema_version",
+    "name",
+    "owner",
+    "visibility",
+    "pushed_at",
+    "created_at",
+    "url",
+    "stars",
+    "forks",
+    "language",
+    "size",
+    "archived",
+    "fork",
+    "description",
+    "topics",
+    "default_branch",
+];
+
+/// `repo` as an object of the JSON output, with the same names as the table's columns where
+/// they share one.
+pub(crate) fn json(repo: &Repository) -> Value {
+    json!({
+        "schema_version": schema::SCHEMA_VERSION,
+        "name": full_name(repo),
+        "owner": repo.owner.login,
+        "visibility": repo.private.map(|private| match private {
+            true => "private",
+            false => "public",
+        }),
+        "pushed_at": repo.pushed_at,
+        "created_at": repo.created_at,
+        "url": Column::Url.cell(repo, Utc::now()),
+        "stars": repo.stargazers_count,
+        "forks": repo.forks_count,
+        "language": repo.language,
+        "size": repo.size,
+        "archived": repo.archived,
+        "fork": repo.fork,
+        "description": repo.description,
+        "topics": repo.topics,
+        "default_branch": repo.default_branch,
+    })
+}
+
+/// Prints the repositories of the token's user ordered by `sort` in `order`.
+pub(crate) async fn run(
+    pat_token: &str,
+    args: &ListArgs,
+    sort: SortKey,
+    order: SortOrder,
+) -> Result<()> {
+    // Checked before listing, which may take a while for many repositories.
+    if let Some(field) = args
+        .fields
+        .iter()
+        .find(|field| !FIELDS.contains(&field.as_str()))
+    {
+        return Err(anyhow!(
+            "{ERROR_ICON} `{field}` is not a field. Hint! Fields are {}",
+            FIELDS.join(", ")
+        ));
+    }
+    let repos = github::get_repos_request("", pat_token, sort, order).await?;
+    if !(args.json || !args.fields.is_empty() || args.jq.is_some()) {
+        println!("{}", render(&repos, Column::all(args.wide), Utc::now()));
+        return Ok(());
+    }
+    let value = Value::Array(repos.iter().map(json).collect());
+    let output = query::render(value, &args.fields, args.jq.as_ref())
+        .map_err(|err| anyhow!("{ERROR_ICON} {err}"))?;
+    if !output.is_empty() {
+        println!("{output}");
+    }
+
+    Ok(())
+}
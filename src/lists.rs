@@ -0,0 +1,126 @@
+//! Allowlist and denylist files of `owner/name` globs, given with `--allowlist` and `--denylist`.
+//!
+//! The files live outside the configuration, so a team can keep them in a shared repository and
+//! point every run at them. Each line is a glob such as `acme/*` or `*/dotfiles`, where `*`
+//! matches any characters but `/` and `?` matches one; a glob without an owner matches the name
+//! under any owner. Blank lines and lines starting with `#` are ignored.
+//!
+//! Repositories on the allowlist keep the visibility they have: no command changes them, and
+//! the policy exempts them like its own `allow`. Repositories on the denylist are never touched
+//! at all: they are left out of every listing, and skipped when named.
+
+use crate::github::Repository;
+use crate::output::verbose;
+use crate::plan::SkipReason;
+use crate::{Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use std::path::Path;
+use std::sync::RwLock;
+
+/// The globs of `--allowlist` and of `--denylist`.
+static LISTS: RwLock<(Vec<String>, Vec<String>)> = RwLock::new((Vec::new(), Vec::new()));
+
+/// Reads the globs of `allowlist` and `denylist`, if given, for the rest of the run, denying
+/// the globs of `deny` too.
+pub(crate) fn configure(
+    allowlist: Option<&Path>,
+    denylist: Option<&Path>,
+    deny: &[String],
+) -> Result<()> {
+    let allow = allowlist.map(read).transpose()?.unwrap_or_default();
+    let mut deny = deny.to_vec();
+    deny.extend(denylist.map(read).transpose()?.unwrap_or_default());
+    verbose!(
+        "`{}` allowlisted and `{}` denylisted globs",
+        allow.len(),
+        deny.len()
+    );
+    if let Ok(mut lists) = LISTS.write() {
+        *lists = (allow, deny);
+    }
+
+    Ok(())
+}
+
+/// Reads the globs of the list at `path`.
+pub(crate) fn read(path: &Path) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("{ERROR_ICON} Failed to read the list {path:?}"))?;
+    parse(&text).map_err(|(line, glob)| {
+        anyhow!("{ERROR_ICON} {path:?}, line {line}: `{glob}` is not an `owner/name` glob, such as `acme/*`")
+    })
+}
+
+/// Parses the globs of a list, or returns the number and text of the first invalid line.
+pub(crate) fn parse(text: &str) -> std::result::Result<Vec<String>, (usize, String)> {
+    let mut globs = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let valid = line.split('/').count() <= 2
+            && line.split('/').all(|part| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "-_.*?".contains(c))
+            });
+        if !valid {
+            return Err((index + 1, line.to_owned()));
+        }
+        globs.push(match line.contains('/') {
+            true => line.to_owned(),
+            false => format!("*/{line}"),
+        });
+    }
+
+    Ok(globs)
+}
+
+/// Returns `true` if `glob` matches `full_name`, case-insensitively.
+pub(crate) fn matches(glob: &str, full_name: &str) -> bool {
+    fn matches(glob: &[u8], name: &[u8]) -> bool {
+        match (glob.split_first(), name.split_first()) {
+            (None, None) => true,
+            (Some((b'*', rest)), _) => {
+                matches(rest, name)
+                    || name
+                        .split_first()
+                        .is_some_and(|(&c, tail)| c != b'/' && matches(glob, tail))
+            }
+            (Some((b'?', rest)), Some((&c, tail))) => c != b'/' && matches(rest, tail),
+            (Some((&g, rest)), Some((&c, tail))) => {
+                g.eq_ignore_ascii_case(&c) && matches(rest, tail)
+            }
+            _ => false,
+        }
+    }
+    matches(glob.as_bytes(), full_name.as_bytes())
+}
+
+/// Why `repo` is left alone by the lists, if it is.
+pub(crate) fn reason(repo: &Repository) -> Option<SkipReason> {
+    let full_name = match repo.full_name.is_empty() {
+        true => format!("{}/{}", repo.owner.login, repo.name),
+        false => repo.full_name.clone(),
+    };
+    let lists = LISTS.read().ok()?;
+    let (allow, deny) = &*lists;
+    if deny.iter().any(|glob| matches(glob, &full_name)) {
+        Some(SkipReason::Filtered)
+    } else if allow.iter().any(|glob| matches(glob, &full_name)) {
+        Some(SkipReason::Protected)
+    } else {
+        None
+    }
+}
+
+/// Leaves the denylisted repositories out of `repos`.
+pub(crate) fn retain(repos: &mut Vec<Repository>) {
+    let before = repos.len();
+    repos.retain(|repo| reason(repo) != Some(SkipReason::Filtered));
+    if repos.len() < before {
+        verbose!("Left out `{}` denylisted repos", before - repos.len());
+    }
+}
@@ -0,0 +1,144 @@
+//! A lock file that keeps two privateer runs from changing repositories or state at once.
+//!
+//! Overlapping runs, such as a cron job and an interactive session, could otherwise interleave
+//! writes to the audit trail and snapshot. The lock holds the PID of its owner; a lock left behind
+//! by a process that no longer exists is taken over.
+//!
+//! The lock is written in full under another name and hard-linked into place, so it never exists
+//! without its owner, and a lock whose owner can't be read counts as held. A stale lock is moved
+//! aside before it is removed, so a lock another run took in the meantime is put back instead.
+
+use crate::{paths, Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use std::ffi::OsString;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Holds the lock until dropped.
+#[derive(Debug)]
+pub(crate) struct RunLock {
+    path: PathBuf,
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Takes the run lock, failing if another live run holds it unless `force` is set.
+pub(crate) fn acquire(force: bool) -> Result<RunLock> {
+    acquire_at(&paths::lock_file()?, force)
+}
+
+pub(crate) fn acquire_at(path: &Path, force: bool) -> Result<RunLock> {
+    let partial = beside(path, "tmp");
+    std::fs::write(&partial, std::process::id().to_string())
+        .with_context(|| format!("Failed to write lock file {partial:?}"))?;
+    let locked = take(path, &partial, force);
+    let _ = std::fs::remove_file(&partial);
+
+    locked
+}
+
+/// Links `partial`, holding the PID of this process, to `path`, once any stale lock is removed.
+fn take(path: &Path, partial: &Path, force: bool) -> Result<RunLock> {
+    loop {
+        match std::fs::hard_link(partial, path) {
+            Ok(()) => {
+                return Ok(RunLock {
+                    path: path.to_owned(),
+                })
+            }
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to create lock file {path:?}"))
+            }
+        }
+        let owner = match std::fs::read_to_string(path) {
+            Ok(owner) => owner,
+            // Released since.
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read lock file {path:?}"))
+            }
+        };
+        if !force {
+            match owner.trim().parse::<u32>() {
+                Ok(pid) if is_running(pid, path) => {
+                    return Err(anyhow!(
+                        "{ERROR_ICON} Another privateer run is active (PID {pid}). Hint! Wait for it to finish, or pass `--force` if it is stuck"
+                    ));
+                }
+                Ok(_) => {}
+                Err(_) => {
+                    return Err(anyhow!(
+                        "{ERROR_ICON} The lock file {path:?} doesn't name the run holding it. Hint! Pass `--force` if no other privateer run is active"
+                    ));
+                }
+            }
+        }
+        remove_if_held_by(path, &owner)?;
+    }
+}
+
+/// Removes the lock at `path` if it is still the one holding `owner`.
+fn remove_if_held_by(path: &Path, owner: &str) -> Result<()> {
+    let aside = beside(path, "stale");
+    match std::fs::rename(path, &aside) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to remove stale lock file {path:?}"))
+        }
+    }
+    if std::fs::read_to_string(&aside).is_ok_and(|moved| moved != owner) {
+        // Another run took the lock after it was read; give it back.
+        let _ = std::fs::hard_link(&aside, path);
+    }
+    let _ = std::fs::remove_file(&aside);
+
+    Ok(())
+}
+
+/// A path next to the lock at `path`, ending in `kind`, that no other call uses.
+fn beside(path: &Path, kind: &str) -> PathBuf {
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(
+        ".{pid}-{call}.{kind}",
+        pid = std::process::id(),
+        call = CALLS.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    PathBuf::from(name)
+}
+
+/// Returns `true` if a process with `pid` exists.
+///
+/// Signal `0` checks that without signalling it; `EPERM` means it exists but belongs to another
+/// user.
+#[cfg(unix)]
+fn is_running(pid: u32, _path: &Path) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: `kill` with signal `0` only checks the process, and takes no pointers.
+    let sent = unsafe { libc::kill(pid, 0) };
+    sent == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Returns `true` unless the lock at `path` is older than any run takes, since the PID of its
+/// owner can't be checked here.
+#[cfg(not(unix))]
+fn is_running(_pid: u32, path: &Path) -> bool {
+    const STALE_AFTER: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_none_or(|age| age < STALE_AFTER)
+}
@@ -0,0 +1,301 @@
+//! `privateer lockdown`: an opinionated preset of settings applied to repositories in one go.
+//!
+//! The preset makes repositories private, turns off the wiki and projects, forbids forks of the
+//! repositories of organizations, turns on secret scanning and push protection, and only lets
+//! the owner's own actions run. `[lockdown]` in the configuration changes any of these. What
+//! would change is shown as a diff and confirmed before anything is sent, and every change is
+//! in the audit trail.
+
+use crate::apply;
+use crate::audit::{self, AuditRecord};
+use crate::cli::{LockdownArgs, SortKey, SortOrder};
+use crate::config::{self, Lockdown};
+use crate::diff::{self, FieldChange};
+use crate::github::{
+    self, ActionsPermissions, FeatureStatus, Repository, SecurityAndAnalysis, UpdateRepository,
+};
+use crate::i18n::t;
+use crate::output::status;
+use crate::{lists, paths, prompt_dialoguer, prompter, validate, Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::anyhow;
+use console::style;
+use serde_json::Value;
+
+/// Shows what the preset would change in the repositories of `args`, then changes it once
+/// confirmed.
+pub(crate) async fn run(pat_token: &str, args: &LockdownArgs) -> Result<()> {
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => paths::config_file()?,
+    };
+    let preset = config::load_if_exists(&path)?.lockdown;
+    let username = github::get_authenticated_user(pat_token).await?;
+    let repos = select_repos(&args.repos, args.all, &username, pat_token).await?;
+
+    let mut plans = Vec::with_capacity(repos.len());
+    for repo in repos {
+        let actions =
+            github::get_actions_permissions(&repo.owner.login, &repo.name, pat_token).await?;
+        let changes = changes(&preset, &repo, &actions);
+        println!("{}", diff::render(&full_name(&repo), &changes));
+        plans.push((repo, changes));
+    }
+    plans.retain(|(_, changes)| changes.iter().any(|change| !change.is_unchanged()));
+    if plans.is_empty() {
+        status!("{SUCCESS_ICON} {}", t!("lockdown-all-done"));
+        return Ok(());
+    }
+    println!(
+        "\n{}",
+        style(t!("lockdown-planned", count = plans.len())).bold()
+    );
+    confirm(args.yes)?;
+
+    let mut failures = Vec::new();
+    for (repo, changes) in &plans {
+        let errors = lock_down(&preset, repo, changes, &username, pat_token).await;
+        match errors.is_empty() {
+            true => status!(
+                "{SUCCESS_ICON} {}",
+                t!("lockdown-done", repo = full_name(repo))
+            ),
+            false => {
+                for err in errors {
+                    eprintln!("{err}");
+                }
+                failures.push(full_name(repo));
+            }
+        }
+    }
+    match failures.is_empty() {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "{ERROR_ICON} Failed to lock down `{count}` repos entirely: {names}",
+            count = failures.len(),
+            names = failures.join(", ")
+        )),
+    }
+}
+
+/// The repositories named in `repos`, owned by `username` unless named with an owner, every
+/// repository `username` owns with `all`, or those picked from a list; those on the
+/// `--allowlist` or `--denylist` are left out.
+pub(crate) async fn select_repos(
+    repos: &[String],
+    all: bool,
+    username: &str,
+    pat_token: &str,
+) -> Result<Vec<Repository>> {
+    let mut selected = if all {
+        github::get_repos_request(username, pat_token, SortKey::Name, SortOrder::Asc)
+            .await?
+            .into_iter()
+            .filter(|repo| repo.owner.login.eq_ignore_ascii_case(username))
+            .collect()
+    } else if repos.is_empty() {
+        if !prompter::is_interactive() {
+            return Err(anyhow!(
+                "{ERROR_ICON} No repositories given. Hint! Name them, or pass `--all`"
+            ));
+        }
+        let listed =
+            github::get_selectable_repos(pat_token, SortKey::Pushed, SortOrder::Desc).await?;
+        let selected = prompt_dialoguer::run_dialoguer(username.to_owned(), listed.clone(), None)?;
+        selected
+            .into_iter()
+            .map(|index| listed[index].clone())
+            .collect()
+    } else {
+        let mut fetched = Vec::with_capacity(repos.len());
+        for input in repos {
+            let repo = validate::repo(input)?;
+            let owner = repo.owner.as_deref().unwrap_or(username);
+            fetched.push(github::get_repo(owner, &repo.name, pat_token).await?);
+        }
+        fetched
+    };
+    // The allowlist and denylist hold for every setting, not only the visibility.
+    selected.retain(|repo| match lists::reason(repo) {
+        Some(reason) => {
+            status!(
+                "{}: {}",
+                full_name(repo),
+                t!("change-skipped", reason = reason.describe())
+            );
+            false
+        }
+        None => true,
+    });
+
+    Ok(selected)
+}
+
+/// Asks to go ahead, unless `yes` already did.
+pub(crate) fn confirm(yes: bool) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+    if !prompter::is_interactive() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Not confirmed. Hint! Pass `--yes` to go ahead without a terminal"
+        ));
+    }
+    match prompter::prompt_yes_no(&t!("go-ahead-prompt"))? {
+        true => Ok(()),
+        false => Err(anyhow!("{ERROR_ICON} {}", t!("nothing-changed"))),
+    }
+}
+
+fn full_name(repo: &Repository) -> String {
+    format!("{}/{}", repo.owner.login, repo.name)
+}
+
+/// Every setting of `preset` against its current value in `repo`, whose Actions permissions are
+/// `actions`. Forking is left out for repositories of users, which can't forbid it.
+pub(crate) fn changes(
+    preset: &Lockdown,
+    repo: &Repository,
+    actions: &ActionsPermissions,
+) -> Vec<FieldChange> {
+    let change = |field: &str, old: Option<Value>, new: Value| FieldChange {
+        field: field.to_owned(),
+        old,
+        new,
+    };
+    let security = repo.security_and_analysis.clone().unwrap_or_default();
+    let status = |status: Option<FeatureStatus>| status.map(|status| Value::from(status.status));
+
+    let mut changes = vec![
+        FieldChange::visibility(repo.private, preset.private),
+        change(
+            "has_wiki",
+            Some(repo.has_wiki.into()),
+            preset.has_wiki.into(),
+        ),
+        change(
+            "has_projects",
+            Some(repo.has_projects.into()),
+            preset.has_projects.into(),
+        ),
+    ];
+    if repo.owner.kind == "Organization" {
+        changes.push(change(
+            "allow_forking",
+            repo.allow_forking.map(Value::from),
+            preset.allow_forking.into(),
+        ));
+    }
+    changes.extend([
+        change(
+            "secret_scanning",
+            status(security.secret_scanning),
+            FeatureStatus::new(preset.secret_scanning).status.into(),
+        ),
+        change(
+            "secret_scanning_push_protection",
+            status(security.secret_scanning_push_protection),
+            FeatureStatus::new(preset.push_protection).status.into(),
+        ),
+        change(
+            "actions",
+            Some(actions.describe().into()),
+            preset.actions.permissions().describe().into(),
+        ),
+    ]);
+
+    changes
+}
+
+/// Makes the `changes` of `preset` to `repo`: the visibility first, like any other visibility
+/// change, then the other settings, then the Actions permissions. A failed step doesn't stop
+/// the next, since security features may not be available to the repository; the errors of
+/// those that failed are returned.
+async fn lock_down(
+    preset: &Lockdown,
+    repo: &Repository,
+    changes: &[FieldChange],
+    actor: &str,
+    pat_token: &str,
+) -> Vec<anyhow::Error> {
+    let (owner, name) = (repo.owner.login.as_str(), repo.name.as_str());
+    let changed = |field: &str| {
+        changes
+            .iter()
+            .find(|change| change.field == field && !change.is_unchanged())
+    };
+    let mut errors = Vec::new();
+
+    if changed("visibility").is_some() {
+        let private = preset.private.to_string();
+        if let Err(err) = apply::apply_one(repo, &private, actor, pat_token, actor, &|_| {}).await {
+            errors.push(err);
+        }
+    }
+
+    let settings = UpdateRepository {
+        has_wiki: changed("has_wiki").map(|_| preset.has_wiki),
+        has_projects: changed("has_projects").map(|_| preset.has_projects),
+        allow_forking: changed("allow_forking").map(|_| preset.allow_forking),
+        ..UpdateRepository::default()
+    };
+    let security = UpdateRepository {
+        security_and_analysis: Some(SecurityAndAnalysis {
+            secret_scanning: changed("secret_scanning")
+                .map(|_| FeatureStatus::new(preset.secret_scanning)),
+            secret_scanning_push_protection: changed("secret_scanning_push_protection")
+                .map(|_| FeatureStatus::new(preset.push_protection)),
+        })
+        .filter(|security| *security != SecurityAndAnalysis::default()),
+        ..UpdateRepository::default()
+    };
+    for (update, fields) in [
+        (settings, &["has_wiki", "has_projects", "allow_forking"][..]),
+        (
+            security,
+            &["secret_scanning", "secret_scanning_push_protection"][..],
+        ),
+    ] {
+        if update == UpdateRepository::default() {
+            continue;
+        }
+        let updated = github::update_repo(owner, name, &update, pat_token).await;
+        let recorded = updated.and_then(|request_id| {
+            fields
+                .iter()
+                .filter_map(|field| changed(field))
+                .try_for_each(|change| record(repo, change, actor, request_id.clone()))
+        });
+        if let Err(err) = recorded {
+            errors.push(err);
+        }
+    }
+
+    if let Some(change) = changed("actions") {
+        let permissions = preset.actions.permissions();
+        let set = github::set_actions_permissions(owner, name, &permissions, pat_token).await;
+        if let Err(err) = set.and_then(|request_id| record(repo, change, actor, request_id)) {
+            errors.push(err);
+        }
+    }
+
+    errors
+}
+
+pub(crate) fn record(
+    repo: &Repository,
+    change: &FieldChange,
+    actor: &str,
+    request_id: Option<String>,
+) -> Result<()> {
+    audit::append(&AuditRecord {
+        timestamp: chrono::Utc::now(),
+        actor: actor.to_owned(),
+        repo: full_name(repo),
+        node_id: audit::node_id(repo),
+        field: change.field.clone(),
+        old: change.old.clone().unwrap_or(Value::Null),
+        new: change.new.clone(),
+        request_id,
+        backup: None,
+    })
+}
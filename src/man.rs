@@ -0,0 +1,41 @@
+//! Man pages generated from the command-line definitions.
+//!
+//! `privateer man --out DIR` writes `privateer.1` and a `privateer-<command>.1` page per
+//! command, with the same descriptions, options and examples as `--help`, so the pages never
+//! drift from the binary they ship with. The command is hidden: it is meant for packagers.
+
+use crate::cli::Cli;
+use crate::i18n::t;
+use crate::output::status;
+use crate::{Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::Context;
+use clap::CommandFactory;
+use clap_mangen::Man;
+use std::path::{Path, PathBuf};
+
+/// Writes the man pages of privateer and of its visible commands to `dir`, returning their paths.
+pub(crate) fn write_pages(dir: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("{ERROR_ICON} Failed to create the directory {dir:?}"))?;
+    let mut command = Cli::command().disable_help_subcommand(true);
+    command.build();
+
+    let mut pages = Vec::new();
+    for command in std::iter::once(command.clone()).chain(
+        command
+            .get_subcommands()
+            .filter(|command| !command.is_hide_set())
+            .cloned(),
+    ) {
+        let page = Man::new(command)
+            .generate_to(dir)
+            .with_context(|| format!("{ERROR_ICON} Failed to write a man page to {dir:?}"))?;
+        pages.push(page);
+    }
+    status!(
+        "{SUCCESS_ICON} {}",
+        t!("man-written", count = pages.len(), dir = format!("{dir:?}"))
+    );
+
+    Ok(pages)
+}
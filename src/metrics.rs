@@ -0,0 +1,126 @@
+//! Counters and gauges of long-running modes, exposed in the Prometheus text format.
+//!
+//! `privateer daemon` serves them on `GET /metrics` next to its health check, and `privateer
+//! watch` does with `--metrics-addr`.
+
+use crate::server::{Request, Response};
+use crate::transport::HttpResponse;
+use crate::Result;
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+/// Gauges not observed yet are left out of [`render`].
+const UNSET: i64 = i64::MIN;
+
+static REPOS_SCANNED: AtomicU64 = AtomicU64::new(0);
+static CHANGES_APPLIED: AtomicU64 = AtomicU64::new(0);
+static API_ERRORS: AtomicU64 = AtomicU64::new(0);
+static RATE_LIMIT_REMAINING: AtomicI64 = AtomicI64::new(UNSET);
+static LAST_RUN: AtomicI64 = AtomicI64::new(UNSET);
+static LAST_SUCCESS: AtomicI64 = AtomicI64::new(UNSET);
+
+/// Counts `count` repositories checked against the policy.
+pub(crate) fn scanned(count: usize) {
+    REPOS_SCANNED.fetch_add(count as u64, Ordering::Relaxed);
+}
+
+/// Counts one visibility change applied.
+pub(crate) fn changed() {
+    CHANGES_APPLIED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Records the outcome of an API request: failures and error statuses count as API errors, and
+/// `X-RateLimit-Remaining` updates the rate-limit gauge.
+pub(crate) fn observe(result: &Result<HttpResponse>) {
+    let response = match result {
+        Ok(response) => response,
+        Err(_) => {
+            API_ERRORS.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+    };
+    if response.status.is_client_error() || response.status.is_server_error() {
+        API_ERRORS.fetch_add(1, Ordering::Relaxed);
+    }
+    if let Some(remaining) = response
+        .header_str("x-ratelimit-remaining")
+        .and_then(|value| value.parse().ok())
+    {
+        RATE_LIMIT_REMAINING.store(remaining, Ordering::Relaxed);
+    }
+}
+
+/// Records a run that finished at `at`, successfully or not.
+pub(crate) fn ran(at: DateTime<Utc>, success: bool) {
+    LAST_RUN.store(at.timestamp(), Ordering::Relaxed);
+    if success {
+        LAST_SUCCESS.store(at.timestamp(), Ordering::Relaxed);
+    }
+}
+
+/// All metrics in the Prometheus text exposition format.
+pub(crate) fn render() -> String {
+    let mut text = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: i128| {
+        let _ = writeln!(text, "# HELP {name} {help}");
+        let _ = writeln!(text, "# TYPE {name} {kind}");
+        let _ = writeln!(text, "{name} {value}");
+    };
+    let counter = |counter: &AtomicU64| counter.load(Ordering::Relaxed) as i128;
+    metric(
+        "privateer_repos_scanned_total",
+        "counter",
+        "Repositories checked against the policy.",
+        counter(&REPOS_SCANNED),
+    );
+    metric(
+        "privateer_visibility_changes_total",
+        "counter",
+        "Visibility changes applied.",
+        counter(&CHANGES_APPLIED),
+    );
+    metric(
+        "privateer_api_errors_total",
+        "counter",
+        "GitHub API requests that failed or returned an error status.",
+        counter(&API_ERRORS),
+    );
+    for (name, help, gauge) in [
+        (
+            "privateer_rate_limit_remaining",
+            "Requests left in the current GitHub rate-limit window.",
+            &RATE_LIMIT_REMAINING,
+        ),
+        (
+            "privateer_last_run_timestamp_seconds",
+            "Unix time the last run finished.",
+            &LAST_RUN,
+        ),
+        (
+            "privateer_last_success_timestamp_seconds",
+            "Unix time the last successful run finished.",
+            &LAST_SUCCESS,
+        ),
+    ] {
+        let value = gauge.load(Ordering::Relaxed);
+        if value != UNSET {
+            metric(name, "gauge", help, value.into());
+        }
+    }
+
+    text
+}
+
+/// Answers `GET /metrics`, or `None` for any other path.
+pub(crate) fn route(request: &Request) -> Option<Response> {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/metrics") => Some(Response {
+            status: 200,
+            content_type: "text/plain; version=0.0.4",
+            body: render(),
+        }),
+        (_, "/metrics") => Some(Response::text(405, "Method Not Allowed\n")),
+        _ => None,
+    }
+}
@@ -0,0 +1,144 @@
+//! `privateer mirror`: keeps copies of repositories, such as sanitized public mirrors of private
+//! ones, in sync with their source.
+//!
+//! Only the branches listed for a mirror in the `[[mirrors]]` of the configuration are pushed, so
+//! work in progress, tags and any other branch of the source never reach the mirror. Branches
+//! are force-pushed: the mirror is a copy, and whatever was pushed to it directly is replaced.
+
+use crate::backup;
+use crate::cli::MirrorArgs;
+use crate::config::{self, MirrorConfig};
+use crate::github;
+use crate::i18n::t;
+use crate::output::status;
+use crate::{paths, validate, Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::anyhow;
+use std::path::Path;
+
+/// Syncs the configured mirrors once, or every `args.every` until interrupted with `Ctrl-C`.
+pub(crate) async fn run(pat_token: &str, args: &MirrorArgs) -> Result<()> {
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => paths::config_file()?,
+    };
+    let mirrors = select(config::load(&path)?.mirrors, &args.sources)?;
+    let username = github::get_authenticated_user(pat_token).await?;
+
+    loop {
+        let mut failures = Vec::new();
+        for mirror in &mirrors {
+            match sync(mirror, &username, pat_token).await {
+                Ok(()) => status!(
+                    "{SUCCESS_ICON} {}",
+                    t!(
+                        "mirror-pushed",
+                        branches = mirror.branches.join(", "),
+                        source = mirror.source.as_str(),
+                        mirror = mirror.mirror.as_str()
+                    )
+                ),
+                Err(err) => {
+                    eprintln!("{err}");
+                    failures.push(mirror.source.clone());
+                }
+            }
+        }
+        let Some(every) = args.every else {
+            return match failures.is_empty() {
+                true => Ok(()),
+                false => Err(anyhow!(
+                    "{ERROR_ICON} Failed to sync the mirrors of `{count}` repos: {names}",
+                    count = failures.len(),
+                    names = failures.join(", ")
+                )),
+            };
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(every) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// The mirrors of `sources`, or all of them if none are given.
+pub(crate) fn select(mirrors: Vec<MirrorConfig>, sources: &[String]) -> Result<Vec<MirrorConfig>> {
+    if mirrors.is_empty() {
+        return Err(anyhow!(
+            "{ERROR_ICON} No mirrors are configured. Hint! Add a `[[mirrors]]` table with `source`, `mirror` and `branches` to the configuration"
+        ));
+    }
+    if let Some(unknown) = sources
+        .iter()
+        .find(|source| !mirrors.iter().any(|mirror| &mirror.source == *source))
+    {
+        return Err(anyhow!(
+            "{ERROR_ICON} `{unknown}` has no mirror in the configuration"
+        ));
+    }
+
+    Ok(mirrors
+        .into_iter()
+        .filter(|mirror| sources.is_empty() || sources.contains(&mirror.source))
+        .collect())
+}
+
+/// Pushes the branches of `mirror` from its source, whose owner defaults to `username`.
+async fn sync(mirror: &MirrorConfig, username: &str, pat_token: &str) -> Result<()> {
+    let url = |input: &str| -> Result<String> {
+        let repo = validate::repo(input)?;
+        Ok(backup::clone_url(
+            repo.owner.as_deref().unwrap_or(username),
+            &repo.name,
+        ))
+    };
+    let (source, target) = (url(&mirror.source)?, url(&mirror.mirror)?);
+    let dir = std::env::temp_dir().join(format!(
+        "privateer-mirror-{}-{}.git",
+        std::process::id(),
+        mirror.source.replace('/', "-")
+    ));
+    let (branches, pat_token) = (mirror.branches.clone(), pat_token.to_owned());
+
+    tokio::task::spawn_blocking(move || {
+        let pushed = push_branches(&source, &target, &branches, &dir, &pat_token);
+        let _ = std::fs::remove_dir_all(&dir);
+        pushed
+    })
+    .await?
+}
+
+/// Fetches `branches` of `source` into a new bare repository at `dir`, then force-pushes them
+/// to `mirror`, leaving every other branch and tag behind.
+pub(crate) fn push_branches(
+    source: &str,
+    mirror: &str,
+    branches: &[String],
+    dir: &Path,
+    pat_token: &str,
+) -> Result<()> {
+    let _ = std::fs::remove_dir_all(dir);
+    let refspecs: Vec<String> = branches
+        .iter()
+        .map(|branch| format!("+refs/heads/{branch}:refs/heads/{branch}"))
+        .collect();
+
+    let mut init = backup::git("");
+    init.args(["init", "--bare", "--quiet"]).arg(dir);
+    backup::run_git(init).map_err(|err| anyhow!("{ERROR_ICON} Failed to create {dir:?}: {err}"))?;
+
+    let mut fetch = backup::git(pat_token);
+    fetch
+        .arg("-C")
+        .arg(dir)
+        .args(["fetch", "--quiet", "--no-tags", source])
+        .args(&refspecs);
+    backup::run_git(fetch)
+        .map_err(|err| anyhow!("{ERROR_ICON} Failed to fetch from `{source}`: {err}"))?;
+
+    let mut push = backup::git(pat_token);
+    push.arg("-C")
+        .arg(dir)
+        .args(["push", "--quiet", mirror])
+        .args(&refspecs);
+    backup::run_git(push).map_err(|err| anyhow!("{ERROR_ICON} Failed to push to `{mirror}`: {err}"))
+}
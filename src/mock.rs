@@ -0,0 +1,579 @@
+//! An in-process fake of the GitHub endpoints privateer uses, enabled by the `mock` feature.
+
+use crate::github::{
+    ActionsPermissions, AuditLogEntry, Collaborator, FeatureStatus, Gist, NewRepository,
+    Organization, Owner, Parent, Permissions, Quota, RateLimitResponse, RateLimits, Repository,
+    SecretAlert, SecurityAndAnalysis, UpdateRepository, User,
+};
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport, SendFuture};
+use reqwest::header;
+use reqwest::Method;
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Mutex;
+
+/// Items per page unless the request asks for another `per_page`, like GitHub.
+const DEFAULT_PER_PAGE: usize = 30;
+
+#[derive(Debug, Default)]
+struct State {
+    login: String,
+    repos: Vec<Repository>,
+    orgs: Vec<Organization>,
+    gists: Vec<Gist>,
+    /// Teams as their organization, slug, and the names of their repositories.
+    teams: Vec<(String, String, Vec<String>)>,
+    /// Actions permissions set, by `owner/name`; Actions are enabled for all actions otherwise.
+    actions: Vec<(String, ActionsPermissions)>,
+    /// Open secret scanning alerts, by `owner/name`.
+    secret_alerts: Vec<(String, SecretAlert)>,
+    /// Visibility changes of the repositories of organizations, oldest first.
+    audit_log: Vec<AuditLogEntry>,
+    /// Number of changes made, for the request ids.
+    updates: u64,
+    /// Id of the last repository added.
+    last_id: u64,
+}
+
+impl State {
+    /// The id and GraphQL id of a new repository.
+    fn next_ids(&mut self) -> (u64, String) {
+        self.last_id += 1;
+        (self.last_id, format!("R_mock{}", self.last_id))
+    }
+}
+
+/// A fake GitHub that keeps its repositories in memory, to use as the transport of a
+/// [`Client`](crate::Client).
+///
+/// It serves the authenticated user, the repository, organization, team and gist listings (paged
+/// with `Link` headers), single repositories, their collaborators and secret scanning alerts, and
+/// the creation, update, topics, Actions permissions and deletion of repositories, which change
+/// the repositories it answers with later. Visibility changes of an organization's repositories
+/// show up in its audit log. Requests without a token get `401`.
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> privateer::Result<()> {
+/// use privateer::{Client, MockGitHub};
+/// use std::sync::Arc;
+///
+/// let github = Arc::new(MockGitHub::new("octocat").with_repo("dotfiles", false));
+/// let client = Client::new("any token").with_transport(github.clone());
+/// client.set_visibility("octocat", "dotfiles", true).await?;
+/// assert_eq!(github.repo("octocat", "dotfiles").unwrap().private, Some(true));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MockGitHub {
+    state: Mutex<State>,
+}
+
+impl MockGitHub {
+    /// A GitHub where the token belongs to `login`, who has no repositories yet.
+    pub fn new(login: &str) -> Self {
+        Self {
+            state: Mutex::new(State {
+                login: login.to_owned(),
+                ..State::default()
+            }),
+        }
+    }
+
+    /// Adds a repository owned by the token's user.
+    pub fn with_repo(self, name: &str, private: bool) -> Self {
+        let owner = self.lock().login.clone();
+        self.with_owned_repo(&owner, name, private)
+    }
+
+    /// Adds a repository owned by `owner`, such as an organization, that the token's user
+    /// administers.
+    pub fn with_owned_repo(self, owner: &str, name: &str, private: bool) -> Self {
+        let mut state = self.lock();
+        let kind = match owner == state.login {
+            true => "User",
+            false => "Organization",
+        };
+        let (id, node_id) = state.next_ids();
+        state.repos.push(Repository {
+            id,
+            node_id,
+            name: name.to_owned(),
+            full_name: format!("{owner}/{name}"),
+            url: format!("https://api.github.com/repos/{owner}/{name}"),
+            html_url: format!("https://github.com/{owner}/{name}"),
+            clone_url: format!("https://github.com/{owner}/{name}.git"),
+            private: Some(private),
+            size: 1,
+            owner: Owner {
+                login: owner.to_owned(),
+                kind: kind.to_owned(),
+            },
+            permissions: Some(Permissions {
+                admin: true,
+                push: true,
+                pull: true,
+            }),
+            ..Repository::default()
+        });
+        drop(state);
+        self
+    }
+
+    /// Adds a public fork of `parent`, an `owner/name`, owned by the token's user.
+    pub fn with_fork(self, name: &str, parent: &str) -> Self {
+        let with_repo = self.with_repo(name, false);
+        if let Some(repo) = with_repo.lock().repos.last_mut() {
+            repo.fork = true;
+            repo.parent = Some(Parent {
+                full_name: parent.to_owned(),
+            });
+        }
+        with_repo
+    }
+
+    /// Adds an organization the token's user belongs to.
+    pub fn with_org(self, login: &str) -> Self {
+        self.lock().orgs.push(Organization {
+            login: login.to_owned(),
+            description: None,
+        });
+        self
+    }
+
+    /// Adds the team `slug` of `org`, with access to the repositories `org/name` of `repos`.
+    pub fn with_team(self, org: &str, slug: &str, repos: &[&str]) -> Self {
+        self.lock().teams.push((
+            org.to_owned(),
+            slug.to_owned(),
+            repos.iter().map(|name| (*name).to_owned()).collect(),
+        ));
+        self
+    }
+
+    /// Turns secret scanning on for the repository `name` of the token's user, and adds an open
+    /// alert for a secret of the kind `secret_type`, such as `GitHub Personal Access Token`.
+    pub fn with_secret_alert(self, name: &str, secret_type: &str) -> Self {
+        {
+            let mut state = self.lock();
+            let full_name = format!("{}/{name}", state.login);
+            if let Some(repo) = state
+                .repos
+                .iter_mut()
+                .find(|repo| repo.full_name == full_name)
+            {
+                repo.security_and_analysis
+                    .get_or_insert_with(SecurityAndAnalysis::default)
+                    .secret_scanning = Some(FeatureStatus::new(true));
+            }
+            let number = state.secret_alerts.len() as u64 + 1;
+            state.secret_alerts.push((
+                full_name.clone(),
+                SecretAlert {
+                    number,
+                    secret_type_display_name: Some(secret_type.to_owned()),
+                    html_url: format!(
+                        "https://github.com/{full_name}/security/secret-scanning/{number}"
+                    ),
+                },
+            ));
+        }
+        self
+    }
+
+    /// Adds a gist of the token's user.
+    pub fn with_gist(self, id: &str, public: bool) -> Self {
+        self.lock().gists.push(Gist {
+            id: id.to_owned(),
+            html_url: format!("https://gist.github.com/{id}"),
+            public,
+            description: None,
+        });
+        self
+    }
+
+    /// The repository `owner/name` as it is now, if it exists.
+    pub fn repo(&self, owner: &str, name: &str) -> Option<Repository> {
+        self.lock()
+            .repos
+            .iter()
+            .find(|repo| is_named(repo, owner, name))
+            .cloned()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn answer(&self, request: &HttpRequest) -> HttpResponse {
+        if !request.headers.contains_key(header::AUTHORIZATION) {
+            return message(401, "Requires authentication");
+        }
+        let mut state = self.lock();
+        let segments: Vec<&str> = request
+            .url
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+            .unwrap_or_default();
+        // GitHub Enterprise Server serves the same API under `/api/v3`.
+        let segments = match segments.as_slice() {
+            ["api", "v3", rest @ ..] => rest.to_vec(),
+            _ => segments,
+        };
+        match (&request.method, segments.as_slice()) {
+            (&Method::GET, ["user"]) => ok(&User {
+                login: state.login.clone(),
+            }),
+            (&Method::GET, ["rate_limit"]) => {
+                let reset = chrono::Utc::now().timestamp() + 3_600;
+                let quota = |limit| Quota {
+                    limit,
+                    used: 0,
+                    remaining: limit,
+                    reset,
+                };
+                ok(&RateLimitResponse {
+                    resources: RateLimits {
+                        core: quota(5_000),
+                        graphql: Some(quota(5_000)),
+                        server_now: None,
+                    },
+                })
+            }
+            (&Method::GET, ["user", "repos"]) => page(request, &state.repos),
+            (&Method::GET, ["user", "orgs"]) => page(request, &state.orgs),
+            (&Method::GET, ["gists"]) => page(request, &state.gists),
+            (&Method::GET, ["orgs", org, "teams", slug, "repos"]) => {
+                let Some((_, _, names)) = state
+                    .teams
+                    .iter()
+                    .find(|(login, team, _)| login == org && team == slug)
+                else {
+                    return message(404, "Not Found");
+                };
+                let repos: Vec<&Repository> = state
+                    .repos
+                    .iter()
+                    .filter(|repo| names.iter().any(|name| is_named(repo, org, name)))
+                    .collect();
+                page(request, &repos)
+            }
+            (&Method::GET, ["orgs", org, "audit-log"]) => {
+                let prefix = format!("{}/", org.to_lowercase());
+                let entries: Vec<&AuditLogEntry> = state
+                    .audit_log
+                    .iter()
+                    .rev()
+                    .filter(|entry| {
+                        entry
+                            .repo
+                            .as_ref()
+                            .is_some_and(|repo| repo.to_lowercase().starts_with(&prefix))
+                    })
+                    .collect();
+                ok(&entries)
+            }
+            (&Method::GET, ["repositories", id]) => {
+                match state.repos.iter().find(|repo| repo.id.to_string() == *id) {
+                    Some(repo) => ok(repo),
+                    None => message(404, "Not Found"),
+                }
+            }
+            (&Method::GET, ["repos", owner, name]) => {
+                match state.repos.iter().find(|repo| is_named(repo, owner, name)) {
+                    Some(repo) => ok(repo),
+                    None => message(404, "Not Found"),
+                }
+            }
+            (&Method::GET, ["repos", owner, name, "collaborators"]) => {
+                match state.repos.iter().any(|repo| is_named(repo, owner, name)) {
+                    true => page(
+                        request,
+                        &[Collaborator {
+                            login: state.login.clone(),
+                            permissions: Some(Permissions {
+                                admin: true,
+                                push: true,
+                                pull: true,
+                            }),
+                        }],
+                    ),
+                    false => message(404, "Not Found"),
+                }
+            }
+            (&Method::POST, ["user", "repos"]) => {
+                let owner = state.login.clone();
+                create(&mut state, &owner, request)
+            }
+            (&Method::POST, ["orgs", org, "repos"]) => {
+                match state
+                    .orgs
+                    .iter()
+                    .any(|known| known.login.eq_ignore_ascii_case(org))
+                {
+                    true => create(&mut state, org, request),
+                    false => message(404, "Not Found"),
+                }
+            }
+            (&Method::PUT, ["repos", owner, name, "topics"]) => {
+                let names = serde_json::from_str::<serde_json::Value>(
+                    request.body.as_deref().unwrap_or("{}"),
+                )
+                .ok()
+                .and_then(|body| serde_json::from_value::<Vec<String>>(body["names"].clone()).ok());
+                let Some(names) = names else {
+                    return message(422, "Invalid request: `names` wasn't supplied");
+                };
+                match state
+                    .repos
+                    .iter_mut()
+                    .find(|repo| is_named(repo, owner, name))
+                {
+                    Some(repo) => {
+                        repo.topics = names;
+                        ok(&json!({ "names": repo.topics }))
+                    }
+                    None => message(404, "Not Found"),
+                }
+            }
+            (&Method::GET, ["repos", owner, name, "secret-scanning", "alerts"]) => {
+                let Some(repo) = state.repos.iter().find(|repo| is_named(repo, owner, name)) else {
+                    return message(404, "Not Found");
+                };
+                let scanning = repo
+                    .security_and_analysis
+                    .as_ref()
+                    .and_then(|security| security.secret_scanning.as_ref())
+                    .is_some_and(FeatureStatus::is_enabled);
+                if !scanning {
+                    return message(404, "Secret scanning is disabled on this repository.");
+                }
+                let full_name = format!("{owner}/{name}");
+                let alerts: Vec<&SecretAlert> = state
+                    .secret_alerts
+                    .iter()
+                    .filter(|(repo, _)| *repo == full_name)
+                    .map(|(_, alert)| alert)
+                    .collect();
+                ok(&alerts)
+            }
+            (&Method::GET, ["repos", owner, name, "actions", "permissions"]) => {
+                if !state.repos.iter().any(|repo| is_named(repo, owner, name)) {
+                    return message(404, "Not Found");
+                }
+                let full_name = format!("{owner}/{name}");
+                match state.actions.iter().find(|(repo, _)| *repo == full_name) {
+                    Some((_, permissions)) => ok(permissions),
+                    None => ok(&ActionsPermissions {
+                        enabled: true,
+                        allowed_actions: Some("all".to_owned()),
+                    }),
+                }
+            }
+            (&Method::PUT, ["repos", owner, name, "actions", "permissions"]) => {
+                let permissions: ActionsPermissions =
+                    match serde_json::from_str(request.body.as_deref().unwrap_or("{}")) {
+                        Ok(permissions) => permissions,
+                        Err(err) => return message(400, &format!("Problems parsing JSON: {err}")),
+                    };
+                if !state.repos.iter().any(|repo| is_named(repo, owner, name)) {
+                    return message(404, "Not Found");
+                }
+                state.updates += 1;
+                let request_id = format!("MOCK:{:04}", state.updates);
+                let full_name = format!("{owner}/{name}");
+                state.actions.retain(|(repo, _)| *repo != full_name);
+                state.actions.push((full_name, permissions));
+                HttpResponse::new(204, "").header("x-github-request-id", &request_id)
+            }
+            (&Method::DELETE, ["repos", owner, name]) => {
+                let before = state.repos.len();
+                state.repos.retain(|repo| !is_named(repo, owner, name));
+                match state.repos.len() < before {
+                    true => HttpResponse::new(204, ""),
+                    false => message(404, "Not Found"),
+                }
+            }
+            (&Method::PATCH, ["repos", owner, name]) => {
+                let update: UpdateRepository =
+                    match serde_json::from_str(request.body.as_deref().unwrap_or("{}")) {
+                        Ok(update) => update,
+                        Err(err) => return message(400, &format!("Problems parsing JSON: {err}")),
+                    };
+                state.updates += 1;
+                let request_id = format!("MOCK:{:04}", state.updates);
+                let audit_id = format!("MOCK-AUDIT:{:04}", state.updates);
+                let login = state.login.clone();
+                let Some(repo) = state
+                    .repos
+                    .iter_mut()
+                    .find(|repo| is_named(repo, owner, name))
+                else {
+                    return message(404, "Not Found");
+                };
+                if let Some(private) = update.private {
+                    repo.private = Some(private);
+                }
+                let access =
+                    (update.private.is_some() && repo.owner.kind == "Organization").then(|| {
+                        AuditLogEntry {
+                            id: audit_id,
+                            action: "repo.access".to_owned(),
+                            actor: Some(login),
+                            repo: Some(repo.full_name.clone()),
+                            visibility: update.private.map(|private| {
+                                if private { "private" } else { "public" }.to_owned()
+                            }),
+                            timestamp: chrono::Utc::now().timestamp_millis(),
+                        }
+                    });
+                if let Some(description) = update.description {
+                    repo.description = Some(description);
+                }
+                if let Some(archived) = update.archived {
+                    repo.archived = archived;
+                }
+                for (enabled, field) in [
+                    (update.has_issues, &mut repo.has_issues),
+                    (update.has_projects, &mut repo.has_projects),
+                    (update.has_wiki, &mut repo.has_wiki),
+                ] {
+                    if let Some(enabled) = enabled {
+                        *field = enabled;
+                    }
+                }
+                if let Some(allow_forking) = update.allow_forking {
+                    repo.allow_forking = Some(allow_forking);
+                }
+                if let Some(security) = update.security_and_analysis {
+                    let current = repo
+                        .security_and_analysis
+                        .get_or_insert_with(Default::default);
+                    if let Some(status) = security.secret_scanning {
+                        current.secret_scanning = Some(status);
+                    }
+                    if let Some(status) = security.secret_scanning_push_protection {
+                        current.secret_scanning_push_protection = Some(status);
+                    }
+                }
+                if let Some(new_name) = update.name {
+                    repo.full_name = format!("{}/{new_name}", repo.owner.login);
+                    repo.name = new_name;
+                }
+                let response = ok(&*repo).header("x-github-request-id", &request_id);
+                state.audit_log.extend(access);
+                response
+            }
+            _ => message(404, "Not Found"),
+        }
+    }
+}
+
+/// Creates the repository the body of `request` describes, owned by `owner`.
+fn create(state: &mut State, owner: &str, request: &HttpRequest) -> HttpResponse {
+    let new: NewRepository = match serde_json::from_str(request.body.as_deref().unwrap_or("{}")) {
+        Ok(new) => new,
+        Err(err) => return message(400, &format!("Problems parsing JSON: {err}")),
+    };
+    if state
+        .repos
+        .iter()
+        .any(|repo| is_named(repo, owner, &new.name))
+    {
+        return message(
+            422,
+            "Repository creation failed: name already exists on this account",
+        );
+    }
+    let (id, node_id) = state.next_ids();
+    let repo = Repository {
+        id,
+        node_id,
+        name: new.name.clone(),
+        full_name: format!("{owner}/{}", new.name),
+        url: format!("https://api.github.com/repos/{owner}/{}", new.name),
+        html_url: format!("https://github.com/{owner}/{}", new.name),
+        clone_url: format!("https://github.com/{owner}/{}.git", new.name),
+        private: Some(new.private),
+        description: new.description,
+        owner: Owner {
+            login: owner.to_owned(),
+            kind: match owner == state.login {
+                true => "User".to_owned(),
+                false => "Organization".to_owned(),
+            },
+        },
+        permissions: Some(Permissions {
+            admin: true,
+            push: true,
+            pull: true,
+        }),
+        ..Repository::default()
+    };
+    state.repos.push(repo.clone());
+
+    HttpResponse {
+        status: reqwest::StatusCode::CREATED,
+        ..ok(&repo)
+    }
+}
+
+fn is_named(repo: &Repository, owner: &str, name: &str) -> bool {
+    repo.owner.login.eq_ignore_ascii_case(owner) && repo.name.eq_ignore_ascii_case(name)
+}
+
+fn ok(body: &impl Serialize) -> HttpResponse {
+    HttpResponse::new(200, serde_json::to_string(body).unwrap_or_default())
+        .header("content-type", "application/json")
+}
+
+fn message(status: u16, message: &str) -> HttpResponse {
+    HttpResponse::new(status, json!({ "message": message }).to_string())
+        .header("content-type", "application/json")
+}
+
+/// The page of `items` the `page` and `per_page` parameters of `request` ask for, linking the
+/// next one if there is one.
+fn page<T: Serialize>(request: &HttpRequest, items: &[T]) -> HttpResponse {
+    let param = |name: &str| {
+        request
+            .url
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| value.parse::<usize>().ok())
+    };
+    let per_page = param("per_page").unwrap_or(DEFAULT_PER_PAGE).clamp(1, 100);
+    let page = param("page").unwrap_or(1).max(1);
+    let start = (page - 1).saturating_mul(per_page).min(items.len());
+    let end = (start + per_page).min(items.len());
+    let response = ok(&&items[start..end]);
+    if end >= items.len() {
+        return response;
+    }
+    let mut next = request.url.clone();
+    let pairs: Vec<(String, String)> = next
+        .query_pairs()
+        .filter(|(key, _)| key != "page")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    next.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("page", &(page + 1).to_string());
+
+    response.header("link", &format!(r#"<{next}>; rel="next""#))
+}
+
+impl HttpTransport for MockGitHub {
+    fn send(&self, request: HttpRequest) -> SendFuture<'_> {
+        let response = HttpResponse {
+            url: request.url.clone(),
+            ..self.answer(&request)
+        };
+
+        Box::pin(std::future::ready(Ok(response)))
+    }
+}
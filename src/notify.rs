@@ -0,0 +1,233 @@
+//! Chat notifications summarizing the changes of a run.
+//!
+//! Each incoming webhook in the `[notifications]` section of the configuration is sent a short
+//! summary, such as "3 repos privatized, 1 failure", followed by the audit details of every
+//! change. A notification that can't be delivered is a warning, never a failed run.
+//!
+//! Email is sent too if `[notifications.email]` is configured, see [`crate::email`], and a
+//! desktop notification is shown with `--notify`.
+//!
+//! ```toml
+//! [[notifications.webhooks]]
+//! kind = "slack"
+//! url = "https://hooks.slack.com/services/T000/B000/XXXX"
+//! ```
+
+use crate::audit::{self, AuditRecord};
+use crate::email::Email;
+use crate::{paths, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+use url::Url;
+
+/// Discord rejects messages longer than this.
+const DISCORD_MAX_LEN: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Kind {
+    Slack,
+    Discord,
+    /// A generic webhook of the matrix-hookshot bridge.
+    Matrix,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Webhook {
+    pub kind: Kind,
+    pub url: Url,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Notifications {
+    pub webhooks: Vec<Webhook>,
+    pub email: Option<Email>,
+}
+
+static CONFIGURED: RwLock<Notifications> = RwLock::new(Notifications {
+    webhooks: Vec::new(),
+    email: None,
+});
+
+static DESKTOP: AtomicBool = AtomicBool::new(false);
+
+/// Shows a desktop notification for each later summary as well.
+pub(crate) fn enable_desktop() {
+    DESKTOP.store(true, Ordering::Relaxed);
+}
+
+/// Sends the notifications of later runs to `notifications`, replacing any set before.
+pub(crate) fn configure(notifications: &Notifications) {
+    if let Ok(mut configured) = CONFIGURED.write() {
+        *configured = notifications.clone();
+    }
+}
+
+/// The changes and failures of one run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Summary {
+    pub changes: Vec<AuditRecord>,
+    /// Repository names paired with what went wrong.
+    pub failures: Vec<(String, String)>,
+    /// Full names of repositories found violating the policy and left as they are.
+    pub violations: Vec<String>,
+}
+
+impl Summary {
+    /// Collects the changes `actor` recorded in the audit trail since `started`.
+    pub(crate) fn since(
+        started: DateTime<Utc>,
+        actor: &str,
+        failures: Vec<(String, String)>,
+    ) -> Result<Self> {
+        let changes = audit::read_from(&paths::audit_file()?)?
+            .into_iter()
+            .filter(|record| record.timestamp >= started && record.actor == actor)
+            .collect();
+
+        Ok(Self {
+            changes,
+            failures,
+            violations: Vec::new(),
+        })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.failures.is_empty() && self.violations.is_empty()
+    }
+
+    /// The headline, such as "3 repos privatized, 1 failure".
+    pub(crate) fn headline(&self) -> String {
+        let count = |private: bool| {
+            self.changes
+                .iter()
+                .filter(|record| record.field == "private" && record.new == Value::Bool(private))
+                .count()
+        };
+        let plural = |count: usize, noun: &str| match count {
+            1 => format!("1 {noun}"),
+            _ => format!("{count} {noun}s"),
+        };
+        let mut parts = Vec::new();
+        for (count, verb) in [(count(true), "privatized"), (count(false), "publicized")] {
+            if count > 0 {
+                parts.push(format!("{} {verb}", plural(count, "repo")));
+            }
+        }
+        if !self.failures.is_empty() {
+            parts.push(plural(self.failures.len(), "failure"));
+        }
+        if !self.violations.is_empty() {
+            parts.push(plural(self.violations.len(), "policy violation"));
+        }
+
+        parts.join(", ")
+    }
+
+    /// The headline followed by one line per change and failure.
+    pub(crate) fn text(&self) -> String {
+        let mut text = format!("privateer: {}", self.headline());
+        for record in &self.changes {
+            text.push_str(&format!(
+                "\n• `{repo}` {field}: {old} → {new} by {actor}",
+                repo = record.repo,
+                field = record.field,
+                old = record.old,
+                new = record.new,
+                actor = record.actor
+            ));
+            if let Some(request_id) = &record.request_id {
+                text.push_str(&format!(" (request {request_id})"));
+            }
+        }
+        for (name, err) in &self.failures {
+            text.push_str(&format!("\n• `{name}` failed: {err}"));
+        }
+        for name in &self.violations {
+            text.push_str(&format!("\n• `{name}` violates the policy"));
+        }
+
+        text
+    }
+}
+
+/// The body to post to a webhook of `kind`.
+pub(crate) fn payload(kind: Kind, text: &str) -> Value {
+    match kind {
+        Kind::Slack | Kind::Matrix => json!({ "text": text }),
+        Kind::Discord => {
+            let content = match text.char_indices().nth(DISCORD_MAX_LEN - 1) {
+                Some((end, _)) => format!("{}…", &text[..end]),
+                None => text.to_owned(),
+            };
+            json!({ "content": content })
+        }
+    }
+}
+
+/// The desktop notification shown for `summary` with `--notify`, its headline.
+pub(crate) fn desktop_notification(summary: &Summary) -> notify_rust::Notification {
+    notify_rust::Notification::new()
+        .appname(env!("CARGO_PKG_NAME"))
+        .summary("privateer")
+        .body(&summary.headline())
+        .finalize()
+}
+
+fn warn(message: impl std::fmt::Display) {
+    eprintln!("{} {message}", console::style("warning:").yellow().bold());
+}
+
+/// Posts `summary` to every configured webhook and emails it, unless there is nothing to report.
+pub(crate) async fn send(summary: &Summary) {
+    let configured = match CONFIGURED.read() {
+        Ok(configured) => configured.clone(),
+        Err(_) => return,
+    };
+    if summary.is_empty() {
+        return;
+    }
+    let text = summary.text();
+    let client = reqwest::Client::new();
+    for webhook in configured.webhooks {
+        let result = client
+            .post(webhook.url.clone())
+            .header("Content-Type", "application/json")
+            .body(payload(webhook.kind, &text).to_string())
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        // Webhook URLs embed their credentials, so they are kept out of the warning.
+        if let Err(err) = result.map_err(reqwest::Error::without_url) {
+            warn(format!(
+                "Failed to send the {kind:?} notification: {err}",
+                kind = webhook.kind
+            ));
+        }
+    }
+    if DESKTOP.load(Ordering::Relaxed) {
+        let notification = desktop_notification(summary);
+        let shown = tokio::task::spawn_blocking(move || notification.show().map(drop)).await;
+        match shown {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => warn(format!("Failed to show the desktop notification: {err}")),
+            Err(err) => warn(format!("Failed to show the desktop notification: {err}")),
+        }
+    }
+    if let Some(email) = configured.email {
+        let subject = format!("privateer: {}", summary.headline());
+        let sent = tokio::task::spawn_blocking(move || email.send(&subject, text)).await;
+        match sent {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => warn(format!("Failed to send the email notification: {err:#}")),
+            Err(err) => warn(format!("Failed to send the email notification: {err}")),
+        }
+    }
+}
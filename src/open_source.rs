@@ -0,0 +1,165 @@
+//! `privateer open-source`: makes repositories public once they are ready to be.
+//!
+//! Before a repository is made public it must have a license GitHub recognizes, a description,
+//! and secret scanning on. A repository missing any of these is refused, with what it misses,
+//! unless that check is skipped with `--skip-check`; the others are still made public. Open
+//! secret scanning alerts refuse it in any case, like every change to public, unless
+//! `--allow-secret-alerts` is passed.
+
+use crate::apply::{self, Applied};
+use crate::cli::{OpenSourceArgs, Prerequisite};
+use crate::diff::{self, FieldChange};
+use crate::github::{self, Repository, SecretAlert};
+use crate::i18n::t;
+use crate::output::status;
+use crate::plan::SkipReason;
+use crate::{lockdown, secrets};
+use crate::{Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::anyhow;
+use console::style;
+
+/// Checks the repositories of `args` and makes those ready for it public once confirmed.
+pub(crate) async fn run(pat_token: &str, args: &OpenSourceArgs) -> Result<()> {
+    let username = github::get_authenticated_user(pat_token).await?;
+    let repos = lockdown::select_repos(&args.repos, args.all, &username, pat_token).await?;
+
+    let (mut ready, mut refused) = (Vec::new(), Vec::new());
+    for repo in repos {
+        let name = apply::full_name(&repo, &username);
+        if repo.private == Some(false) {
+            // `--all` lists every repository, public or not.
+            if !args.all {
+                status!("{}", t!("open-source-already-public", repo = name.as_str()));
+            }
+            continue;
+        }
+        // Only listed if checked, as listing them takes access to security events; the change
+        // itself still refuses open alerts.
+        let alerts = match args.skip_check.contains(&Prerequisite::SecretScanning) {
+            true => None,
+            false => {
+                github::get_open_secret_alerts(&repo.owner.login, &repo.name, pat_token).await?
+            }
+        };
+        let problems = problems(
+            &repo,
+            alerts.as_deref(),
+            &args.skip_check,
+            secrets::alerts_allowed(),
+        );
+        match problems.is_empty() {
+            true => {
+                let change = FieldChange::visibility(repo.private, false);
+                println!("{}", diff::render(&name, &[change]));
+                ready.push(repo);
+            }
+            false => {
+                eprintln!(
+                    "{ERROR_ICON} {}",
+                    t!("open-source-not-ready", repo = name.as_str())
+                );
+                for (_, problem) in problems {
+                    eprintln!("  - {problem}");
+                }
+                refused.push(name);
+            }
+        }
+    }
+    if ready.is_empty() && refused.is_empty() {
+        status!("{SUCCESS_ICON} {}", t!("open-source-all-public"));
+        return Ok(());
+    }
+    if !ready.is_empty() {
+        println!(
+            "\n{}",
+            style(t!("open-source-ready", count = ready.len())).bold()
+        );
+        lockdown::confirm(args.yes)?;
+    }
+
+    let mut failures = Vec::new();
+    for repo in &ready {
+        let name = apply::full_name(repo, &username);
+        match apply::apply_one(repo, "false", &username, pat_token, &username, &|_| {}).await {
+            Ok(Applied::Skipped) => status!(
+                "{name}: {}",
+                t!(
+                    "change-skipped",
+                    reason = SkipReason::ChangedSincePlanned.describe()
+                )
+            ),
+            Ok(_) => status!(
+                "{SUCCESS_ICON} {}",
+                t!("open-source-made-public", repo = name.as_str())
+            ),
+            Err(err) => {
+                eprintln!("{err}");
+                failures.push(name);
+            }
+        }
+    }
+    match (refused.is_empty(), failures.is_empty()) {
+        (true, true) => Ok(()),
+        (false, _) => Err(anyhow!(
+            "{ERROR_ICON} {}",
+            t!(
+                "open-source-refused",
+                count = refused.len(),
+                repos = refused.join(", ")
+            )
+        )),
+        (true, false) => Err(anyhow!(
+            "{ERROR_ICON} {}",
+            t!(
+                "open-source-failed",
+                count = failures.len(),
+                repos = failures.join(", ")
+            )
+        )),
+    }
+}
+
+/// What `repo` misses before it can be made public, leaving out the `skipped` checks. `alerts`
+/// are its open secret scanning alerts, or `None` if secret scanning is off; they are a problem
+/// that can't be skipped, unless `allow_alerts`.
+pub(crate) fn problems(
+    repo: &Repository,
+    alerts: Option<&[SecretAlert]>,
+    skipped: &[Prerequisite],
+    allow_alerts: bool,
+) -> Vec<(Option<Prerequisite>, String)> {
+    let mut problems = Vec::new();
+    if repo.license.is_none() {
+        problems.push((Some(Prerequisite::License), t!("open-source-no-license")));
+    }
+    if repo
+        .description
+        .as_deref()
+        .is_none_or(|description| description.trim().is_empty())
+    {
+        problems.push((
+            Some(Prerequisite::Description),
+            t!("open-source-no-description"),
+        ));
+    }
+    match alerts {
+        None => problems.push((
+            Some(Prerequisite::SecretScanning),
+            t!("open-source-no-secret-scanning"),
+        )),
+        Some([]) => {}
+        Some(_) if allow_alerts => {}
+        Some(alerts) => problems.push((
+            None,
+            t!(
+                "open-source-secret-alerts",
+                alerts = secrets::summary(alerts)
+            ),
+        )),
+    }
+    problems.retain(|(prerequisite, _)| {
+        prerequisite.is_none_or(|prerequisite| !skipped.contains(&prerequisite))
+    });
+
+    problems
+}
@@ -0,0 +1,165 @@
+//! Global output settings: quiet, verbose and plain modes, colors and icons.
+//!
+//! Configured once from the command line in `main` and consulted wherever privateer prints.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+static PLAIN_ICONS: AtomicBool = AtomicBool::new(false);
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Applies the output settings for this run.
+///
+/// Colors and emoji icons are disabled by `--no-color` or a non-empty `NO_COLOR` environment
+/// variable (see <https://no-color.org>). Emoji icons alone are replaced by ASCII with `--ascii`
+/// or when the terminal is unlikely to render them. `--plain` disables both, as well as anything
+/// that redraws the screen, see [`is_plain`].
+pub(crate) fn init(quiet: bool, verbose: bool, no_color: bool, ascii: bool, plain: bool) {
+    let no_color = colors_disabled(no_color, plain, std::env::var_os("NO_COLOR"));
+    if cfg!(windows) {
+        enable_virtual_terminal();
+    }
+    if no_color {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+    QUIET.store(quiet, Ordering::Relaxed);
+    VERBOSE.store(verbose, Ordering::Relaxed);
+    PLAIN.store(plain, Ordering::Relaxed);
+    PLAIN_ICONS.store(
+        no_color || ascii || !terminal_supports_emoji(),
+        Ordering::Relaxed,
+    );
+}
+
+/// Returns `true` if colors are off, from `--no-color`, `--plain` or the value of `NO_COLOR`,
+/// which only counts when it isn't empty.
+pub(crate) fn colors_disabled(
+    no_color: bool,
+    plain: bool,
+    env: Option<std::ffi::OsString>,
+) -> bool {
+    plain || no_color || env.is_some_and(|value| !value.is_empty())
+}
+
+/// Has the Windows console interpret ANSI escapes, which it doesn't by default in cmd.exe and
+/// PowerShell, turning colors off for a stream whose console is too old to, rather than printing
+/// the escapes as text.
+fn enable_virtual_terminal() {
+    // `console` switches on virtual terminal processing while checking for color support.
+    let stdout = console::Term::stdout();
+    if stdout.is_term() && !stdout.features().colors_supported() {
+        console::set_colors_enabled(false);
+    }
+    let stderr = console::Term::stderr();
+    if stderr.is_term() && !stderr.features().colors_supported() {
+        console::set_colors_enabled_stderr(false);
+    }
+}
+
+/// Returns `false` for piped output, legacy Windows consoles and non-UTF-8 locales, where emoji
+/// render as tofu boxes.
+fn terminal_supports_emoji() -> bool {
+    if !console::Term::stdout().features().wants_emoji() {
+        return false;
+    }
+    // Only Windows Terminal says it wants emoji, not the console of cmd.exe or PowerShell.
+    if cfg!(windows) {
+        return true;
+    }
+    is_utf8_locale(|name| std::env::var(name).ok())
+}
+
+/// Returns `true` unless the locale, read through `var`, uses another encoding than UTF-8.
+pub(crate) fn is_utf8_locale(var: impl Fn(&str) -> Option<String>) -> bool {
+    // The first locale variable that is set decides, as in `setlocale(3)`.
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| var(name).filter(|value| !value.is_empty()))
+        .is_none_or(|locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
+
+/// Returns `true` if only errors and final summaries should be printed.
+pub(crate) fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Returns `true` if privateer should explain what it does, such as which files it read.
+pub(crate) fn is_verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Returns `true` if output must only ever be appended, for screen readers: no progress bars,
+/// and numbered lists to answer by number instead of widgets that move the cursor.
+pub(crate) fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// Returns `true` if emoji are disabled and plain text should be printed instead.
+pub(crate) fn plain_icons() -> bool {
+    PLAIN_ICONS.load(Ordering::Relaxed)
+}
+
+/// Prints like `println!`, unless `--quiet` was given.
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use status;
+
+/// Prints like `eprintln!`, unless `--quiet` was given. For progress, such as how many
+/// repositories were listed, which must stay out of what a command prints for scripts.
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+pub(crate) use progress;
+
+/// Prints like `eprintln!`, only with `--verbose`.
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::output::is_verbose() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+pub(crate) use verbose;
+
+/// An icon prefixing success and error messages, rendered as plain text when emoji are disabled.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Icon {
+    Success,
+    Error,
+    Warning,
+}
+
+impl Icon {
+    /// The emoji of the icon, or its plain text if `plain`.
+    pub(crate) fn text(self, plain: bool) -> &'static str {
+        match (self, plain) {
+            (Icon::Success, false) => "\u{2705}", // ✅ green_check_unicode.
+            (Icon::Error, false) => "\u{274C}",   // ❌ red_x_unicode.
+            (Icon::Warning, false) => "\u{26A0}\u{FE0F}", // ⚠️ warning sign.
+            (Icon::Success, true) => "[OK]",
+            (Icon::Error, true) => "[FAIL]",
+            (Icon::Warning, true) => "[WARN]",
+        }
+    }
+}
+
+impl fmt::Display for Icon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text(plain_icons()))
+    }
+}
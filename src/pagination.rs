@@ -0,0 +1,134 @@
+//! Walking the pages of a GitHub listing.
+//!
+//! Listings are requested 100 items at a time, the most GitHub allows, and the next page is the
+//! `rel="next"` URL of the `Link` header rather than a page number counted locally, so listings
+//! that GitHub pages by cursor work too. Every page goes through [`github::send`], and so waits
+//! out the rate limit and the request budget like any other request.
+
+use crate::github::{self, api_error};
+use crate::{cancel, Result, ERROR_ICON};
+use anyhow::anyhow;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use url::Url;
+
+/// Items per page; the maximum for every listing privateer uses.
+const PER_PAGE: &str = "100";
+
+/// Most pages allocated for up front, whatever the `Link` header claims.
+const MAX_RESERVED_PAGES: usize = 1_000;
+
+/// The `rel="next"` URL of a `Link` header, such as
+/// `<https://api.github.com/user/repos?page=2>; rel="next", <…?page=5>; rel="last"`.
+pub(crate) fn next_link(link: &str) -> Option<Url> {
+    link_with_rel(link, "next")
+}
+
+/// The page number of the `rel="last"` URL of a `Link` header, to size a listing up front.
+pub(crate) fn last_page(link: &str) -> Option<usize> {
+    link_with_rel(link, "last")?
+        .query_pairs()
+        .find(|(name, _)| name == "page")?
+        .1
+        .parse()
+        .ok()
+}
+
+fn link_with_rel(link: &str, rel: &str) -> Option<Url> {
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        let matches = params.split(';').any(|param| {
+            param
+                .trim()
+                .strip_prefix("rel=")
+                .is_some_and(|value| value.trim_matches('"') == rel)
+        });
+        let url = url.trim().strip_prefix('<')?.strip_suffix('>')?;
+        match matches {
+            true => Url::parse(url).ok(),
+            false => None,
+        }
+    })
+}
+
+/// The pages of the listing at an API URL, each a `Vec<T>`.
+pub(crate) struct Paginator<T> {
+    next: Option<Url>,
+    pat_token: String,
+    pages: usize,
+    /// Number of the last page, from the `Link` header of the first one.
+    last: Option<usize>,
+    items: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Paginator<T> {
+    /// The listing at `url`, requested with `pat_token`. `per_page` is added unless `url` sets it.
+    pub(crate) fn new(mut url: Url, pat_token: &str) -> Self {
+        if !url.query_pairs().any(|(name, _)| name == "per_page") {
+            url.query_pairs_mut().append_pair("per_page", PER_PAGE);
+        }
+
+        Self {
+            next: Some(url),
+            pat_token: pat_token.to_owned(),
+            pages: 0,
+            last: None,
+            items: PhantomData,
+        }
+    }
+
+    /// Number of pages fetched so far.
+    pub(crate) fn pages(&self) -> usize {
+        self.pages
+    }
+
+    /// Fetches the next page, or returns `None` after the last one.
+    ///
+    /// Fails with [`cancel::Cancelled`] if the token in scope was cancelled, before asking for it.
+    pub(crate) async fn next_page(&mut self) -> Result<Option<Vec<T>>> {
+        cancel::check()?;
+        let Some(url) = self.next.take() else {
+            return Ok(None);
+        };
+        let path = url.path().to_owned();
+        let request = github::api_request(Method::GET, url, &self.pat_token)?;
+        let response = github::send(request).await?;
+        if !response.status.is_success() {
+            return Err(anyhow!(
+                "{ERROR_ICON} Failed to fetch page {page} of `{path}`: {err}",
+                page = self.pages + 1,
+                err = api_error(&response)
+            ));
+        }
+        let items: Vec<T> = serde_json::from_str(&response.body)?;
+        self.pages += 1;
+        let link = response.header_str("link");
+        if self.pages == 1 {
+            self.last = link.and_then(last_page);
+        }
+        // An empty page ends the listing even if GitHub links another one.
+        if !items.is_empty() {
+            self.next = link.and_then(next_link);
+        }
+
+        Ok(Some(items))
+    }
+
+    /// Fetches every remaining page, calling `on_page` with the number of items so far after each.
+    ///
+    /// The items are allocated once, for as many pages as the first one says there are.
+    pub(crate) async fn collect(mut self, on_page: impl Fn(&Self, usize)) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        while let Some(page) = self.next_page().await? {
+            if items.is_empty() {
+                let pages = self.last.unwrap_or(1).min(MAX_RESERVED_PAGES);
+                items.reserve(pages.saturating_mul(page.len()));
+            }
+            items.extend(page);
+            on_page(&self, items.len());
+        }
+
+        Ok(items)
+    }
+}
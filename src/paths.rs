@@ -0,0 +1,97 @@
+//! Locations of files privateer keeps between runs.
+
+use crate::Result;
+use anyhow::anyhow;
+use std::path::PathBuf;
+
+/// Returns privateer's state directory, creating it if needed.
+///
+/// Uses `$XDG_STATE_HOME/privateer`, falling back to `~/.local/state/privateer`, or to
+/// `%LOCALAPPDATA%\privateer` on Windows.
+pub(crate) fn state_dir() -> Result<PathBuf> {
+    app_dir("XDG_STATE_HOME", "LOCALAPPDATA", &[".local", "state"])
+}
+
+/// Returns privateer's configuration directory, creating it if needed.
+///
+/// Uses `$XDG_CONFIG_HOME/privateer`, falling back to `~/.config/privateer`, or to
+/// `%APPDATA%\privateer` on Windows.
+pub(crate) fn config_dir() -> Result<PathBuf> {
+    app_dir("XDG_CONFIG_HOME", "APPDATA", &[".config"])
+}
+
+/// Returns privateer's cache directory, creating it if needed.
+///
+/// Uses `$XDG_CACHE_HOME/privateer`, falling back to `~/.cache/privateer`, or to
+/// `%LOCALAPPDATA%\privateer` on Windows.
+pub(crate) fn cache_dir() -> Result<PathBuf> {
+    app_dir("XDG_CACHE_HOME", "LOCALAPPDATA", &[".cache"])
+}
+
+fn app_dir(var: &str, windows_var: &str, fallback: &[&str]) -> Result<PathBuf> {
+    let env = |name: &str| std::env::var_os(name).filter(|dir| !dir.is_empty());
+    let base = match env(var) {
+        Some(dir) => PathBuf::from(dir),
+        // Windows keeps application data in known folders rather than dotfiles under `HOME`,
+        // which usually isn't even set.
+        None if cfg!(windows) => env(windows_var).map(PathBuf::from).ok_or_else(|| {
+            anyhow!("Could not determine the folder for application data, `%{windows_var}%`")
+        })?,
+        None => std::env::var_os("HOME")
+            .map(|home| {
+                fallback
+                    .iter()
+                    .fold(PathBuf::from(home), |path, part| path.join(part))
+            })
+            .ok_or_else(|| anyhow!("Could not determine the home directory"))?,
+    };
+    let dir = base.join(env!("CARGO_PKG_NAME"));
+    std::fs::create_dir_all(&dir)?;
+
+    Ok(dir)
+}
+
+/// Path of the configuration file read by `privateer daemon`.
+pub(crate) fn config_file() -> Result<PathBuf> {
+    Ok(config_dir()?.join("config.toml"))
+}
+
+/// Path of the append-only audit trail.
+pub(crate) fn audit_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("audit.jsonl"))
+}
+
+/// Path of the baseline saved by `privateer snapshot`.
+pub(crate) fn snapshot_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("snapshot.json"))
+}
+
+/// Path of the changes left by an unfinished chunked apply.
+pub(crate) fn checkpoint_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("checkpoint.json"))
+}
+
+/// Path of the time of the last check made by `privateer watch`.
+pub(crate) fn watch_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("watch.json"))
+}
+
+/// Path of the time of the last successful enforcement run.
+pub(crate) fn heartbeat_file() -> Result<PathBuf> {
+    Ok(state_dir()?.join("heartbeat.json"))
+}
+
+/// Path of the repositories fetched by recent interactive runs.
+pub(crate) fn repo_cache_file() -> Result<PathBuf> {
+    Ok(cache_dir()?.join("repos.json"))
+}
+
+/// Path of the lock file held while a run may change repositories or state.
+///
+/// Uses `$XDG_RUNTIME_DIR`, which is cleared on logout, falling back to the state directory.
+pub(crate) fn lock_file() -> Result<PathBuf> {
+    match std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        Some(dir) => Ok(PathBuf::from(dir).join(concat!(env!("CARGO_PKG_NAME"), ".lock"))),
+        None => Ok(state_dir()?.join("run.lock")),
+    }
+}
@@ -0,0 +1,721 @@
+//! Fuzzy-filterable multi-select used to choose repositories.
+//!
+//! `/` starts a search: typing then narrows the list live, highlighting the matched characters,
+//! until `Enter` or `Esc` returns to the list. There `Space` toggles the highlighted item, `a`
+//! selects all filtered items, `i` inverts and `n` clears their selection, and `Enter` confirms.
+//! Selections survive changes to the filter, so items can be picked across several searches. The
+//! list is rendered on stderr, like dialoguer's prompts.
+//!
+//! When items belong to more than one group (e.g. repository owners), they are listed under group
+//! headers: `Space` on a header toggles every filtered item of the group and `Tab` collapses or
+//! expands it.
+//!
+//! `p` opens a preview of the highlighted item below the list, and closes it again, as `Enter`
+//! does while nothing is selected, since there is nothing to confirm yet. `o` opens the
+//! highlighted item's web page in the default browser; `y` copies it to the clipboard and `Y`
+//! copies the item's command (e.g. `git clone …`) instead.
+//!
+//! Only one page of rows is drawn at a time, so accounts with thousands of repositories stay
+//! responsive; `PageUp`/`PageDown` and `Home`/`End` move by pages or to either end.
+//!
+//! With `--plain` the items are printed once as a numbered list instead, and selected by typing
+//! their numbers, see [`parse_numbers`].
+
+use crate::i18n::t;
+use crate::{format, output, prompter, shell, Result, ERROR_ICON};
+use anyhow::anyhow;
+use console::{style, Key, Term};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// A selectable row: `key` is matched against the filter, `detail` is shown after it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PickerItem {
+    pub key: String,
+    pub detail: String,
+    /// Name of the group the item is listed under, if any.
+    pub group: Option<String>,
+    /// Lines shown below the list for the highlighted item when the preview is open.
+    pub preview: Vec<String>,
+    /// Web page of the item, opened in the browser with `o` and copied with `y`.
+    pub url: Option<String>,
+    /// Shell command copied with `Y`, such as a `git clone`.
+    pub command: Option<String>,
+    /// Whether the item starts out selected.
+    pub checked: bool,
+    /// Visibility of the repo, for the `v` filter; `None` if it is not known.
+    pub private: Option<bool>,
+    /// Whether the repo is a fork, for the `f` filter.
+    pub fork: bool,
+    /// Whether the repo is archived, for the `r` filter.
+    pub archived: bool,
+}
+
+/// An item matching the current filter, with the char positions of `key` that matched.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Match {
+    pub index: usize,
+    pub positions: Vec<usize>,
+}
+
+/// Returns the items matching `query`, best match first; all items in order if it is empty.
+pub(crate) fn filter(items: &[PickerItem], query: &str) -> Vec<Match> {
+    if query.is_empty() {
+        return (0..items.len())
+            .map(|index| Match {
+                index,
+                positions: Vec::new(),
+            })
+            .collect();
+    }
+
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut scored: Vec<(i64, Match)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let (score, positions) = matcher.fuzzy_indices(&item.key, query)?;
+            Some((score, Match { index, positions }))
+        })
+        .collect();
+    // Stable sort keeps the original order among equally good matches.
+    scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    scored.into_iter().map(|(_, m)| m).collect()
+}
+
+/// Keyboard shortcuts listed by the `?` help overlay.
+const KEYBINDINGS: &[(&str, &str)] = &[
+    (
+        "space",
+        "select or deselect the highlighted repo, or a whole group",
+    ),
+    (
+        "enter",
+        "confirm the selection; with none, show or hide the preview",
+    ),
+    ("/", "filter by name; enter or esc when done"),
+    ("esc", "clear the filter"),
+    (
+        "a / i / n",
+        "select all / invert / select none of the filtered repos",
+    ),
+    ("tab", "collapse or expand the highlighted group"),
+    ("v", "cycle showing all / only private / only public repos"),
+    ("f", "cycle showing all / only forks / no forks"),
+    ("r", "cycle showing all / only archived / no archived repos"),
+    ("p", "show or hide the preview of the highlighted repo"),
+    ("o", "open the highlighted repo in the browser"),
+    (
+        "y / Y",
+        "copy the url / clone command of the highlighted repo",
+    ),
+    (
+        "\u{2191}\u{2193} pgup pgdn",
+        "move; home/end jump to the first/last row",
+    ),
+    ("?", "show or hide this help"),
+    ("ctrl-c", "quit"),
+];
+
+/// A filter on a yes/no attribute of the items, cycled with a key.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum Toggle {
+    #[default]
+    Any,
+    Only,
+    Hide,
+}
+
+impl Toggle {
+    fn next(self) -> Self {
+        match self {
+            Toggle::Any => Toggle::Only,
+            Toggle::Only => Toggle::Hide,
+            Toggle::Hide => Toggle::Any,
+        }
+    }
+
+    /// Returns `true` if an item with the attribute set to `value` passes; unknown values only
+    /// pass `Any`.
+    fn allows(self, value: Option<bool>) -> bool {
+        match self {
+            Toggle::Any => true,
+            Toggle::Only => value == Some(true),
+            Toggle::Hide => value == Some(false),
+        }
+    }
+}
+
+/// Whether keys are shortcuts or edit the search query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Normal,
+    Search,
+}
+
+/// A line of the list: either a group header or a matching item.
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    Header(usize),
+    Item(Match),
+}
+
+/// What happened after a key press.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Outcome {
+    Continue,
+    Confirm,
+    /// The URL of the highlighted item should be opened in the browser.
+    Open(String),
+    /// The text should be copied to the clipboard.
+    Copy(String),
+}
+
+pub(crate) struct Picker {
+    prompt: String,
+    items: Vec<PickerItem>,
+    checked: Vec<bool>,
+    query: String,
+    mode: Mode,
+    /// Filters on the visibility, fork and archived state of the items.
+    visibility: Toggle,
+    forks: Toggle,
+    archived: Toggle,
+    matches: Vec<Match>,
+    /// Distinct groups in order of first appearance; empty unless there are at least two.
+    groups: Vec<String>,
+    /// Index into `groups` of every item.
+    item_groups: Vec<Option<usize>>,
+    collapsed: Vec<bool>,
+    rows: Vec<Row>,
+    /// Widest key, so the details line up in columns.
+    key_width: usize,
+    /// Number of rows drawn at a time.
+    page: usize,
+    /// Whether the preview of the highlighted item is shown.
+    preview: bool,
+    /// Whether the help overlay replaces the list.
+    help: bool,
+    /// Feedback shown below the list until the next key press.
+    message: Option<String>,
+    /// Position of the highlighted row within `rows`.
+    cursor: usize,
+    /// Position within `rows` of the first visible row.
+    offset: usize,
+}
+
+impl Picker {
+    pub(crate) fn new(prompt: impl Into<String>, items: Vec<PickerItem>) -> Self {
+        let mut groups: Vec<String> = Vec::new();
+        for group in items.iter().filter_map(|item| item.group.as_ref()) {
+            if !groups.contains(group) {
+                groups.push(group.clone());
+            }
+        }
+        if groups.len() < 2 {
+            groups.clear();
+        }
+        let item_groups = items
+            .iter()
+            .map(|item| {
+                let group = item.group.as_ref()?;
+                groups.iter().position(|g| g == group)
+            })
+            .collect();
+        let key_width = items
+            .iter()
+            .map(|item| console::measure_text_width(&item.key))
+            .max()
+            .unwrap_or(0);
+
+        let mut picker = Self {
+            prompt: prompt.into(),
+            checked: items.iter().map(|item| item.checked).collect(),
+            collapsed: vec![false; groups.len()],
+            groups,
+            item_groups,
+            key_width,
+            page: 10,
+            preview: false,
+            help: false,
+            message: None,
+            items,
+            query: String::new(),
+            mode: Mode::Normal,
+            visibility: Toggle::Any,
+            forks: Toggle::Any,
+            archived: Toggle::Any,
+            matches: Vec::new(),
+            rows: Vec::new(),
+            cursor: 0,
+            offset: 0,
+        };
+        picker.refilter();
+        picker
+    }
+
+    fn group_of(&self, index: usize) -> Option<usize> {
+        self.item_groups[index]
+    }
+
+    /// Indices of the checked items, in their original order.
+    pub(crate) fn selection(&self) -> Vec<usize> {
+        (0..self.items.len()).filter(|&i| self.checked[i]).collect()
+    }
+
+    fn refilter(&mut self) {
+        self.matches = filter(&self.items, &self.query);
+        self.matches.retain(|m| {
+            let item = &self.items[m.index];
+            self.visibility.allows(item.private)
+                && self.forks.allows(Some(item.fork))
+                && self.archived.allows(Some(item.archived))
+        });
+        self.rebuild_rows();
+        self.cursor = 0;
+        self.offset = 0;
+    }
+
+    /// Lays out the matches under their group headers, skipping items of collapsed groups.
+    fn rebuild_rows(&mut self) {
+        if self.groups.is_empty() {
+            self.rows = self.matches.iter().cloned().map(Row::Item).collect();
+            self.cursor = self.cursor.min(self.rows.len().saturating_sub(1));
+            return;
+        }
+
+        let mut members: Vec<Vec<&Match>> = vec![Vec::new(); self.groups.len()];
+        let mut ungrouped = Vec::new();
+        for m in &self.matches {
+            match self.group_of(m.index) {
+                Some(group) => members[group].push(m),
+                None => ungrouped.push(m),
+            }
+        }
+        let mut rows = Vec::with_capacity(self.matches.len() + self.groups.len());
+        for (group, members) in members.into_iter().enumerate() {
+            if members.is_empty() {
+                continue;
+            }
+            rows.push(Row::Header(group));
+            if !self.collapsed[group] {
+                rows.extend(members.into_iter().cloned().map(Row::Item));
+            }
+        }
+        rows.extend(ungrouped.into_iter().cloned().map(Row::Item));
+
+        self.rows = rows;
+        self.cursor = self.cursor.min(self.rows.len().saturating_sub(1));
+    }
+
+    /// Labels of the active attribute filters, shown after the query.
+    fn toggle_labels(&self) -> String {
+        let labels = [
+            match self.visibility {
+                Toggle::Any => None,
+                Toggle::Only => Some("private only"),
+                Toggle::Hide => Some("public only"),
+            },
+            match self.forks {
+                Toggle::Any => None,
+                Toggle::Only => Some("forks only"),
+                Toggle::Hide => Some("no forks"),
+            },
+            match self.archived {
+                Toggle::Any => None,
+                Toggle::Only => Some("archived only"),
+                Toggle::Hide => Some("no archived"),
+            },
+        ];
+
+        labels
+            .into_iter()
+            .flatten()
+            .map(|label| format!("  {}", style(format!("[{label}]")).magenta()))
+            .collect()
+    }
+
+    /// Applies `f` to the checked state of every item matching the current filter.
+    fn update_filtered(&mut self, f: impl Fn(bool) -> bool) {
+        for m in &self.matches {
+            self.checked[m.index] = f(self.checked[m.index]);
+        }
+    }
+
+    /// Checks every filtered item of `group`, or unchecks them if they all are already.
+    fn toggle_group(&mut self, group: usize) {
+        let members: Vec<usize> = self
+            .matches
+            .iter()
+            .map(|m| m.index)
+            .filter(|&index| self.group_of(index) == Some(group))
+            .collect();
+        let check = !members.iter().all(|&index| self.checked[index]);
+        for index in members {
+            self.checked[index] = check;
+        }
+    }
+
+    /// Updates the state for a key press.
+    pub(crate) fn handle_key(&mut self, key: Key) -> Outcome {
+        self.message = None;
+        // Any key closes the help overlay.
+        if self.help {
+            self.help = false;
+            return Outcome::Continue;
+        }
+        match (self.mode, key) {
+            (_, Key::ArrowUp) => self.cursor = self.cursor.saturating_sub(1),
+            (_, Key::ArrowDown) => {
+                self.cursor = (self.cursor + 1).min(self.rows.len().saturating_sub(1))
+            }
+            (_, Key::PageUp) => self.cursor = self.cursor.saturating_sub(self.page),
+            (_, Key::PageDown) => {
+                self.cursor = (self.cursor + self.page).min(self.rows.len().saturating_sub(1))
+            }
+            (_, Key::Home) => self.cursor = 0,
+            (_, Key::End) => self.cursor = self.rows.len().saturating_sub(1),
+            (Mode::Search, Key::Enter | Key::Escape) => self.mode = Mode::Normal,
+            (Mode::Search, Key::Backspace) if !self.query.is_empty() => {
+                self.query.pop();
+                self.refilter();
+            }
+            (Mode::Search, Key::Char(c)) if !c.is_control() => {
+                self.query.push(c);
+                self.refilter();
+            }
+            (Mode::Normal, Key::Enter) if !self.checked.contains(&true) => {
+                self.preview = !self.preview
+            }
+            (Mode::Normal, Key::Enter) => return Outcome::Confirm,
+            (Mode::Normal, Key::Char('/')) => self.mode = Mode::Search,
+            (Mode::Normal, Key::Escape) if !self.query.is_empty() => {
+                self.query.clear();
+                self.refilter();
+            }
+            (Mode::Normal, Key::Char(' ')) => match self.rows.get(self.cursor) {
+                Some(Row::Item(m)) => self.checked[m.index] = !self.checked[m.index],
+                Some(&Row::Header(group)) => self.toggle_group(group),
+                None => {}
+            },
+            (Mode::Normal, Key::Tab) => {
+                if let Some(&Row::Header(group)) = self.rows.get(self.cursor) {
+                    self.collapsed[group] = !self.collapsed[group];
+                    self.rebuild_rows();
+                }
+            }
+            (Mode::Normal, Key::Char('?')) => self.help = true,
+            (Mode::Normal, Key::Char('v')) => {
+                self.visibility = self.visibility.next();
+                self.refilter();
+            }
+            (Mode::Normal, Key::Char('f')) => {
+                self.forks = self.forks.next();
+                self.refilter();
+            }
+            (Mode::Normal, Key::Char('r')) => {
+                self.archived = self.archived.next();
+                self.refilter();
+            }
+            (Mode::Normal, Key::Char('p')) => self.preview = !self.preview,
+            (Mode::Normal, Key::Char('o')) => {
+                if let Some(Row::Item(m)) = self.rows.get(self.cursor) {
+                    match &self.items[m.index].url {
+                        Some(url) => return Outcome::Open(url.clone()),
+                        None => self.message = Some("No web page to open".to_owned()),
+                    }
+                }
+            }
+            (Mode::Normal, Key::Char(c @ ('y' | 'Y'))) => {
+                if let Some(Row::Item(m)) = self.rows.get(self.cursor) {
+                    let item = &self.items[m.index];
+                    let text = match c {
+                        'y' => &item.url,
+                        _ => &item.command,
+                    };
+                    match text {
+                        Some(text) => return Outcome::Copy(text.clone()),
+                        None => self.message = Some("Nothing to copy".to_owned()),
+                    }
+                }
+            }
+            (Mode::Normal, Key::Char('a')) => self.update_filtered(|_| true),
+            (Mode::Normal, Key::Char('i')) => self.update_filtered(|checked| !checked),
+            (Mode::Normal, Key::Char('n')) => self.update_filtered(|_| false),
+            _ => {}
+        }
+
+        Outcome::Continue
+    }
+
+    /// Renders the prompt, filter and at most `rows` items around the cursor.
+    pub(crate) fn render(&mut self, rows: usize) -> Vec<String> {
+        let preview = match (self.preview, self.rows.get(self.cursor)) {
+            (true, Some(Row::Item(m))) => self.items[m.index].preview.clone(),
+            _ => Vec::new(),
+        };
+        // The preview takes its room from the list, which always keeps a few rows.
+        let rows = match preview.is_empty() {
+            true => rows,
+            false => rows.saturating_sub(preview.len() + 1).max(3),
+        };
+        self.page = rows;
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + rows {
+            self.offset = self.cursor + 1 - rows;
+        }
+
+        let (hint, cursor) = match (self.help, self.mode) {
+            (true, _) => ("(press any key to close the help)", ""),
+            (false, Mode::Normal) => (
+                "(space to select, enter to confirm, / to filter, ? for help)",
+                "",
+            ),
+            (false, Mode::Search) => ("(type to filter, enter or esc when done)", "_"),
+        };
+        let mut lines = vec![
+            format!(
+                "{} {} {}",
+                style("?").yellow(),
+                style(&self.prompt).bold(),
+                style(hint).dim()
+            ),
+            format!(
+                "  {} {}{cursor}{toggles}",
+                style("filter:").dim(),
+                self.query,
+                toggles = self.toggle_labels()
+            ),
+        ];
+        if self.help {
+            let width = KEYBINDINGS
+                .iter()
+                .map(|(keys, _)| keys.chars().count())
+                .max();
+            lines.extend(KEYBINDINGS.iter().map(|(keys, action)| {
+                format!(
+                    "  {keys:<width$}  {action}",
+                    keys = style(keys).cyan(),
+                    width = width.unwrap_or(0)
+                )
+            }));
+            return lines;
+        }
+        if self.matches.is_empty() {
+            lines.push(style("  no matching repositories").dim().to_string());
+        }
+        // Count the filtered and checked members of every group for the headers.
+        let mut group_counts = vec![(0, 0); self.groups.len()];
+        for m in &self.matches {
+            if let Some(group) = self.group_of(m.index) {
+                group_counts[group].0 += 1;
+                group_counts[group].1 += usize::from(self.checked[m.index]);
+            }
+        }
+        for (row, line) in self.rows.iter().enumerate().skip(self.offset).take(rows) {
+            let pointer = match row == self.cursor {
+                true => style(">").cyan().to_string(),
+                false => " ".to_owned(),
+            };
+            let m = match line {
+                Row::Item(m) => m,
+                &Row::Header(group) => {
+                    let (total, checked) = group_counts[group];
+                    let arrow = match self.collapsed[group] {
+                        true => "\u{25B8}",  // ▸ collapsed.
+                        false => "\u{25BE}", // ▾ expanded.
+                    };
+                    lines.push(format!(
+                        "{pointer} {arrow} {group} {count}",
+                        group = style(&self.groups[group]).bold(),
+                        count = style(format!("({checked}/{total} selected)")).dim(),
+                    ));
+                    continue;
+                }
+            };
+            let item = &self.items[m.index];
+            let checkbox = match self.checked[m.index] {
+                true => style("[x]").green().to_string(),
+                false => "[ ]".to_owned(),
+            };
+            let key: String = item
+                .key
+                .chars()
+                .enumerate()
+                .map(|(i, c)| match m.positions.contains(&i) {
+                    true => style(c).cyan().bold().underlined().to_string(),
+                    false => c.to_string(),
+                })
+                .collect();
+            let padding = " ".repeat(self.key_width - console::measure_text_width(&item.key));
+            let indent = match self.groups.is_empty() {
+                true => "",
+                false => "  ",
+            };
+            lines.push(format!(
+                "{pointer} {indent}{checkbox} {key}{padding} {}",
+                item.detail
+            ));
+        }
+        if self.rows.len() > rows {
+            lines.push(
+                style(format!(
+                    "  {position}/{total}{of} (pgup/pgdn to scroll)",
+                    position = format::thousands(self.cursor + 1),
+                    total = format::thousands(self.rows.len()),
+                    of = match self.matches.len() < self.items.len() {
+                        true => format!(" of {}", format::thousands(self.items.len())),
+                        false => String::new(),
+                    },
+                ))
+                .dim()
+                .to_string(),
+            );
+        }
+        if let Some(message) = &self.message {
+            lines.push(format!("  {}", style(message).yellow()));
+        }
+        if !preview.is_empty() {
+            lines.push(
+                style(format!("  {}", "\u{2500}".repeat(40)))
+                    .dim()
+                    .to_string(),
+            );
+            lines.extend(preview.iter().map(|line| format!("  {line}")));
+        }
+
+        lines
+    }
+
+    /// Runs the picker until `Enter` and returns the indices of the checked items.
+    pub(crate) fn interact(mut self) -> Result<Vec<usize>> {
+        if output::is_plain() {
+            return self.interact_plain();
+        }
+        let term = Term::stderr();
+        if !term.is_term() {
+            return Err(anyhow!(
+                "The repository selector requires an interactive terminal"
+            ));
+        }
+        // Leave room for the prompt, filter, position indicator and the line being typed.
+        let rows = (term.size().0 as usize).saturating_sub(5).max(5);
+
+        // The clipboard is kept open for the whole session: on X11 the copied text is only
+        // available while its owner is alive.
+        let mut clipboard: Option<arboard::Clipboard> = None;
+        term.hide_cursor()?;
+        let mut drawn = 0;
+        loop {
+            let lines = self.render(rows);
+            if drawn > 0 {
+                term.clear_last_lines(drawn)?;
+            }
+            for line in &lines {
+                term.write_line(line)?;
+            }
+            drawn = lines.len();
+
+            match self.handle_key(term.read_key()?) {
+                Outcome::Continue => {}
+                Outcome::Confirm => break,
+                Outcome::Open(url) => {
+                    if let Err(err) = shell::open_in_browser(&url) {
+                        self.message = Some(err.to_string());
+                    }
+                }
+                Outcome::Copy(text) => {
+                    let copied = match clipboard.as_mut() {
+                        Some(clipboard) => clipboard.set_text(text.clone()),
+                        None => arboard::Clipboard::new().and_then(|mut new| {
+                            let copied = new.set_text(text.clone());
+                            clipboard = Some(new);
+                            copied
+                        }),
+                    };
+                    self.message = Some(match copied {
+                        Ok(()) => format!("Copied `{text}`"),
+                        Err(err) => format!("Failed to copy to the clipboard: {err}"),
+                    });
+                }
+            }
+        }
+        term.clear_last_lines(drawn)?;
+        term.show_cursor()?;
+
+        let selection = self.selection();
+        term.write_line(&format!(
+            "{} {} {}",
+            style("\u{2714}").green(),
+            style(&self.prompt).bold(),
+            style(format!("{} selected", selection.len())).dim()
+        ))?;
+
+        Ok(selection)
+    }
+
+    /// Prints the items as a numbered list and reads the numbers of those to select, for
+    /// `--plain`. Nothing is redrawn, so screen readers can follow along.
+    fn interact_plain(self) -> Result<Vec<usize>> {
+        println!("{}", self.prompt);
+        for (index, item) in self.items.iter().enumerate() {
+            let key = match self.group_of(index) {
+                Some(group) => format!("{}/{}", self.groups[group], item.key),
+                None => item.key.clone(),
+            };
+            println!(
+                "{number:>4}. {mark} {key} {detail}",
+                number = index + 1,
+                mark = if self.checked[index] { "[x]" } else { "[ ]" },
+                detail = item.detail.split_whitespace().collect::<Vec<_>>().join(" "),
+            );
+        }
+        let preselected = self.selection();
+        loop {
+            let input = prompter::prompt_user_input(&t!(
+                "picker-numbers-prompt",
+                count = preselected.len()
+            ))?;
+            if input.is_empty() {
+                return Ok(preselected);
+            }
+            match parse_numbers(&input, self.items.len()) {
+                Ok(selection) => return Ok(selection),
+                Err(err) => println!("{ERROR_ICON} {err}"),
+            }
+        }
+    }
+}
+
+/// Parses the numbers of the items to select out of `count`, such as `1, 3, 5-7`, or `all`, into
+/// their indices in order. Numbers start at 1, as listed by the plain picker.
+pub(crate) fn parse_numbers(input: &str, count: usize) -> std::result::Result<Vec<usize>, String> {
+    if input.trim().eq_ignore_ascii_case("all") {
+        return Ok((0..count).collect());
+    }
+    let number = |text: &str| -> std::result::Result<usize, String> {
+        let number: usize = text
+            .trim()
+            .parse()
+            .map_err(|_| t!("picker-not-a-number", input = text.trim().to_owned()))?;
+        match (1..=count).contains(&number) {
+            true => Ok(number - 1),
+            false => Err(t!("picker-out-of-range", number = number, count = count)),
+        }
+    };
+    let mut selected = vec![false; count];
+    for part in input
+        .split([',', ' '])
+        .filter(|part| !part.trim().is_empty())
+    {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (number(first)?, number(last)?),
+            None => (number(part)?, number(part)?),
+        };
+        selected[first.min(last)..=first.max(last)].fill(true);
+    }
+
+    Ok((0..count).filter(|&index| selected[index]).collect())
+}
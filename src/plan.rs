@@ -0,0 +1,166 @@
+//! Deciding what a run changes, apart from how it asks and reports.
+//!
+//! Everything here is a pure function of repositories and the settings wanted for them: no
+//! prompts, no output and no requests. The command, the TUI, the daemon and the library then
+//! plan alike, and only differ in how they gather the wanted settings and show the result.
+
+use crate::apply;
+use crate::diff::FieldChange;
+use crate::github::Repository;
+use crate::i18n::t;
+use crate::lists;
+use crate::policy::Policy;
+use crate::Result;
+use serde::Serialize;
+
+/// A repository paired with the visibility wanted for it, `true` for private.
+pub(crate) type Wanted = (Repository, bool);
+
+/// One repository of a plan, with the change it needs.
+#[derive(Debug, Clone)]
+pub(crate) struct Step {
+    pub repo: Repository,
+    pub private: bool,
+    pub change: FieldChange,
+    /// Why the repository is left alone, if it is.
+    pub skip: Option<SkipReason>,
+}
+
+/// Why a repository is left alone, named in `snake_case` in JSON output for scripts to act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SkipReason {
+    /// It already has the visibility wanted.
+    AlreadyDesiredState,
+    /// The policy exempts it with `allow`, or it is on the `--allowlist`.
+    Protected,
+    /// It is a fork, whose visibility GitHub doesn't change.
+    Fork,
+    /// The token can't administer it.
+    NoAdmin,
+    /// It is archived, and so read-only.
+    Archived,
+    /// It is on the `--denylist`.
+    Filtered,
+    /// It changed on GitHub since it was planned, and `--on-conflict` left it alone.
+    ChangedSincePlanned,
+}
+
+impl SkipReason {
+    /// The name of the reason in JSON output, such as `no_admin`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            SkipReason::AlreadyDesiredState => "already_desired_state",
+            SkipReason::Protected => "protected",
+            SkipReason::Fork => "fork",
+            SkipReason::NoAdmin => "no_admin",
+            SkipReason::Archived => "archived",
+            SkipReason::Filtered => "filtered",
+            SkipReason::ChangedSincePlanned => "changed_since_planned",
+        }
+    }
+
+    /// The reason in words, for people.
+    pub(crate) fn describe(self) -> String {
+        match self {
+            SkipReason::AlreadyDesiredState => t!("skip-already-desired-state"),
+            SkipReason::Protected => t!("skip-protected"),
+            SkipReason::Fork => t!("skip-fork"),
+            SkipReason::NoAdmin => t!("skip-no-admin"),
+            SkipReason::Archived => t!("skip-archived"),
+            SkipReason::Filtered => t!("skip-filtered"),
+            SkipReason::ChangedSincePlanned => t!("skip-changed-since-planned"),
+        }
+    }
+}
+
+/// Why `repo` can't be made private if `private`, or public otherwise, or `None` if it can.
+pub(crate) fn skip_reason(repo: &Repository, private: bool) -> Option<SkipReason> {
+    if repo.private == Some(private) {
+        Some(SkipReason::AlreadyDesiredState)
+    } else if let Some(reason) = lists::reason(repo) {
+        Some(reason)
+    } else if !repo.can_administer() {
+        Some(SkipReason::NoAdmin)
+    } else if repo.archived {
+        Some(SkipReason::Archived)
+    } else if repo.fork {
+        Some(SkipReason::Fork)
+    } else {
+        None
+    }
+}
+
+impl Step {
+    /// Returns `true` if the repository already has the wanted visibility.
+    pub(crate) fn is_unchanged(&self) -> bool {
+        self.change.is_unchanged()
+    }
+}
+
+/// Splits `repos` into those the token can administer and those it can only read, whose
+/// visibility can't be changed.
+pub(crate) fn split_read_only(repos: Vec<Repository>) -> (Vec<Repository>, Vec<Repository>) {
+    repos.into_iter().partition(Repository::can_administer)
+}
+
+/// The settings `policy` wants for `repos`: its default visibility for each violation.
+pub(crate) fn for_policy(policy: &Policy, repos: &[Repository]) -> Vec<Wanted> {
+    let private = policy.default_visibility.is_private();
+    policy
+        .violations(repos)
+        .into_iter()
+        .map(|repo| (repo.clone(), private))
+        .collect()
+}
+
+/// Plans `wanted` in the order given, with repositories reached twice planned once.
+///
+/// Repositories without an owner belong to `username`. Wanting a repository both private and
+/// public is an error.
+pub(crate) fn plan(wanted: Vec<Wanted>, username: &str) -> Result<Vec<Step>> {
+    let wanted = wanted
+        .into_iter()
+        .map(|(repo, private)| (repo, private.to_string()))
+        .collect();
+
+    Ok(apply::dedupe(wanted, username)?
+        .into_iter()
+        .map(|(repo, privacy)| {
+            let private = privacy == "true";
+            Step {
+                change: FieldChange::visibility(repo.private, private),
+                skip: skip_reason(&repo, private),
+                repo,
+                private,
+            }
+        })
+        .collect())
+}
+
+/// The change of every step, for display, leaving out those that can't be made.
+pub(crate) fn changes(steps: &[Step]) -> Vec<FieldChange> {
+    steps
+        .iter()
+        .filter(|step| step.skip.is_none() || step.is_unchanged())
+        .map(|step| step.change.clone())
+        .collect()
+}
+
+/// The steps that change something, to hand to [`apply::apply`].
+pub(crate) fn to_apply(steps: Vec<Step>) -> apply::Plan {
+    steps
+        .into_iter()
+        .filter(|step| step.skip.is_none())
+        .map(|step| (step.repo, step.private.to_string()))
+        .collect()
+}
+
+/// The repositories of `steps` left alone, by `owner/name` with `username` as the owner of those
+/// without one, and why.
+pub(crate) fn skipped(steps: &[Step], username: &str) -> Vec<(String, SkipReason)> {
+    steps
+        .iter()
+        .filter_map(|step| Some((apply::full_name(&step.repo, username), step.skip?)))
+        .collect()
+}
@@ -0,0 +1,73 @@
+//! The visibility policy enforced by `privateer watch` and `privateer daemon`.
+
+use crate::cli::Visibility;
+use crate::github::Repository;
+use crate::lists;
+use crate::plan::SkipReason;
+use serde::Deserialize;
+
+/// Which visibility repositories should have, and which are exempt.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Policy {
+    pub default_visibility: Visibility,
+    /// Names of repositories left alone, compared case-insensitively.
+    pub allow: Vec<String>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            default_visibility: Visibility::Private,
+            allow: Vec::new(),
+        }
+    }
+}
+
+impl Policy {
+    /// Returns `true` if `name` is exempt from the policy.
+    pub(crate) fn allows(&self, name: &str) -> bool {
+        self.allow
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the repositories that don't have the default visibility and aren't exempt.
+    ///
+    /// Repositories the user can't administer are left out, since changing them would fail.
+    pub(crate) fn violations<'a>(
+        &self,
+        repos: impl IntoIterator<Item = &'a Repository>,
+    ) -> Vec<&'a Repository> {
+        let private = self.default_visibility.is_private();
+        repos
+            .into_iter()
+            .filter(|repo| repo.private == Some(!private))
+            .filter(|repo| repo.can_administer())
+            .filter(|repo| !self.allows(&repo.name))
+            .filter(|repo| lists::reason(repo).is_none())
+            .collect()
+    }
+
+    /// Returns the repositories that don't have the default visibility but are left alone, and
+    /// why: exempt, on the `--allowlist` or `--denylist`, or not administered by the user.
+    pub(crate) fn exempt<'a>(
+        &self,
+        repos: impl IntoIterator<Item = &'a Repository>,
+    ) -> Vec<(&'a Repository, SkipReason)> {
+        let private = self.default_visibility.is_private();
+        repos
+            .into_iter()
+            .filter(|repo| repo.private == Some(!private))
+            .filter_map(|repo| {
+                let reason = match lists::reason(repo) {
+                    Some(reason) => reason,
+                    None if self.allows(&repo.name) => SkipReason::Protected,
+                    None if !repo.can_administer() => SkipReason::NoAdmin,
+                    None => return None,
+                };
+                Some((repo, reason))
+            })
+            .collect()
+    }
+}
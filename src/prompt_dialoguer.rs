@@ -0,0 +1,122 @@
+use crate::github::Repository;
+use crate::i18n::t;
+use crate::picker::{Picker, PickerItem};
+use crate::Result;
+use crate::{format, output};
+use anyhow::Context;
+use console::style;
+
+/// Enables user interaction and returns the result.
+///
+/// Typing filters the repositories by name. The user can select the items with the 'Space' bar
+/// and on 'Enter' the indices of selected items will be returned.
+/// The dialog is rendered on stderr.
+/// Result contains `Vec<index>` if user hit 'Enter'.
+///
+/// With a `goal` visibility, the repositories that don't have it yet start out selected. Each
+/// repository is tagged with how `username` accesses it: as its owner, a collaborator or
+/// through a team.
+///
+/// In this implementation, we use the `Url` crate to construct the URLs, `console` to style the
+/// URLs with underline, and `fmt::Write` to format the items with the repository name and
+/// clickable URL.
+pub(crate) fn run_dialoguer(
+    username: String,
+    repos: Vec<Repository>,
+    goal: Option<bool>,
+) -> Result<Vec<usize>> {
+    let options = repo_items(&username, &repos, goal);
+    let selections = Picker::new(t!("select-repos"), options)
+        .interact()
+        .context("Failed to select repositories")?;
+
+    Ok(selections)
+}
+
+/// The items of the picker for `repos`, see [`run_dialoguer`].
+pub(crate) fn repo_items(
+    username: &str,
+    repos: &[Repository],
+    goal: Option<bool>,
+) -> Vec<PickerItem> {
+    let now = chrono::Utc::now();
+    let plain = output::plain_icons();
+    // The API may omit `private`, so an unknown visibility is shown rather than assumed.
+    let parse_visibility = |is_private: Option<bool>| match (is_private, plain) {
+        (Some(false), false) => style("\u{1F310} public ".to_string()).dim(), // 🌐 globe.
+        (Some(true), false) => style("\u{1F512} private".to_string()).yellow(), // 🔒 lock.
+        (None, false) => style("\u{2754} unknown".to_string()).red(),         // ❔ question mark.
+        (Some(false), true) => style("public ".to_string()).dim(),
+        (Some(true), true) => style("private".to_string()).yellow(),
+        (None, true) => style("unknown".to_string()).red(),
+    };
+    let badge = |enabled: bool, label: &'static str| match enabled {
+        true => style(label).magenta().to_string(),
+        false => " ".repeat(label.len()),
+    };
+
+    let mut options: Vec<PickerItem> = Vec::new();
+    for repo in repos {
+        let pushed = repo.pushed_at.map_or(t!("never-pushed"), |pushed_at| {
+            t!("pushed", date = format::date(pushed_at, now))
+        });
+        options.push(PickerItem {
+            // Repositories are grouped by owner when they belong to more than one.
+            group: Some(repo.owner.login.clone()).filter(|login| !login.is_empty()),
+            key: repo.name.clone(),
+            detail: format!(
+                "{visibility} {access} {fork} {archived} {empty} {stars} {pushed}",
+                visibility = parse_visibility(repo.private),
+                access = style(format!("{:<12}", repo.access(username).label())).cyan(),
+                fork = badge(repo.fork, "fork"),
+                archived = badge(repo.archived, "archived"),
+                empty = badge(repo.is_empty(), "empty"),
+                stars = style(format!("\u{2605}{:>5}", repo.stargazers_count)).dim(), // ★ star.
+                pushed = style(pushed).dim(),
+            ),
+            preview: preview(repo),
+            url: Some(repo.html_url.clone()).filter(|url| !url.is_empty()),
+            command: Some(&repo.clone_url)
+                .filter(|url| !url.is_empty())
+                .map(|url| format!("git clone {url}")),
+            checked: goal.is_some_and(|private| repo.private == Some(!private)),
+            private: repo.private,
+            fork: repo.fork,
+            archived: repo.archived,
+        });
+    }
+
+    options
+}
+
+/// Details of `repo` shown in the picker's preview, from the already-fetched listing.
+fn preview(repo: &Repository) -> Vec<String> {
+    let label = |label: &str| style(format!("{label:<14}")).dim().to_string();
+    let mut lines = vec![
+        repo.description
+            .clone()
+            .unwrap_or_else(|| style("No description").dim().to_string()),
+        format!("{}{}", label("topics"), repo.topics.join(", ")),
+        format!(
+            "{}{}",
+            label("branch"),
+            repo.default_branch.as_deref().unwrap_or("-")
+        ),
+        match repo.is_empty() {
+            true => format!("{}empty, no commits yet", label("size")),
+            false => format!(
+                "{}{} KB",
+                label("size"),
+                format::thousands(repo.size as usize)
+            ),
+        },
+        format!("{}{}", label("open issues"), repo.open_issues_count),
+    ];
+    if let Some(parent) = &repo.parent {
+        lines.push(format!("{}{}", label("forked from"), parent.full_name));
+    } else if repo.fork {
+        lines.push(format!("{}yes", label("fork")));
+    }
+
+    lines
+}
@@ -0,0 +1,285 @@
+use crate::i18n::t;
+use crate::{output, paths};
+use crate::{Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::{DefaultHistory, History};
+use rustyline::validate::Validator;
+use rustyline::{Context as LineContext, Editor, Helper};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Line editor shared by all prompts, so input history carries across the session.
+static EDITOR: Mutex<Option<Editor<PromptHelper, DefaultHistory>>> = Mutex::new(None);
+/// File the input history is loaded from and saved to, if persisting it is enabled.
+static HISTORY_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Completes the input from a list of candidates, such as repository names.
+#[derive(Default)]
+struct PromptHelper {
+    completions: Vec<String>,
+}
+
+impl Completer for PromptHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &LineContext<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok((0, complete(&self.completions, &line[..pos])))
+    }
+}
+
+/// The completions of `input` among `completions`, case-insensitively. Those going on past a `/`
+/// after `input` are cut after it, so an owner such as `acme/` is completed before any of the
+/// repositories of `acme`.
+pub(crate) fn complete(completions: &[String], input: &str) -> Vec<String> {
+    let prefix = input.to_lowercase();
+    let mut candidates: Vec<String> = completions
+        .iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&prefix))
+        .map(
+            |candidate| match candidate.get(input.len()..).and_then(|rest| rest.find('/')) {
+                Some(slash) => candidate[..input.len() + slash + 1].to_owned(),
+                None => candidate.clone(),
+            },
+        )
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    candidates
+}
+
+impl Hinter for PromptHelper {
+    type Hint = String;
+}
+
+impl Highlighter for PromptHelper {}
+
+impl Validator for PromptHelper {}
+
+impl Helper for PromptHelper {}
+
+/// Persists the input history of prompts across runs in the state directory.
+pub(crate) fn enable_persistent_history() -> Result<()> {
+    let path = paths::state_dir()?.join("prompt_history.txt");
+    let _ = HISTORY_FILE.set(path);
+    Ok(())
+}
+
+/// Returns `true` if both stdin and stdout are terminals, so the user can answer prompts.
+///
+/// Pipes, cron jobs and CI runners are not; values must then come from flags or the
+/// environment instead.
+pub(crate) fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+fn ensure_interactive(message: &str) -> Result<()> {
+    match is_interactive() {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "{ERROR_ICON} {}",
+            t!(
+                "not-a-terminal",
+                message = message.trim().trim_end_matches(':').to_owned()
+            )
+        )),
+    }
+}
+
+/// Separates `message` from the input by a space, which translations can't end with.
+fn with_space(message: &str) -> String {
+    match message.ends_with(char::is_whitespace) {
+        true => message.to_owned(),
+        false => format!("{message} "),
+    }
+}
+
+/// Function `prompt_for_token` prompts the user to enter a GitHub API token and returns it.
+///
+/// # Panics
+///
+/// This function panics if it is unable to prompt for the token in a secure manner.
+pub(crate) fn prompt_for_token() -> Result<String> {
+    let message = with_space(&t!("token-prompt"));
+    ensure_interactive(&message)?;
+    let token = rpassword::prompt_password(&message)
+        .with_context(|| "Failed to prompt for token securely")?;
+
+    // A token pasted into a Windows console can keep the `\r` of its line ending.
+    Ok(token.trim().to_owned())
+}
+
+/// Function `prompt_user_input` prompts the user to enter a value and returns it.
+///
+/// Input can be edited with the arrow keys and earlier answers recalled with up/down.
+///
+/// # Arguments
+///
+/// * `message` - A message to display to the user when prompting for input.
+///
+/// # Errors
+///
+/// Returns an error if the user cancels with `Ctrl-C` or `Ctrl-D`, or input can't be read.
+pub(crate) fn prompt_user_input(message: &str) -> Result<String> {
+    prompt_with_completions(message, Vec::new())
+}
+
+/// Like [`prompt_user_input`], completing the input from `completions` on `Tab`.
+///
+/// With `--plain` the input is read as a plain line instead, without the line editor redrawing it.
+pub(crate) fn prompt_with_completions(message: &str, completions: Vec<String>) -> Result<String> {
+    ensure_interactive(message)?;
+    if output::is_plain() {
+        return read_plain_line(message);
+    }
+    let mut guard = EDITOR
+        .lock()
+        .map_err(|_| anyhow!("Prompt editor is poisoned"))?;
+    let editor = match guard.as_mut() {
+        Some(editor) => editor,
+        None => {
+            let mut editor = Editor::new()?;
+            if let Some(path) = HISTORY_FILE.get() {
+                // A missing history file just means nothing was saved yet.
+                let _ = editor.load_history(path);
+            }
+            guard.insert(editor)
+        }
+    };
+    editor.set_helper(Some(PromptHelper { completions }));
+
+    let read = editor.readline(&with_space(message));
+    accept(
+        read,
+        editor.history_mut(),
+        HISTORY_FILE.get().map(PathBuf::as_path),
+    )
+}
+
+/// The answer `read` by the line editor, trimmed, after adding it to `history` and saving that
+/// to `path`, if any. An empty answer isn't remembered. `Ctrl-C` and `Ctrl-D` cancel the prompt.
+pub(crate) fn accept(
+    read: rustyline::Result<String>,
+    history: &mut DefaultHistory,
+    path: Option<&Path>,
+) -> Result<String> {
+    match read {
+        Ok(line) => {
+            let input = line.trim().to_string();
+            if !input.is_empty() {
+                history.add(&input)?;
+                if let Some(path) = path {
+                    history
+                        .save(path)
+                        .with_context(|| format!("Failed to save prompt history to {path:?}"))?;
+                }
+            }
+            Ok(input)
+        }
+        Err(ReadlineError::Interrupted | ReadlineError::Eof) => {
+            Err(anyhow!("{ERROR_ICON} {}", t!("cancelled")))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn read_plain_line(message: &str) -> Result<String> {
+    print!("{}", with_space(message));
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    match std::io::stdin().read_line(&mut line)? {
+        0 => Err(anyhow!("{ERROR_ICON} {}", t!("cancelled"))),
+        _ => Ok(line.trim().to_owned()),
+    }
+}
+
+/// How many times a prompt is repeated when its input can't be read.
+const READ_ATTEMPTS: usize = 3;
+
+/// An answer to a yes/no question asked for every item of a list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Answer {
+    /// The answer for this item only.
+    One(bool),
+    /// The answer for this item and all remaining ones.
+    All(bool),
+}
+
+/// Interprets `y`, `yes`, `t` or `true` as yes and `n`, `no`, `f` or `false` as no, in any
+/// case.
+pub(crate) fn parse_yes_no(input: &str) -> Option<bool> {
+    match input.trim().to_lowercase().as_str() {
+        "y" | "yes" | "t" | "true" => Some(true),
+        "n" | "no" | "f" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Like [`prompt_user_input`], but asks again if the input can't be read rather than
+/// giving up on the first error. Cancelling still aborts.
+fn prompt_retrying(message: &str) -> Result<String> {
+    for _ in 1..READ_ATTEMPTS {
+        match prompt_user_input(message) {
+            Err(err) if err.is::<ReadlineError>() => {
+                println!(
+                    "{ERROR_ICON} {}",
+                    t!("read-failed", error = err.to_string())
+                )
+            }
+            result => return result,
+        }
+    }
+    prompt_user_input(message)
+}
+
+/// Prompts with `message` until it is answered yes or no, see [`parse_yes_no`].
+pub(crate) fn prompt_yes_no(message: &str) -> Result<bool> {
+    loop {
+        match parse_yes_no(&prompt_retrying(message)?) {
+            Some(answer) => return Ok(answer),
+            None => println!("{ERROR_ICON} {}", t!("yes-no-invalid")),
+        }
+    }
+}
+
+/// Like [`prompt_yes_no`], also accepting `a` to then give one answer for all remaining items.
+pub(crate) fn prompt_yes_no_all(message: &str) -> Result<Answer> {
+    loop {
+        let input = prompt_retrying(message)?;
+        if input.eq_ignore_ascii_case("a") {
+            let answer = prompt_yes_no(&t!("all-remaining-prompt"))?;
+            return Ok(Answer::All(answer));
+        }
+        match parse_yes_no(&input) {
+            Some(answer) => return Ok(Answer::One(answer)),
+            None => println!("{ERROR_ICON} {}", t!("yes-no-all-invalid")),
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) fn prompt_for_privacy() -> Result<bool> {
+    // Prompt the user to enter the privacy setting for the repository.
+    println!("Should the repository be private? [y/n]");
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read input")?;
+
+    // Parse the input as a boolean value.
+    match input.trim().to_lowercase().as_ref() {
+        "y" | "yes" => Ok(true),
+        "n" | "no" => Ok(false),
+        _ => Err(anyhow!("Invalid input, please enter y or n".to_owned())),
+    }
+}
@@ -0,0 +1,182 @@
+//! `--fields` and `--jq`: trimming JSON output down to what a script needs.
+//!
+//! `--fields name,visibility` keeps only those fields of each object. `--jq` takes a path in the
+//! subset of `jq` most scripts need: `.name` for a field, `[0]` or `[-1]` for an element, `[]`
+//! for every element, chained as in `.[].owner` or `.[] | .topics[0]`. Each value the path leads
+//! to is printed on its own line, strings without quotes, like `gh --jq` does.
+
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+
+/// One step of a `--jq` path.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Step {
+    /// A field of an object, `null` if it has none.
+    Key(String),
+    /// An element of an array, counted from the end if negative, `null` if out of range.
+    Index(i64),
+    /// Every element of an array, or every value of an object.
+    Each,
+}
+
+/// A path given with `--jq`, such as `.[].name`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Path(pub Vec<Step>);
+
+/// Why a `--jq` path can't be parsed, or can't be followed through a value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl FromStr for Path {
+    type Err = Error;
+
+    fn from_str(expr: &str) -> Result<Self, Error> {
+        let invalid = |at: &str| {
+            Error(format!(
+                "`{expr}` is not a path at `{at}`. Hint! Paths are like `.[].name` or `.[0].topics`"
+            ))
+        };
+        let mut steps = Vec::new();
+        let mut rest = expr.trim();
+        if !rest.starts_with('.') {
+            return Err(invalid(rest));
+        }
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('|') {
+                rest = after.trim_start();
+                if !rest.starts_with('.') {
+                    return Err(invalid(rest));
+                }
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let (inside, after) = after.split_once(']').ok_or_else(|| invalid(rest))?;
+                steps.push(match inside.trim() {
+                    "" => Step::Each,
+                    index => Step::Index(index.parse().map_err(|_| invalid(rest))?),
+                });
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('.') {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                if end > 0 {
+                    steps.push(Step::Key(after[..end].to_owned()));
+                } else if !(after.is_empty() || after.starts_with(['[', ' ', '|'])) {
+                    return Err(invalid(rest));
+                }
+                rest = &after[end..];
+            } else if rest.starts_with(' ') {
+                rest = rest.trim_start();
+            } else {
+                return Err(invalid(rest));
+            }
+        }
+
+        Ok(Path(steps))
+    }
+}
+
+impl Path {
+    /// The values `value` leads to along the path.
+    pub(crate) fn query(&self, value: &Value) -> Result<Vec<Value>, Error> {
+        let mut values = vec![value.clone()];
+        for step in &self.0 {
+            let mut next = Vec::with_capacity(values.len());
+            for value in values {
+                match (step, value) {
+                    (Step::Key(key), Value::Object(mut object)) => {
+                        next.push(object.remove(key).unwrap_or(Value::Null))
+                    }
+                    (Step::Index(index), Value::Array(mut array)) => {
+                        let len = array.len() as i64;
+                        let index = if *index < 0 { len + index } else { *index };
+                        next.push(match (0..len).contains(&index) {
+                            true => array.swap_remove(index as usize),
+                            false => Value::Null,
+                        });
+                    }
+                    (Step::Each, Value::Array(array)) => next.extend(array),
+                    (Step::Each, Value::Object(object)) => next.extend(object.into_values()),
+                    (_, Value::Null) => next.push(Value::Null),
+                    (step, value) => {
+                        return Err(Error(format!(
+                            "Can't follow `{}` through {}",
+                            describe(step),
+                            kind(&value)
+                        )))
+                    }
+                }
+            }
+            values = next;
+        }
+
+        Ok(values)
+    }
+}
+
+fn describe(step: &Step) -> String {
+    match step {
+        Step::Key(key) => format!(".{key}"),
+        Step::Index(index) => format!("[{index}]"),
+        Step::Each => "[]".to_owned(),
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// Keeps only `fields` of `value`, or of each object in it if it is an array.
+pub(crate) fn pick(value: Value, fields: &[String]) -> Value {
+    match value {
+        Value::Array(values) => values
+            .into_iter()
+            .map(|value| pick(value, fields))
+            .collect(),
+        Value::Object(mut object) => {
+            let mut picked = Map::new();
+            for field in fields {
+                picked.insert(field.clone(), object.remove(field).unwrap_or(Value::Null));
+            }
+            Value::Object(picked)
+        }
+        value => value,
+    }
+}
+
+/// Renders `value` for output: trimmed to `fields` if any are given, then either followed
+/// along `jq`, a line per value, or pretty-printed whole.
+pub(crate) fn render(value: Value, fields: &[String], jq: Option<&Path>) -> Result<String, Error> {
+    let value = match fields.is_empty() {
+        true => value,
+        false => pick(value, fields),
+    };
+    let Some(path) = jq else {
+        return Ok(serde_json::to_string_pretty(&value).unwrap_or_default());
+    };
+    let lines: Vec<String> = path
+        .query(&value)?
+        .into_iter()
+        .map(|value| match value {
+            Value::String(text) => text,
+            value => value.to_string(),
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
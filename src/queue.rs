@@ -0,0 +1,111 @@
+//! A requests-per-minute budget shared by every API request of the process.
+//!
+//! Automation modes can make bursts of changes across a large organization. With a budget, API
+//! requests are spread out to at most one every `60s / requests_per_minute` instead of hitting
+//! GitHub's secondary rate limits. Explicit actions, such as webhook deliveries for a repository
+//! someone just created, take the next free slot before background enforcement does.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Priority {
+    /// Triggered by a person, directly or through a webhook.
+    Explicit,
+    /// Scheduled enforcement, such as `privateer daemon` and `privateer watch`.
+    Background,
+}
+
+tokio::task_local! {
+    static PRIORITY: Priority;
+}
+
+/// Runs `future` with its API requests at `priority`.
+pub(crate) async fn with_priority<F: Future>(priority: Priority, future: F) -> F::Output {
+    PRIORITY.scope(priority, future).await
+}
+
+/// Hands out evenly spaced slots for requests.
+#[derive(Debug)]
+pub(crate) struct Budget {
+    spacing: Duration,
+    next: Option<Instant>,
+    explicit_waiting: usize,
+}
+
+impl Budget {
+    pub(crate) fn new(requests_per_minute: u32) -> Self {
+        Self {
+            spacing: Duration::from_secs(60) / requests_per_minute.max(1),
+            next: None,
+            explicit_waiting: 0,
+        }
+    }
+
+    /// Takes the slot at `now` if it is free and no explicit request is waiting for it ahead of
+    /// a background one. Otherwise returns when to try again.
+    pub(crate) fn try_acquire(&mut self, priority: Priority, now: Instant) -> Result<(), Instant> {
+        let next = self.next.unwrap_or(now);
+        let yields = priority == Priority::Background && self.explicit_waiting > 0;
+        match next <= now && !yields {
+            true => {
+                self.next = Some(now + self.spacing);
+                Ok(())
+            }
+            // A yielding background request tries again after the slot the explicit one takes.
+            false => Err(next.max(now) + if yields { self.spacing } else { Duration::ZERO }),
+        }
+    }
+
+    pub(crate) fn wait(&mut self, priority: Priority) {
+        if priority == Priority::Explicit {
+            self.explicit_waiting += 1;
+        }
+    }
+
+    pub(crate) fn done_waiting(&mut self, priority: Priority) {
+        if priority == Priority::Explicit {
+            self.explicit_waiting = self.explicit_waiting.saturating_sub(1);
+        }
+    }
+}
+
+static BUDGET: Mutex<Option<Budget>> = Mutex::new(None);
+
+/// Limits later requests to `requests_per_minute`, or lifts the limit with `None`.
+pub(crate) fn configure(requests_per_minute: Option<u32>) {
+    if let Ok(mut budget) = BUDGET.lock() {
+        *budget = requests_per_minute.map(Budget::new);
+    }
+}
+
+fn with_budget<T>(f: impl FnOnce(&mut Budget) -> T) -> Option<T> {
+    BUDGET.lock().ok()?.as_mut().map(f)
+}
+
+/// Waits for the next slot of the budget, if one is configured.
+pub(crate) async fn acquire() {
+    let priority = PRIORITY
+        .try_with(|priority| *priority)
+        .unwrap_or(Priority::Explicit);
+    with_budget(|budget| budget.wait(priority));
+    // Dropped when the slot is taken, or when the request is dropped while it waits, such as on
+    // a timeout or `Ctrl-C`; a waiter that never leaves would hold background requests forever.
+    let _waiting = Waiting(priority);
+    while let Some(Err(retry_at)) =
+        with_budget(|budget| budget.try_acquire(priority, Instant::now()))
+    {
+        tokio::time::sleep_until(retry_at).await;
+    }
+}
+
+/// A request waiting for a slot, counted by the budget until dropped.
+struct Waiting(Priority);
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        with_budget(|budget| budget.done_waiting(self.0));
+    }
+}
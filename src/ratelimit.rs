@@ -0,0 +1,130 @@
+//! Waiting out GitHub's rate limit instead of failing.
+//!
+//! When a request is rejected for exceeding the rate limit, the wait comes from the response
+//! itself: `Retry-After` if present, otherwise `X-RateLimit-Reset` measured against the server's
+//! `Date` header rather than the local clock. A machine with a wrong clock then neither sleeps
+//! for hours nor retries straight away.
+//!
+//! `privateer rate-limit` shows the quotas left, to decide whether to start a large batch now.
+
+use crate::events::{self, Event};
+use crate::github::{self, Quota};
+use crate::transport::{self, HttpRequest, HttpResponse};
+use crate::{deprecation, format, metrics, queue, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// Longest wait for a reset; past it, the rejection is returned to the caller instead.
+pub(crate) const MAX_WAIT: Duration = Duration::from_secs(15 * 60);
+
+/// Extra wait after the reset, so the retry doesn't race the server's own clock.
+const MARGIN: Duration = Duration::from_secs(1);
+
+/// Time until the rate limit resets at `reset`, measured from `server_now` when the server sent
+/// its time, and from `local_now` otherwise.
+pub(crate) fn reset_delay(
+    reset: DateTime<Utc>,
+    server_now: Option<DateTime<Utc>>,
+    local_now: DateTime<Utc>,
+) -> Duration {
+    let now = server_now.unwrap_or(local_now);
+
+    (reset - now).to_std().unwrap_or_default() + MARGIN
+}
+
+/// How long to wait before retrying a request rejected with `headers`, or `None` if it wasn't
+/// rejected for the rate limit.
+pub(crate) fn retry_delay(headers: &HeaderMap, local_now: DateTime<Utc>) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    if let Some(seconds) = header("retry-after").and_then(|value| value.parse().ok()) {
+        return Some(Duration::from_secs(seconds));
+    }
+    if header("x-ratelimit-remaining") != Some("0") {
+        return None;
+    }
+    let reset = DateTime::from_timestamp(header("x-ratelimit-reset")?.parse().ok()?, 0)?;
+
+    Some(reset_delay(reset, server_date(headers), local_now))
+}
+
+/// The time of the server when it sent a response with `headers`, from its `Date` header.
+pub(crate) fn server_date(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    let date = headers.get("date")?.to_str().ok()?;
+
+    DateTime::parse_from_rfc2822(date)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Sends `request` through the current [`transport`], and if it is rejected for the rate limit,
+/// waits for the reset and sends it again, once unless the client configured more retries.
+///
+/// Deprecation notices on the response are passed on to [`deprecation::warn_once`].
+pub(crate) async fn send(request: HttpRequest) -> Result<HttpResponse> {
+    let result = send_retrying(request).await;
+    metrics::observe(&result);
+
+    result
+}
+
+async fn send_retrying(request: HttpRequest) -> Result<HttpResponse> {
+    let settings = transport::settings();
+    let mut retries = settings.retries;
+    loop {
+        queue::acquire().await;
+        let response = settings.transport.send(request.clone()).await?;
+        deprecation::warn_once(response.url.path(), &response.headers);
+        if retries == 0
+            || !matches!(
+                response.status,
+                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+            )
+        {
+            return Ok(response);
+        }
+        match retry_delay(&response.headers, Utc::now()) {
+            Some(delay) if delay <= MAX_WAIT => {
+                events::emit(Event::RateLimited {
+                    retry_in_secs: delay.as_secs(),
+                });
+                tokio::time::sleep(delay).await;
+                retries -= 1;
+            }
+            _ => return Ok(response),
+        }
+    }
+}
+
+/// Describes what is left of `quota` and when it resets, as seen at `now`.
+pub(crate) fn describe(quota: &Quota, now: DateTime<Utc>) -> String {
+    let left = format!(
+        "{} of {} left",
+        format::thousands(quota.remaining as usize),
+        format::thousands(quota.limit as usize)
+    );
+    match DateTime::from_timestamp(quota.reset, 0) {
+        Some(reset) if reset > now => {
+            let wait = Duration::from_secs((reset - now).num_seconds().unsigned_abs());
+            format!(
+                "{left}, resets in {} (at {})",
+                humantime::format_duration(wait),
+                reset.format("%H:%M:%S UTC")
+            )
+        }
+        _ => format!("{left}, reset already"),
+    }
+}
+
+/// Prints the quotas of the REST and the GraphQL API left to `pat_token`.
+pub(crate) async fn show(pat_token: &str) -> Result<()> {
+    let limits = github::get_rate_limit(pat_token).await?;
+    let now = limits.server_now.unwrap_or_else(Utc::now);
+    println!("core     {}", describe(&limits.core, now));
+    if let Some(graphql) = &limits.graphql {
+        println!("graphql  {}", describe(graphql, now));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! The version of the JSON privateer writes, for scripts and for later runs reading it back.
+//!
+//! Every JSON document and JSON line privateer writes carries `schema_version`, as
+//! `MAJOR.MINOR`. A new minor version only adds fields, so whatever reads an older one keeps
+//! working; a new major version renames or removes fields, or changes what they mean. Files read
+//! back to act on, such as the checkpoint `--resume` applies, are refused if their major version
+//! differs, rather than carrying out changes planned by an incompatible privateer. Files written
+//! before versions were added have none, and are read as `1.0`.
+
+use crate::{Result, ERROR_ICON};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+/// The version of everything this privateer writes.
+pub(crate) const SCHEMA_VERSION: &str = "1.0";
+
+/// `value` with `schema_version` as its first field, for writing.
+#[derive(Debug, Serialize)]
+pub(crate) struct Versioned<'a, T: Serialize> {
+    schema_version: &'static str,
+    #[serde(flatten)]
+    value: &'a T,
+}
+
+/// Wraps `value`, which must serialize as an object, to write it with `schema_version`.
+pub(crate) fn versioned<T: Serialize>(value: &T) -> Versioned<'_, T> {
+    Versioned {
+        schema_version: SCHEMA_VERSION,
+        value,
+    }
+}
+
+#[derive(Deserialize)]
+struct Header {
+    #[serde(default)]
+    schema_version: Option<String>,
+}
+
+/// Checks the JSON document `text`, `what` for messages, was written with a schema this
+/// privateer reads; documents without a version predate them and are compatible.
+pub(crate) fn check(text: &str, what: &str) -> Result<()> {
+    let Ok(Header {
+        schema_version: Some(version),
+    }) = serde_json::from_str(text)
+    else {
+        return Ok(());
+    };
+    match is_compatible(&version) {
+        true => Ok(()),
+        false => Err(anyhow!(
+            "{ERROR_ICON} {what} has schema version `{version}`, which this privateer (schema `{SCHEMA_VERSION}`) can't use. Hint! Use the privateer that wrote it, or create it again"
+        )),
+    }
+}
+
+/// Returns `true` if `version` has the major version of [`SCHEMA_VERSION`].
+pub(crate) fn is_compatible(version: &str) -> bool {
+    let major = |version: &str| {
+        version
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_owned()
+    };
+    major(version) == major(SCHEMA_VERSION)
+}
@@ -0,0 +1,305 @@
+//! Checks for leaked secrets before a repository is made public.
+//!
+//! A credential pushed to a public repository is out for good, even if the repository is made
+//! private again a minute later. So every visibility change to public is refused while secret
+//! scanning reports open alerts for the repository, or while they can't be listed, unless
+//! `--allow-secret-alerts` is passed.
+//!
+//! With `--scan-history`, the recent history of the default branch is also cloned and searched
+//! locally for secrets that are rarely anything else, such as AWS access keys and private keys,
+//! and a repository adding any of them is refused, listing where each was found.
+
+use crate::backup;
+use crate::github::{self, SecretAlert};
+use crate::output::verbose;
+use crate::urls;
+use crate::{Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use std::fmt;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Whether repositories with open alerts may be made public, from `--allow-secret-alerts`.
+static ALLOW_ALERTS: AtomicBool = AtomicBool::new(false);
+
+/// Number of recent commits searched for secrets, from `--scan-history`; `0` searches none.
+static SCAN_HISTORY: AtomicU64 = AtomicU64::new(0);
+
+/// Lets repositories with open secret scanning alerts be made public if `allow_alerts`, and
+/// searches the last `scan_history` commits of each for secrets first, if any.
+pub(crate) fn configure(allow_alerts: bool, scan_history: Option<u64>) {
+    ALLOW_ALERTS.store(allow_alerts, Ordering::Relaxed);
+    SCAN_HISTORY.store(scan_history.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Returns `true` if repositories with open secret scanning alerts may be made public.
+pub(crate) fn alerts_allowed() -> bool {
+    ALLOW_ALERTS.load(Ordering::Relaxed)
+}
+
+/// Returns `true` if the history of repositories is searched before they are made public.
+pub(crate) fn scans_history() -> bool {
+    SCAN_HISTORY.load(Ordering::Relaxed) > 0
+}
+
+/// Fails if `owner/name`, about to be made public, has open secret scanning alerts or if they
+/// can't be listed, unless that is allowed. A repository without secret scanning passes, since
+/// there is nothing to list.
+pub(crate) async fn check_alerts(owner: &str, name: &str, pat_token: &str) -> Result<()> {
+    if alerts_allowed() {
+        return Ok(());
+    }
+    let alerts = github::get_open_secret_alerts(owner, name, pat_token)
+        .await
+        .map_err(|err| {
+            anyhow!("{err}\n{ERROR_ICON} `{owner}/{name}` was not made public, since it can't be checked for leaked secrets. Hint! Pass `--allow-secret-alerts` to make it public anyway")
+        })?;
+    match alerts {
+        None => {
+            verbose!("Secret scanning is off for `{owner}/{name}`, so it has no alerts to check");
+            Ok(())
+        }
+        Some(alerts) if alerts.is_empty() => Ok(()),
+        Some(alerts) => Err(anyhow!(
+            "{ERROR_ICON} `{owner}/{name}` was not made public: it has {summary}. Hint! Revoke the secrets and close the alerts at {url}/security/secret-scanning, or pass `--allow-secret-alerts` to make it public anyway",
+            summary = summary(&alerts),
+            url = urls::host().html_repo_url(owner, name)
+        )),
+    }
+}
+
+/// The number of `alerts` and the kinds of secret they found, such as
+/// ``"`1` open secret scanning alerts (GitHub Personal Access Token)"``.
+pub(crate) fn summary(alerts: &[SecretAlert]) -> String {
+    let mut kinds: Vec<&str> = alerts
+        .iter()
+        .map(|alert| {
+            alert
+                .secret_type_display_name
+                .as_deref()
+                .unwrap_or("unknown secret")
+        })
+        .collect();
+    kinds.sort_unstable();
+    kinds.dedup();
+
+    format!(
+        "`{count}` open secret scanning alerts ({kinds})",
+        count = alerts.len(),
+        kinds = kinds.join(", ")
+    )
+}
+
+/// A likely secret added by a commit.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Finding {
+    /// What it looks like, such as `AWS access key ID`.
+    pub kind: &'static str,
+    /// Abbreviated hash of the commit adding it.
+    pub commit: String,
+    pub path: String,
+    /// The secret with everything after its well-known prefix hidden.
+    pub redacted: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{kind} `{redacted}` in `{path}`, added by {commit}",
+            kind = self.kind,
+            redacted = self.redacted,
+            path = self.path,
+            commit = self.commit
+        )
+    }
+}
+
+/// Fails if the recent history of `owner/name`, about to be made public, adds likely secrets,
+/// when `--scan-history` asks for it.
+pub(crate) async fn check_history(owner: &str, name: &str, pat_token: &str) -> Result<()> {
+    let commits = SCAN_HISTORY.load(Ordering::Relaxed);
+    if commits == 0 {
+        return Ok(());
+    }
+    verbose!("Searching the last `{commits}` commits of `{owner}/{name}` for secrets");
+    let url = backup::clone_url(owner, name);
+    let dir = std::env::temp_dir().join(format!(
+        "privateer-scan-{}-{owner}-{name}.git",
+        std::process::id()
+    ));
+    let pat_token = pat_token.to_owned();
+    let findings = tokio::task::spawn_blocking(move || {
+        let scanned = scan_history(&url, &dir, commits, &pat_token);
+        let _ = std::fs::remove_dir_all(&dir);
+        scanned
+    })
+    .await??;
+    if findings.is_empty() {
+        return Ok(());
+    }
+    let list: Vec<String> = findings
+        .iter()
+        .map(|finding| format!("  - {finding}"))
+        .collect();
+
+    Err(anyhow!(
+        "{ERROR_ICON} `{owner}/{name}` was not made public: its last `{commits}` commits add `{count}` likely secrets:\n{list}\nHint! Revoke them and remove them from the history, or leave out `--scan-history` to make it public anyway",
+        count = findings.len(),
+        list = list.join("\n")
+    ))
+}
+
+/// Clones the last `commits` commits of the default branch of `url` into `dir` and searches
+/// what they add for secrets.
+pub(crate) fn scan_history(
+    url: &str,
+    dir: &Path,
+    commits: u64,
+    pat_token: &str,
+) -> Result<Vec<Finding>> {
+    let _ = std::fs::remove_dir_all(dir);
+    let depth = commits.to_string();
+    let mut clone = backup::git(pat_token);
+    clone
+        .args([
+            "clone",
+            "--bare",
+            "--quiet",
+            "--no-tags",
+            "--depth",
+            &depth,
+            url,
+        ])
+        .arg(dir);
+    backup::run_git(clone)
+        .map_err(|err| anyhow!("{ERROR_ICON} Failed to clone `{url}` to search it: {err}"))?;
+
+    let mut log = backup::git("");
+    log.arg("-C")
+        .arg(dir)
+        .args([
+            "log",
+            "--patch",
+            "--no-color",
+            "--no-ext-diff",
+            "--format=commit %h",
+            "-n",
+            &depth,
+        ])
+        .stdout(Stdio::piped());
+    let output = log
+        .output()
+        .with_context(|| format!("{ERROR_ICON} Failed to run `git`. Hint! Install git first"))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match output.status.success() {
+        true => Ok(find_secrets(&String::from_utf8_lossy(&output.stdout))),
+        // An empty repository has no history to search.
+        false if stderr.contains("does not have any commits") => Ok(Vec::new()),
+        false => Err(anyhow!(
+            "{ERROR_ICON} Failed to read the history of `{url}`: {}",
+            stderr.trim()
+        )),
+    }
+}
+
+/// The likely secrets on the lines added in `log`, the output of `git log --patch` with each
+/// commit starting with `commit <hash>`.
+pub(crate) fn find_secrets(log: &str) -> Vec<Finding> {
+    let (mut commit, mut path) = ("", "");
+    let mut findings = Vec::new();
+    for line in log.lines() {
+        if let Some(hash) = line.strip_prefix("commit ") {
+            commit = hash;
+        } else if let Some(added) = line.strip_prefix("+++ ") {
+            path = added.strip_prefix("b/").unwrap_or(added);
+        } else if let Some(added) = line.strip_prefix('+') {
+            findings.extend(
+                secrets_in(added)
+                    .into_iter()
+                    .map(|(kind, redacted)| Finding {
+                        kind,
+                        commit: commit.to_owned(),
+                        path: path.to_owned(),
+                        redacted,
+                    }),
+            );
+        }
+    }
+
+    findings
+}
+
+/// A kind of secret recognized by its prefix and the characters following it.
+struct Pattern {
+    kind: &'static str,
+    prefixes: &'static [&'static str],
+    /// Number of characters after the prefix.
+    len: usize,
+    is_char: fn(u8) -> bool,
+}
+
+fn is_upper_alphanumeric(byte: u8) -> bool {
+    byte.is_ascii_uppercase() || byte.is_ascii_digit()
+}
+
+fn is_alphanumeric(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric()
+}
+
+fn is_word(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+const PATTERNS: &[Pattern] = &[
+    Pattern {
+        kind: "AWS access key ID",
+        prefixes: &["AKIA", "ASIA"],
+        len: 16,
+        is_char: is_upper_alphanumeric,
+    },
+    Pattern {
+        kind: "GitHub token",
+        prefixes: &["ghp_", "gho_", "ghu_", "ghs_", "ghr_"],
+        len: 36,
+        is_char: is_alphanumeric,
+    },
+    Pattern {
+        kind: "GitHub token",
+        prefixes: &["github_pat_"],
+        len: 82,
+        is_char: is_word,
+    },
+];
+
+/// The kinds of secret on `line`, each with the secret redacted.
+fn secrets_in(line: &str) -> Vec<(&'static str, String)> {
+    let mut found = Vec::new();
+    if let Some(start) = line.find("-----BEGIN ") {
+        let header = &line[start..];
+        if let Some(end) = header.find("PRIVATE KEY") {
+            let end = end + "PRIVATE KEY".len();
+            found.push(("private key", format!("{}-----", &header[..end])));
+        }
+    }
+    let bytes = line.as_bytes();
+    for pattern in PATTERNS {
+        for prefix in pattern.prefixes {
+            for (start, _) in line.match_indices(prefix) {
+                let end = start + prefix.len() + pattern.len;
+                let bounded = start == 0 || !is_word(bytes[start - 1]);
+                let complete = bytes.len() >= end
+                    && bytes[start + prefix.len()..end]
+                        .iter()
+                        .all(|byte| (pattern.is_char)(*byte))
+                    && bytes.get(end).is_none_or(|byte| !is_word(*byte));
+                if bounded && complete {
+                    found.push((pattern.kind, format!("{prefix}…")));
+                }
+            }
+        }
+    }
+
+    found
+}
@@ -0,0 +1,214 @@
+//! A minimal HTTP/1.1 server for the endpoints `privateer daemon` exposes.
+//!
+//! Each connection carries one request and is closed after the response, which is all probes
+//! and webhooks need. Requests are bounded in size and in time, since the server may listen on
+//! every interface: a client can neither fill the memory nor hold a connection open forever.
+
+use crate::Result;
+use std::future::Future;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Requests with larger bodies are rejected.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// Requests whose request line and headers together are larger are rejected.
+const MAX_HEAD: usize = 16 * 1024;
+
+/// Requests with more headers are rejected.
+const MAX_HEADERS: usize = 100;
+
+/// Longest wait for a whole request, after which the connection is answered with a timeout.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Request {
+    pub method: String,
+    /// The path without the query, such as `/health`.
+    pub path: String,
+    /// Header names are lowercase.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Returns the value of the header `name`, which must be lowercase.
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub(crate) fn json(status: u16, body: &serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: body.to_string(),
+        }
+    }
+
+    pub(crate) fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.into(),
+        }
+    }
+
+    pub(crate) fn not_found() -> Self {
+        Self::text(404, "Not Found\n")
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+/// Answers every connection on `listener` with `handler` until the task is dropped.
+pub(crate) async fn serve<H, F>(listener: TcpListener, handler: H) -> Result<()>
+where
+    H: Fn(Request) -> F + Clone + Send + 'static,
+    F: Future<Output = Response> + Send,
+{
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            // A client that hangs up or sends garbage only affects its own connection.
+            let _ = handle(stream, handler).await;
+        });
+    }
+}
+
+async fn handle<H, F>(mut stream: TcpStream, handler: H) -> Result<()>
+where
+    H: Fn(Request) -> F,
+    F: Future<Output = Response>,
+{
+    let response = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => handler(request).await,
+        Ok(Err(rejected)) => rejected,
+        Err(_) => Response::text(408, "Request Timeout\n"),
+    };
+    let head = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {length}\r\nConnection: close\r\n\r\n",
+        status = response.status,
+        reason = reason(response.status),
+        content_type = response.content_type,
+        length = response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+fn bad_request() -> Response {
+    Response::text(400, "Bad Request\n")
+}
+
+fn head_too_large() -> Response {
+    Response::text(431, "Request Header Fields Too Large\n")
+}
+
+/// Reads a line of the head of a request into `line`, taking its length from what is `left` of
+/// [`MAX_HEAD`].
+async fn read_head_line<R>(
+    reader: &mut R,
+    line: &mut String,
+    left: &mut usize,
+) -> std::result::Result<(), Response>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    line.clear();
+    let read = reader
+        .take(*left as u64)
+        .read_line(line)
+        .await
+        .map_err(|_| bad_request())?;
+    if !line.ends_with('\n') {
+        // Either the head ran past its limit, or the client hung up halfway through it.
+        return Err(match read == *left {
+            true => head_too_large(),
+            false => bad_request(),
+        });
+    }
+    *left -= read;
+
+    Ok(())
+}
+
+/// Reads one request, or returns the response rejecting it: malformed, with a head larger than
+/// [`MAX_HEAD`] or more than [`MAX_HEADERS`] headers, or with a body larger than [`MAX_BODY`].
+async fn read_request(stream: &mut TcpStream) -> std::result::Result<Request, Response> {
+    let mut reader = BufReader::new(stream);
+    let mut left = MAX_HEAD;
+    let mut line = String::new();
+    read_head_line(&mut reader, &mut line, &mut left).await?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request());
+    };
+    let method = method.to_owned();
+    let path = target.split('?').next().unwrap_or(target).to_owned();
+
+    let mut headers = Vec::new();
+    loop {
+        read_head_line(&mut reader, &mut line, &mut left).await?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(head_too_large());
+        }
+        let (name, value) = header.split_once(':').ok_or_else(bad_request)?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+    }
+
+    let mut request = Request {
+        method,
+        path,
+        headers,
+        body: Vec::new(),
+    };
+    let length: usize = match request.header("content-length") {
+        Some(length) => length.parse().map_err(|_| bad_request())?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err(Response::text(413, "Payload Too Large\n"));
+    }
+    request.body.resize(length, 0);
+    reader
+        .read_exact(&mut request.body)
+        .await
+        .map_err(|_| bad_request())?;
+
+    Ok(request)
+}
@@ -0,0 +1,118 @@
+#![allow(dead_code)]
+
+use crate::ApiResponse;
+use crate::{Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::{anyhow, Context};
+use std::process::{Command, Stdio};
+
+/// Opens `url` in the default browser without waiting for it to exit.
+pub(crate) fn open_in_browser(url: &str) -> Result<()> {
+    browser_command(std::env::consts::OS, url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("{ERROR_ICON} Failed to open `{url}` in the browser"))?;
+
+    Ok(())
+}
+
+/// The command opening `url` in the default browser of `os`, as named by
+/// [`std::env::consts::OS`].
+pub(crate) fn browser_command(os: &str, url: &str) -> Command {
+    let mut cmd = match os {
+        "macos" => Command::new("open"),
+        "windows" => {
+            let mut cmd = Command::new("cmd");
+            // The empty argument is the window title `start` expects before the URL.
+            cmd.args(["/C", "start", ""]);
+            cmd
+        }
+        _ => Command::new("xdg-open"),
+    };
+    cmd.arg(url);
+
+    cmd
+}
+
+/// Runs `command` with the platform's shell and returns what it printed, trimmed.
+pub(crate) fn output_of(command: &str) -> Result<String> {
+    let mut cmd = match std::env::consts::OS {
+        "windows" => {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C");
+            cmd
+        }
+        _ => {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c");
+            cmd
+        }
+    };
+    let output = cmd
+        .arg(command)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("{ERROR_ICON} Failed to run `{command}`"))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} `{command}` failed with {status}",
+            status = output.status
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Command to make the repository private:
+///
+/// ```text
+/// curl -H "Authorization: token <your PAT>" -X PATCH https://api.github.com/repos/<your username>/<your repository name> -d '{"private": true}'
+/// ```
+fn post_request_curl(
+    _repository: String,
+    privacy: String,
+    api_url: String,
+    pat_token: Option<String>,
+) -> Result<()> {
+    let options = format!(r#"{{"private": {is_private}}}"#, is_private = privacy);
+    let auth_header = format!("Authorization: token {token}", token = pat_token.unwrap(),);
+
+    let cmd = std::process::Command::new("curl")
+        .args(["-H", &auth_header, "-X", "PATCH", &api_url, "-d", &options])
+        .output() // .spawn()
+        .with_context(|| "curl command failed to start")?;
+
+    // The API call was successful, and the response can be accessed here.
+    let stdout = String::from_utf8_lossy(&cmd.stdout);
+    match serde_json::from_str::<ApiResponse>(&stdout) {
+        Ok(response) if response.message == "Not Found" => {
+            return Err(anyhow!(
+                "{ERROR_ICON} Failed to execute `curl` command: `{response:?}`",
+                response = response,
+            ));
+        }
+        _ => (),
+    }
+
+    if !cmd.status.success() {
+        return Err(anyhow!(
+            "{ERROR_ICON} Failed to execute `curl` command: `{stderr:?}`",
+            stderr = cmd.stderr,
+        ));
+    }
+    println!("{SUCCESS_ICON} curl: {cmd}", cmd = cmd.status);
+
+    Ok(())
+}
+// // Validate the privacy and api.
+// match privacy.trim().to_lowercase().as_ref() {
+//     "true" | "false" => (),
+//     _ => return Err(anyhow!("Invalid `privacy`:, enter `true` or `false`")),
+// };
+// let api_url_template =
+//     "https://api.github.com/repos/<your username>/<your repository name>";
+// if !api_url.contains( api_url_template .split("<") .collect::<Vec<_>>() .first() .unwrap()) {
+//     return Err(anyhow!( "Internal error: `api_url` must be similar to `{help}`", help = api_url_template));
+// }
@@ -0,0 +1,103 @@
+//! `privateer sizes`: how much storage repositories use, to decide what to archive or delete
+//! before reaching the limits of a plan.
+//!
+//! Sizes are GitHub's own, in kilobytes, as listed with the repositories; no extra requests are
+//! made. The largest repositories come first, followed by totals per visibility.
+
+use crate::cli::{SizesArgs, SortKey, SortOrder};
+use crate::github::{self, Repository};
+use crate::{format, Result};
+use console::style;
+
+/// Repositories and their combined size.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Total {
+    pub repos: usize,
+    pub kb: u64,
+}
+
+impl Total {
+    fn add(&mut self, repo: &Repository) {
+        self.repos += 1;
+        self.kb += repo.size;
+    }
+}
+
+/// Totals of each visibility, and of all repositories.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Totals {
+    pub private: Total,
+    pub public: Total,
+    /// Repositories whose visibility the API didn't report.
+    pub unknown: Total,
+    pub all: Total,
+}
+
+/// Sorts `repos` largest first, by name among equal sizes.
+pub(crate) fn largest_first(repos: &mut [Repository]) {
+    repos.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+}
+
+/// Adds up the sizes of `repos` per visibility.
+pub(crate) fn totals(repos: &[Repository]) -> Totals {
+    let mut totals = Totals::default();
+    for repo in repos {
+        match repo.private {
+            Some(true) => totals.private.add(repo),
+            Some(false) => totals.public.add(repo),
+            None => totals.unknown.add(repo),
+        }
+        totals.all.add(repo);
+    }
+
+    totals
+}
+
+/// Prints the repositories of the token's user largest first, then the totals.
+pub(crate) async fn run(pat_token: &str, args: &SizesArgs) -> Result<()> {
+    let username = github::get_authenticated_user(pat_token).await?;
+    let mut repos =
+        github::get_repos_request(&username, pat_token, SortKey::Name, SortOrder::Asc).await?;
+    largest_first(&mut repos);
+
+    let shown = args.top.unwrap_or(repos.len()).min(repos.len());
+    for repo in &repos[..shown] {
+        let visibility = match repo.private {
+            Some(true) => style("private").yellow(),
+            Some(false) => style("public ").dim(),
+            None => style("unknown").red(),
+        };
+        println!(
+            "{size:>10}  {visibility}  {owner}/{name}",
+            size = format::size(repo.size),
+            owner = repo.owner.login,
+            name = repo.name,
+        );
+    }
+    if shown < repos.len() {
+        println!(
+            "{}",
+            style(format!("… and {} more", repos.len() - shown)).dim()
+        );
+    }
+
+    let totals = totals(&repos);
+    println!();
+    for (label, total) in [
+        ("private", totals.private),
+        ("public", totals.public),
+        ("unknown", totals.unknown),
+        ("total", totals.all),
+    ] {
+        if total.repos == 0 && label == "unknown" {
+            continue;
+        }
+        println!(
+            "{size:>10}  {label:<7}  {repos} repos",
+            size = format::size(total.kb),
+            repos = format::thousands(total.repos),
+        );
+    }
+
+    Ok(())
+}
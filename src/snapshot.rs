@@ -0,0 +1,144 @@
+//! Baseline snapshots of repository state and drift detection against them.
+//!
+//! `privateer snapshot` saves the live state of every repository; `privateer drift` later
+//! compares the live state to that baseline and reports repositories whose visibility changed
+//! outside privateer, as well as repositories that appeared or disappeared since.
+
+use crate::cli::{SnapshotArgs, SortKey, SortOrder};
+use crate::diff::{self, FieldChange};
+use crate::github::{self, Repository};
+use crate::{paths, schema, Result, SUCCESS_ICON};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use console::style;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The saved state of all repositories at a point in time.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub repos: Vec<Repository>,
+}
+
+/// A difference between a snapshot and the live state of a repository.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Drift {
+    /// The repository exists in both, but some of its settings differ.
+    Changed {
+        name: String,
+        changes: Vec<FieldChange>,
+    },
+    /// The repository did not exist when the snapshot was taken.
+    Added { name: String },
+    /// The repository no longer exists, or is no longer accessible.
+    Removed { name: String },
+}
+
+/// Compares the repositories in `snapshot` to `live`, ordered by repository name.
+pub(crate) fn compare(snapshot: &[Repository], live: &[Repository]) -> Vec<Drift> {
+    let mut drifts = Vec::new();
+    for before in snapshot {
+        match live.iter().find(|repo| repo.name == before.name) {
+            Some(after) => {
+                let changes: Vec<FieldChange> = match (before.private, after.private) {
+                    (old, Some(new)) if old != Some(new) => {
+                        vec![FieldChange::visibility(old, new)]
+                    }
+                    _ => Vec::new(),
+                };
+                if !changes.is_empty() {
+                    drifts.push(Drift::Changed {
+                        name: before.name.clone(),
+                        changes,
+                    });
+                }
+            }
+            None => drifts.push(Drift::Removed {
+                name: before.name.clone(),
+            }),
+        }
+    }
+    for after in live {
+        if !snapshot.iter().any(|repo| repo.name == after.name) {
+            drifts.push(Drift::Added {
+                name: after.name.clone(),
+            });
+        }
+    }
+    drifts.sort_by(|a, b| a.name().cmp(b.name()));
+
+    drifts
+}
+
+impl Drift {
+    fn name(&self) -> &str {
+        match self {
+            Drift::Changed { name, .. } | Drift::Added { name } | Drift::Removed { name } => name,
+        }
+    }
+}
+
+fn snapshot_path(args: &SnapshotArgs) -> Result<PathBuf> {
+    match &args.file {
+        Some(file) => Ok(file.clone()),
+        None => paths::snapshot_file(),
+    }
+}
+
+pub(crate) fn read_from(path: &Path) -> Result<Snapshot> {
+    let text = std::fs::read_to_string(path).with_context(|| {
+        format!("Failed to read snapshot {path:?}. Hint! Run `privateer snapshot` first")
+    })?;
+    schema::check(&text, &format!("The snapshot {path:?}"))?;
+    serde_json::from_str(&text).with_context(|| format!("Malformed snapshot {path:?}"))
+}
+
+/// Fetches the live state of all repositories and saves it as the baseline.
+pub(crate) async fn take(pat_token: &str, args: &SnapshotArgs) -> Result<()> {
+    let path = snapshot_path(args)?;
+    let snapshot = Snapshot {
+        taken_at: Utc::now(),
+        repos: github::get_repos_request("", pat_token, SortKey::Name, SortOrder::Asc).await?,
+    };
+    std::fs::write(
+        &path,
+        serde_json::to_string_pretty(&schema::versioned(&snapshot))?,
+    )
+    .with_context(|| format!("Failed to write snapshot {path:?}"))?;
+    println!(
+        "{SUCCESS_ICON} Saved snapshot of `{count}` repos to {path:?}",
+        count = snapshot.repos.len()
+    );
+
+    Ok(())
+}
+
+/// Reports every repository whose live state differs from the saved baseline.
+pub(crate) async fn drift(pat_token: &str, args: &SnapshotArgs) -> Result<()> {
+    let snapshot = read_from(&snapshot_path(args)?)?;
+    let live = github::get_repos_request("", pat_token, SortKey::Name, SortOrder::Asc).await?;
+    let drifts = compare(&snapshot.repos, &live);
+    if drifts.is_empty() {
+        println!(
+            "{SUCCESS_ICON} No drift since the snapshot taken at {taken_at}",
+            taken_at = snapshot.taken_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Found `{count}` changes since the snapshot taken at {taken_at}:",
+        count = drifts.len(),
+        taken_at = snapshot.taken_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    for drift in &drifts {
+        match drift {
+            Drift::Changed { name, changes } => println!("{}", diff::render(name, changes)),
+            Drift::Added { name } => println!("{} {}", style("+").green(), name),
+            Drift::Removed { name } => println!("{} {}", style("-").red(), name),
+        }
+    }
+
+    Ok(())
+}
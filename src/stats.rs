@@ -0,0 +1,205 @@
+//! `privateer stats`: what the audit trail says about every run so far.
+//!
+//! The trail only knows the repositories privateer changed, so the visibility shares are of
+//! those, each counted with the visibility of its last change by the end of the month. A
+//! repository is followed by its GraphQL id when its records have one, so renames don't split
+//! it in two. The ids of audit-log entries, recorded with `--verify-audit-log`, aren't changes
+//! and are left out.
+
+use crate::audit::{self, AuditRecord};
+use crate::cli::StatsArgs;
+use crate::i18n::t;
+use crate::{paths, schema, table, Result};
+use chrono::{DateTime, Utc};
+use console::style;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The changes of one month, and the visibility of the repositories known by its end.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Month {
+    /// As `YYYY-MM`.
+    pub month: String,
+    /// Every change of a setting, of any repository.
+    pub changes: usize,
+    pub privatized: usize,
+    pub publicized: usize,
+    /// Repositories whose last change of visibility by the end of the month made them private.
+    pub private: usize,
+    pub public: usize,
+}
+
+/// A repository by how often it was changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Touched {
+    /// `owner/name` as of its last change.
+    pub repo: String,
+    pub changes: usize,
+    pub last_changed: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Stats {
+    pub months: Vec<Month>,
+    /// The most changed repositories, most changed first.
+    pub most_touched: Vec<Touched>,
+}
+
+/// Aggregates `records`, oldest first, counting the changes from `since` on and keeping the
+/// `top` most changed repositories.
+pub(crate) fn compute(records: &[AuditRecord], since: Option<DateTime<Utc>>, top: usize) -> Stats {
+    let changes: Vec<&AuditRecord> = records
+        .iter()
+        .filter(|record| record.field != "audit_log")
+        .collect();
+    // Renames keep the id, so records without one fall back to the name they were made under.
+    let key = |record: &AuditRecord| {
+        record
+            .node_id
+            .clone()
+            .unwrap_or_else(|| record.repo.to_lowercase())
+    };
+    let month = |record: &AuditRecord| record.timestamp.format("%Y-%m").to_string();
+
+    let mut months: BTreeMap<String, Month> = BTreeMap::new();
+    let mut visibility: BTreeMap<String, bool> = BTreeMap::new();
+    let mut touched: BTreeMap<String, Touched> = BTreeMap::new();
+    for record in changes {
+        if let (true, Value::Bool(private)) = (record.field == "private", &record.new) {
+            visibility.insert(key(record), *private);
+        }
+        let counted = since.is_none_or(|since| record.timestamp >= since);
+        if counted {
+            let entry = touched.entry(key(record)).or_insert_with(|| Touched {
+                repo: record.repo.clone(),
+                changes: 0,
+                last_changed: record.timestamp,
+            });
+            entry.repo = record.repo.clone();
+            entry.changes += 1;
+            entry.last_changed = record.timestamp;
+        }
+        // Months before `since` are only needed for the visibility they leave behind.
+        let month = months.entry(month(record)).or_insert_with(|| Month {
+            month: month(record),
+            changes: 0,
+            privatized: 0,
+            publicized: 0,
+            private: 0,
+            public: 0,
+        });
+        if counted {
+            month.changes += 1;
+            match (record.field.as_str(), &record.new) {
+                ("private", Value::Bool(true)) => month.privatized += 1,
+                ("private", Value::Bool(false)) => month.publicized += 1,
+                _ => {}
+            }
+        }
+        month.private = visibility.values().filter(|private| **private).count();
+        month.public = visibility.len() - month.private;
+    }
+    let cutoff = since.map(|since| since.format("%Y-%m").to_string());
+    let months = months
+        .into_values()
+        .filter(|month| cutoff.as_ref().is_none_or(|cutoff| month.month >= *cutoff))
+        .collect();
+
+    let mut most_touched: Vec<Touched> = touched.into_values().collect();
+    most_touched.sort_by(|a, b| {
+        b.changes
+            .cmp(&a.changes)
+            .then(b.last_changed.cmp(&a.last_changed))
+    });
+    most_touched.truncate(top);
+
+    Stats {
+        months,
+        most_touched,
+    }
+}
+
+/// Prints the statistics of the audit trail for `args`.
+pub(crate) fn run(args: &StatsArgs) -> Result<()> {
+    let records: Vec<AuditRecord> = audit::read_from(&paths::audit_file()?)?
+        .into_iter()
+        .filter(|record| args.until.is_none_or(|until| record.timestamp <= until))
+        .collect();
+    let stats = compute(&records, args.since, args.top);
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&schema::versioned(&stats))?
+        );
+        return Ok(());
+    }
+    if stats.months.is_empty() {
+        println!("{}", t!("no-audit-records"));
+        return Ok(());
+    }
+
+    println!("{}", render(&stats));
+
+    Ok(())
+}
+
+/// Lays out `stats` as a table of months and one of the most changed repositories.
+pub(crate) fn render(stats: &Stats) -> String {
+    let column = |header, numeric| table::Column { header, numeric };
+    let months: Vec<Vec<String>> = stats
+        .months
+        .iter()
+        .map(|month| {
+            let known = month.private + month.public;
+            vec![
+                month.month.clone(),
+                month.changes.to_string(),
+                month.privatized.to_string(),
+                month.publicized.to_string(),
+                month.private.to_string(),
+                month.public.to_string(),
+                match known {
+                    0 => "-".to_owned(),
+                    known => format!("{}%", month.private * 100 / known),
+                },
+            ]
+        })
+        .collect();
+    let repos: Vec<Vec<String>> = stats
+        .most_touched
+        .iter()
+        .map(|touched| {
+            vec![
+                touched.repo.clone(),
+                touched.changes.to_string(),
+                touched.last_changed.format("%Y-%m-%d").to_string(),
+            ]
+        })
+        .collect();
+
+    format!(
+        "{}\n\n{}\n{}",
+        table::render(
+            &[
+                column("month", false),
+                column("changes", true),
+                column("privatized", true),
+                column("publicized", true),
+                column("private", true),
+                column("public", true),
+                column("private share", true),
+            ],
+            &months,
+        ),
+        style("Most changed repositories").bold(),
+        table::render(
+            &[
+                column("repo", false),
+                column("changes", true),
+                column("last change", false),
+            ],
+            &repos,
+        )
+    )
+}
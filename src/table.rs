@@ -0,0 +1,52 @@
+//! Aligned columns of text for terminal output.
+//!
+//! Cells may be styled: widths are measured without their escape codes.
+
+use console::{measure_text_width, pad_str, style, Alignment};
+
+/// A column of a [`render`]ed table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Column {
+    pub header: &'static str,
+    /// Numbers are aligned right, text left.
+    pub numeric: bool,
+}
+
+/// Lays out `rows` under the dimmed headers of `columns`, two spaces apart, one line each.
+///
+/// The last column isn't padded, so lines don't end in spaces.
+pub(crate) fn render(columns: &[Column], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .map(|row| row.get(i).map_or(0, |cell| measure_text_width(cell)))
+                .chain(std::iter::once(column.header.len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let line = |cells: Vec<String>| {
+        let last = cells.len().saturating_sub(1);
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| match (columns[i].numeric, i == last) {
+                (true, _) => pad_str(cell, widths[i], Alignment::Right, None).into_owned(),
+                (false, true) => cell.clone(),
+                (false, false) => pad_str(cell, widths[i], Alignment::Left, None).into_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    let headers = columns
+        .iter()
+        .map(|column| style(column.header).dim().to_string())
+        .collect();
+    std::iter::once(line(headers))
+        .chain(rows.iter().map(|row| line(row.clone())))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,72 @@
+//! Selecting repositories by a topic, such as `to-privatize`, with `--topic`.
+//!
+//! Topics can be set from anywhere GitHub is, such as its mobile app, so tagging a repository
+//! queues it for the next run, which may be a scheduled `privateer --topic to-privatize
+//! --private true --yes`. With `--remove-topic`, the topic is removed from each repository once
+//! it has the visibility wanted, so the queue empties itself.
+
+use crate::apply::{self, Edit};
+use crate::github::{self, Repository};
+use crate::i18n::t;
+use crate::output::status;
+use crate::plan::Wanted;
+use crate::{cancel, Result, ERROR_ICON, SUCCESS_ICON};
+
+/// Returns `true` if `repo` carries `topic`.
+pub(crate) fn has(repo: &Repository, topic: &str) -> bool {
+    repo.topics
+        .iter()
+        .any(|tagged| tagged.eq_ignore_ascii_case(topic))
+}
+
+/// Removes `topic` from each of `wanted` that now has the visibility wanted, as GitHub reports
+/// it, recording the change in the audit trail with `actor`; the others keep it for a later run.
+///
+/// Returns how many repositories the topic was removed from. A repository that can't be fetched
+/// or changed keeps its topic and is reported, without failing the others.
+pub(crate) async fn remove(
+    wanted: &[Wanted],
+    topic: &str,
+    username: &str,
+    pat_token: &str,
+    actor: &str,
+) -> Result<usize> {
+    let mut removed = 0;
+    for (repo, private) in wanted {
+        cancel::check()?;
+        let owner = match repo.owner.login.is_empty() {
+            true => username,
+            false => repo.owner.login.as_str(),
+        };
+        let live = match github::get_repo_fresh(owner, &repo.name, pat_token).await {
+            Ok(live) => live,
+            Err(err) => {
+                eprintln!("{err}");
+                continue;
+            }
+        };
+        if live.private != Some(*private) || !has(&live, topic) {
+            continue;
+        }
+        let edit = Edit {
+            topics: Some(
+                live.topics
+                    .iter()
+                    .filter(|tagged| !tagged.eq_ignore_ascii_case(topic))
+                    .cloned()
+                    .collect(),
+            ),
+            ..Edit::default()
+        };
+        match apply::apply_edit(&live, &edit, username, pat_token, actor).await {
+            Ok(()) => removed += 1,
+            Err(err) => eprintln!("{ERROR_ICON} `{owner}/{}`: {err}", repo.name),
+        }
+    }
+    status!(
+        "{SUCCESS_ICON} {}",
+        t!("topic-removed", topic = topic, count = removed)
+    );
+
+    Ok(removed)
+}
@@ -0,0 +1,294 @@
+//! The HTTP layer under every GitHub API request.
+//!
+//! Requests go through an [`HttpTransport`], [`ReqwestTransport`] unless another one is in scope
+//! with [`with_transport`]. [`FakeTransport`] answers from canned responses instead, so that
+//! pagination, retries and error messages can be tested without reaching GitHub.
+//!
+//! The rest of what a [`Client`](crate::Client) configures, the host, `User-Agent`, API version,
+//! timeout and retries, is kept in scope next to the transport as [`Settings`].
+
+use crate::cache::RepoCache;
+use crate::urls::{self, Host};
+use crate::Result;
+use anyhow::anyhow;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use url::Url;
+
+/// A boxed future sending one request.
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<HttpResponse>> + Send + 'a>>;
+
+/// An HTTP request to the GitHub API.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    /// `GET`, `POST`, and so on.
+    pub method: Method,
+    /// Full URL, including the query.
+    pub url: Url,
+    /// Request headers.
+    pub headers: HeaderMap,
+    /// JSON body, if any.
+    pub body: Option<String>,
+    /// Longest wait for the whole response, if limited.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    /// A request without headers, body or timeout.
+    pub fn new(method: Method, url: Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: HeaderMap::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+
+    /// Adds the header `name: value`.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Sets the body to `body`.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+}
+
+/// A response read in full.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    /// Status code.
+    pub status: StatusCode,
+    /// Response headers.
+    pub headers: HeaderMap,
+    /// URL the response came from.
+    pub url: Url,
+    /// Body as text.
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// A response with `status` and `body`, and no headers.
+    ///
+    /// # Panics
+    ///
+    /// If `status` isn't a valid status code.
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::from_u16(status).expect("a valid status code"),
+            headers: HeaderMap::new(),
+            url: Url::parse("http://localhost/").expect("a valid URL"),
+            body: body.into(),
+        }
+    }
+
+    /// Adds the header `name: value`.
+    ///
+    /// # Panics
+    ///
+    /// If `name` or `value` isn't valid in a header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(
+            HeaderName::from_bytes(name.as_bytes()).expect("a valid header name"),
+            HeaderValue::from_str(value).expect("a valid header value"),
+        );
+        self
+    }
+
+    /// Value of the header `name`, if present and text.
+    pub fn header_str(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+}
+
+/// Sends HTTP requests.
+pub trait HttpTransport: Send + Sync {
+    /// Sends `request` and reads the whole response. Redirects are returned, not followed.
+    fn send(&self, request: HttpRequest) -> SendFuture<'_>;
+}
+
+/// Sends requests over the network with [`reqwest`].
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self {
+            // Redirects are followed by the caller: a redirected POST would turn into a GET.
+            client: reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .expect("a TLS backend"),
+        }
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> SendFuture<'_> {
+        Box::pin(async move {
+            let mut builder = self
+                .client
+                .request(request.method, request.url)
+                .headers(request.headers);
+            if let Some(body) = request.body {
+                builder = builder.body(body);
+            }
+            if let Some(timeout) = request.timeout {
+                builder = builder.timeout(timeout);
+            }
+            let response = builder.send().await?;
+
+            Ok(HttpResponse {
+                status: response.status(),
+                headers: response.headers().clone(),
+                url: response.url().clone(),
+                body: response.text().await?,
+            })
+        })
+    }
+}
+
+/// Answers requests from canned responses, recording each request it is sent.
+///
+/// Responses are registered per method and path, and given out in the order they were added;
+/// the last one for a path keeps being given out.
+#[derive(Debug, Default)]
+pub struct FakeTransport {
+    routes: Mutex<Vec<(Method, String, VecDeque<HttpResponse>)>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl FakeTransport {
+    /// A transport without any responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers a later `method` request to `path` with `response`.
+    pub fn respond(&self, method: Method, path: &str, response: HttpResponse) -> &Self {
+        let mut routes = self.routes.lock().expect("routes lock");
+        match routes
+            .iter_mut()
+            .find(|(m, p, _)| *m == method && p == path)
+        {
+            Some((_, _, responses)) => responses.push_back(response),
+            None => routes.push((method, path.to_owned(), VecDeque::from([response]))),
+        }
+        self
+    }
+
+    /// The requests sent so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().expect("requests lock").clone()
+    }
+}
+
+impl HttpTransport for FakeTransport {
+    fn send(&self, request: HttpRequest) -> SendFuture<'_> {
+        let mut routes = self.routes.lock().expect("routes lock");
+        let response = routes
+            .iter_mut()
+            .find(|(method, path, _)| *method == request.method && path == request.url.path())
+            .and_then(|(_, _, responses)| match responses.len() {
+                1 => responses.front().cloned(),
+                _ => responses.pop_front(),
+            })
+            .map(|response| HttpResponse {
+                url: request.url.clone(),
+                ..response
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "No response for {method} {path}",
+                    method = request.method,
+                    path = request.url.path()
+                )
+            });
+        self.requests.lock().expect("requests lock").push(request);
+
+        Box::pin(std::future::ready(response))
+    }
+}
+
+/// The `User-Agent` unless another is configured, naming the version and where to report
+/// problems, as GitHub asks of API clients.
+pub(crate) const DEFAULT_USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (+",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+/// The REST API version requested unless another is configured.
+pub(crate) const DEFAULT_API_VERSION: &str = "2022-11-28";
+
+/// How API requests are built and sent.
+#[derive(Clone)]
+pub(crate) struct Settings {
+    pub transport: Arc<dyn HttpTransport>,
+    pub host: Host,
+    pub user_agent: String,
+    /// Sent as `X-GitHub-Api-Version`; `None` leaves the header out, for servers that predate
+    /// API versions.
+    pub api_version: Option<String>,
+    pub timeout: Option<Duration>,
+    /// How many times a request rejected for the rate limit is sent again.
+    pub retries: u32,
+    /// Repositories fetched earlier in the session, reused instead of fetching them again.
+    pub cache: Option<Arc<RepoCache>>,
+}
+
+impl Default for Settings {
+    /// The settings of the command: the network, the host selected by `GH_HOST`, and one retry.
+    fn default() -> Self {
+        static TRANSPORT: OnceLock<Arc<dyn HttpTransport>> = OnceLock::new();
+
+        Self {
+            transport: Arc::clone(TRANSPORT.get_or_init(|| Arc::new(ReqwestTransport::default()))),
+            host: urls::from_env().clone(),
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            api_version: Some(DEFAULT_API_VERSION.to_owned()),
+            timeout: None,
+            retries: 1,
+            cache: None,
+        }
+    }
+}
+
+tokio::task_local! {
+    static SETTINGS: Arc<Settings>;
+}
+
+/// Runs `future` with its API requests built and sent according to `settings`.
+pub(crate) async fn with_settings<F: Future>(settings: Arc<Settings>, future: F) -> F::Output {
+    SETTINGS.scope(settings, future).await
+}
+
+/// The settings in scope, or the defaults.
+pub(crate) fn settings() -> Arc<Settings> {
+    SETTINGS
+        .try_with(Arc::clone)
+        .unwrap_or_else(|_| Arc::new(Settings::default()))
+}
+
+/// Runs `future` with its API requests sent through `transport`.
+pub async fn with_transport<F: Future>(transport: Arc<dyn HttpTransport>, future: F) -> F::Output {
+    let settings = Settings {
+        transport,
+        ..(*settings()).clone()
+    };
+    with_settings(Arc::new(settings), future).await
+}
@@ -0,0 +1,959 @@
+//! Full-screen terminal UI, started with `privateer tui`.
+//!
+//! The browse screen shows a searchable table of repositories next to a detail pane and the
+//! panel of pending changes. Checked repositories (or the highlighted one) are staged to become
+//! private with `P` or public with `U`, and `e` opens a form editing several settings of the
+//! highlighted one at once. `A` opens the apply screen, where `y` applies the staged changes with
+//! live progress. `?` lists every keyboard shortcut.
+
+use crate::apply::{self, Edit};
+use crate::cli::{SortKey, SortOrder};
+use crate::github::{self, Repository};
+use crate::picker::{self, PickerItem};
+use crate::plan::{self, SkipReason};
+use crate::{cancel, events, format, transport, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{
+    Block, Cell, Clear, Gauge, List, ListItem, Paragraph, Row, Table, TableState, Wrap,
+};
+use ratatui::{DefaultTerminal, Frame};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+/// Maximum number of repositories updated at the same time from the apply screen.
+const CONCURRENCY: usize = 4;
+
+/// Keyboard shortcuts listed by the `?` help overlay.
+const KEYBINDINGS: &[(&str, &str)] = &[
+    ("/", "search by name; enter or esc when done"),
+    (
+        "j k \u{2191} \u{2193}",
+        "move the cursor; pgup/pgdn by a page",
+    ),
+    ("space", "check or uncheck the highlighted repo"),
+    (
+        "P",
+        "stage the checked (or highlighted) repos to become private",
+    ),
+    (
+        "U",
+        "stage the checked (or highlighted) repos to become public",
+    ),
+    (
+        "e",
+        "edit the settings of the highlighted repo; enter stages them",
+    ),
+    ("x", "unstage the checked (or highlighted) repos"),
+    ("A", "review the pending changes"),
+    ("y", "apply the pending changes, on the review screen"),
+    ("?", "show or hide this help"),
+    (
+        "q esc",
+        "quit, go back from the review screen, or abort the changes not started",
+    ),
+];
+
+/// The fields of the edit form, in order.
+const FIELDS: &[&str] = &[
+    "visibility",
+    "description",
+    "topics",
+    "issues",
+    "projects",
+    "wiki",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Screen {
+    Browse,
+    /// The edit form, over the browse screen.
+    Edit,
+    Apply,
+}
+
+/// The staged changes of `repos[repo]`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Pending {
+    pub repo: usize,
+    /// The visibility to change to, if it changes.
+    pub private: Option<bool>,
+    /// The other settings to change.
+    pub edit: Edit,
+}
+
+impl Pending {
+    fn is_empty(&self) -> bool {
+        self.private.is_none() && self.edit.is_empty()
+    }
+
+    /// The staged changes of `repo` in a few words, such as `public → private, topics, wiki off`.
+    fn summary(&self, repo: &Repository) -> String {
+        let mut parts: Vec<String> = self
+            .private
+            .map(|private| {
+                format!(
+                    "{} \u{2192} {}",
+                    visibility(repo.private),
+                    visibility(Some(private))
+                )
+            })
+            .into_iter()
+            .collect();
+        for change in self.edit.changes(repo) {
+            let field = change.field.trim_start_matches("has_");
+            parts.push(match change.new.as_bool() {
+                Some(true) => format!("{field} on"),
+                Some(false) => format!("{field} off"),
+                None => field.to_owned(),
+            });
+        }
+
+        parts.join(", ")
+    }
+}
+
+/// The settings of one repository as shown in the edit form.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Form {
+    pub repo: usize,
+    /// Index into [`FIELDS`] of the field keys go to.
+    pub focus: usize,
+    pub private: bool,
+    pub description: String,
+    /// Topics separated by commas or spaces.
+    pub topics: String,
+    pub has_issues: bool,
+    pub has_projects: bool,
+    pub has_wiki: bool,
+}
+
+impl Form {
+    /// The form for `repos[index]`, filled in with its settings and the changes staged for it.
+    fn new(index: usize, repo: &Repository, pending: Option<&Pending>) -> Self {
+        let edit = pending
+            .map(|pending| pending.edit.clone())
+            .unwrap_or_default();
+        Self {
+            repo: index,
+            focus: 0,
+            private: pending
+                .and_then(|pending| pending.private)
+                .or(repo.private)
+                .unwrap_or_default(),
+            description: edit
+                .description
+                .or_else(|| repo.description.clone())
+                .unwrap_or_default(),
+            topics: edit
+                .topics
+                .unwrap_or_else(|| repo.topics.clone())
+                .join(", "),
+            has_issues: edit.has_issues.unwrap_or(repo.has_issues),
+            has_projects: edit.has_projects.unwrap_or(repo.has_projects),
+            has_wiki: edit.has_wiki.unwrap_or(repo.has_wiki),
+        }
+    }
+
+    /// The text of the focused field, unless it is a toggle.
+    fn text(&mut self) -> Option<&mut String> {
+        match FIELDS[self.focus] {
+            "description" => Some(&mut self.description),
+            "topics" => Some(&mut self.topics),
+            _ => None,
+        }
+    }
+
+    fn toggle(&mut self) {
+        match FIELDS[self.focus] {
+            "visibility" => self.private = !self.private,
+            "issues" => self.has_issues = !self.has_issues,
+            "projects" => self.has_projects = !self.has_projects,
+            "wiki" => self.has_wiki = !self.has_wiki,
+            _ => {}
+        }
+    }
+
+    /// The value of `field` as shown in the form.
+    fn value(&self, field: &str) -> String {
+        let on_off = |enabled: bool| match enabled {
+            true => "on".to_owned(),
+            false => "off".to_owned(),
+        };
+        match field {
+            "visibility" => visibility(Some(self.private)).to_owned(),
+            "description" => self.description.clone(),
+            "topics" => self.topics.clone(),
+            "issues" => on_off(self.has_issues),
+            "projects" => on_off(self.has_projects),
+            _ => on_off(self.has_wiki),
+        }
+    }
+
+    /// The changes the form makes to `repo`, which are none if nothing was changed.
+    pub(crate) fn to_pending(&self, repo: &Repository) -> Pending {
+        let changed = |current: bool, wanted: bool| (current != wanted).then_some(wanted);
+        let description = self.description.trim();
+        let topics = parse_topics(&self.topics);
+        Pending {
+            repo: self.repo,
+            private: (repo.private != Some(self.private)).then_some(self.private),
+            edit: Edit {
+                description: (repo.description.as_deref().unwrap_or_default() != description)
+                    .then(|| description.to_owned()),
+                topics: (topics != repo.topics).then_some(topics),
+                has_issues: changed(repo.has_issues, self.has_issues),
+                has_projects: changed(repo.has_projects, self.has_projects),
+                has_wiki: changed(repo.has_wiki, self.has_wiki),
+            },
+        }
+    }
+}
+
+/// The topics of `input`, separated by commas or spaces, lowercased like GitHub stores them and
+/// without repeats.
+pub(crate) fn parse_topics(input: &str) -> Vec<String> {
+    let mut topics: Vec<String> = Vec::new();
+    for topic in input.split([',', ' ']).map(str::trim) {
+        let topic = topic.to_lowercase();
+        if !topic.is_empty() && !topics.contains(&topic) {
+            topics.push(topic);
+        }
+    }
+
+    topics
+}
+
+/// Progress of a pending change on the apply screen.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ChangeState {
+    Queued,
+    Running(&'static str),
+    Done,
+    /// The repository already had the staged visibility, or changed since it was staged and was
+    /// left alone.
+    Skipped,
+    Failed(String),
+    /// Aborted before it was attempted.
+    Cancelled,
+}
+
+pub(crate) struct App {
+    pub repos: Vec<Repository>,
+    keys: Vec<PickerItem>,
+    query: String,
+    searching: bool,
+    /// Indices into `repos` of the rows matching `query`.
+    pub visible: Vec<usize>,
+    table: TableState,
+    pub checked: Vec<bool>,
+    pub pending: Vec<Pending>,
+    pub screen: Screen,
+    /// The edit form, on [`Screen::Edit`].
+    pub form: Option<Form>,
+    pub progress: Vec<ChangeState>,
+    applying: bool,
+    /// Cancelled to abort the changes not started yet.
+    pub cancel: CancellationToken,
+    /// Whether the help overlay is shown.
+    pub help: bool,
+    pub quit: bool,
+}
+
+impl App {
+    pub(crate) fn new(repos: Vec<Repository>) -> Self {
+        let keys: Vec<PickerItem> = repos
+            .iter()
+            .map(|repo| PickerItem {
+                key: repo.name.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let mut app = Self {
+            checked: vec![false; repos.len()],
+            repos,
+            keys,
+            query: String::new(),
+            searching: false,
+            visible: Vec::new(),
+            table: TableState::default(),
+            pending: Vec::new(),
+            screen: Screen::Browse,
+            form: None,
+            progress: Vec::new(),
+            applying: false,
+            cancel: CancellationToken::new(),
+            help: false,
+            quit: false,
+        };
+        app.refilter();
+        app
+    }
+
+    fn refilter(&mut self) {
+        self.visible = picker::filter(&self.keys, &self.query)
+            .into_iter()
+            .map(|m| m.index)
+            .collect();
+        self.table.select((!self.visible.is_empty()).then_some(0));
+    }
+
+    /// Index into `repos` of the highlighted row.
+    pub(crate) fn highlighted(&self) -> Option<usize> {
+        self.visible.get(self.table.selected()?).copied()
+    }
+
+    /// The checked repositories, or the highlighted one if none are checked.
+    fn targets(&self) -> Vec<usize> {
+        let checked: Vec<usize> = (0..self.repos.len()).filter(|&i| self.checked[i]).collect();
+        match checked.is_empty() {
+            true => self.highlighted().into_iter().collect(),
+            false => checked,
+        }
+    }
+
+    /// Stages a change to `private` for the targets, replacing visibility changes already staged
+    /// for them. Repositories with a reason to be skipped, see [`plan::skip_reason`], such as
+    /// being at that visibility already, are left alone.
+    fn stage(&mut self, private: bool) {
+        for repo in self.targets() {
+            let wanted = plan::skip_reason(&self.repos[repo], private)
+                .is_none()
+                .then_some(private);
+            match self.pending.iter_mut().find(|pending| pending.repo == repo) {
+                Some(pending) => pending.private = wanted,
+                None => self.pending.push(Pending {
+                    repo,
+                    private: wanted,
+                    edit: Edit::default(),
+                }),
+            }
+        }
+        self.pending.retain(|pending| !pending.is_empty());
+        self.checked.iter_mut().for_each(|checked| *checked = false);
+    }
+
+    /// Opens the edit form for the highlighted repository, if its settings can be changed.
+    fn open_form(&mut self) {
+        let Some(index) = self.highlighted() else {
+            return;
+        };
+        if !self.repos[index].can_administer() {
+            return;
+        }
+        let pending = self.pending.iter().find(|pending| pending.repo == index);
+        self.form = Some(Form::new(index, &self.repos[index], pending));
+        self.screen = Screen::Edit;
+    }
+
+    /// Updates the edit form for a key press. `enter` stages its changes, replacing those staged
+    /// for the repository before, and `esc` closes it without staging anything.
+    fn handle_form_key(&mut self, key: KeyCode) {
+        let Some(form) = &mut self.form else {
+            self.screen = Screen::Browse;
+            return;
+        };
+        match key {
+            KeyCode::Esc => {
+                self.form = None;
+                self.screen = Screen::Browse;
+            }
+            KeyCode::Enter => {
+                let pending = form.to_pending(&self.repos[form.repo]);
+                self.pending.retain(|staged| staged.repo != pending.repo);
+                if !pending.is_empty() {
+                    self.pending.push(pending);
+                }
+                self.form = None;
+                self.screen = Screen::Browse;
+            }
+            KeyCode::Up | KeyCode::BackTab => {
+                form.focus = (form.focus + FIELDS.len() - 1) % FIELDS.len()
+            }
+            KeyCode::Down | KeyCode::Tab => form.focus = (form.focus + 1) % FIELDS.len(),
+            KeyCode::Backspace => {
+                if let Some(text) = form.text() {
+                    text.pop();
+                }
+            }
+            KeyCode::Char(c) => match form.text() {
+                Some(text) => text.push(c),
+                None if c == ' ' => form.toggle(),
+                None => {}
+            },
+            KeyCode::Left | KeyCode::Right if form.text().is_none() => form.toggle(),
+            _ => {}
+        }
+    }
+
+    fn unstage(&mut self) {
+        let targets = self.targets();
+        self.pending
+            .retain(|pending| !targets.contains(&pending.repo));
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(selected) = self.table.selected() else {
+            return;
+        };
+        let last = self.visible.len().saturating_sub(1) as isize;
+        self.table
+            .select(Some((selected as isize + delta).clamp(0, last) as usize));
+    }
+
+    /// Updates the state for a key press; returns `true` when the staged changes should be applied.
+    pub(crate) fn handle_key(&mut self, key: KeyCode) -> bool {
+        // Any key closes the help overlay.
+        if self.help {
+            self.help = false;
+            return false;
+        }
+        if self.screen == Screen::Edit {
+            self.handle_form_key(key);
+            return false;
+        }
+        match (self.screen, self.searching, key) {
+            (Screen::Browse, true, KeyCode::Enter | KeyCode::Esc) => self.searching = false,
+            (Screen::Browse, true, KeyCode::Backspace) => {
+                self.query.pop();
+                self.refilter();
+            }
+            (Screen::Browse, true, KeyCode::Char(c)) => {
+                self.query.push(c);
+                self.refilter();
+            }
+            (Screen::Browse, _, KeyCode::Up) => self.move_cursor(-1),
+            (Screen::Browse, _, KeyCode::Down) => self.move_cursor(1),
+            (Screen::Browse, _, KeyCode::PageUp) => self.move_cursor(-10),
+            (Screen::Browse, _, KeyCode::PageDown) => self.move_cursor(10),
+            (Screen::Browse, false, KeyCode::Char('k')) => self.move_cursor(-1),
+            (Screen::Browse, false, KeyCode::Char('j')) => self.move_cursor(1),
+            (Screen::Browse, false, KeyCode::Char('/')) => self.searching = true,
+            (_, false, KeyCode::Char('?')) => self.help = true,
+            (Screen::Browse, false, KeyCode::Char(' ')) => {
+                if let Some(repo) = self.highlighted() {
+                    self.checked[repo] = !self.checked[repo];
+                }
+            }
+            (Screen::Browse, false, KeyCode::Char('P')) => self.stage(true),
+            (Screen::Browse, false, KeyCode::Char('U')) => self.stage(false),
+            (Screen::Browse, false, KeyCode::Char('x')) => self.unstage(),
+            (Screen::Browse, false, KeyCode::Char('e')) => self.open_form(),
+            (Screen::Browse, false, KeyCode::Char('A')) if !self.pending.is_empty() => {
+                self.progress = vec![ChangeState::Queued; self.pending.len()];
+                self.screen = Screen::Apply;
+            }
+            (Screen::Browse, false, KeyCode::Char('q') | KeyCode::Esc) => self.quit = true,
+            (Screen::Apply, _, KeyCode::Char('y')) if !self.applying => {
+                self.applying = true;
+                return true;
+            }
+            (Screen::Apply, _, KeyCode::Char('q') | KeyCode::Esc) if self.is_running() => {
+                self.cancel.cancel()
+            }
+            (Screen::Apply, _, KeyCode::Char('q') | KeyCode::Esc) => match self.applying {
+                true => self.quit = true,
+                false => self.screen = Screen::Browse,
+            },
+            _ => {}
+        }
+
+        false
+    }
+
+    fn is_running(&self) -> bool {
+        self.applying
+            && self
+                .progress
+                .iter()
+                .any(|state| matches!(state, ChangeState::Queued | ChangeState::Running(_)))
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [body, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        match self.screen {
+            Screen::Browse | Screen::Edit => self.draw_browse(frame, body),
+            Screen::Apply => self.draw_apply(frame, body),
+        }
+        if let Some(form) = &self.form {
+            draw_form(frame, body, form, &self.repos[form.repo]);
+        }
+        let help = match (self.screen, self.searching) {
+            (Screen::Edit, _) => {
+                "\u{2191} \u{2193} field \u{b7} type to edit \u{b7} space toggle \u{b7} enter stage \u{b7} esc cancel"
+            }
+            (Screen::Browse, true) => "type to search \u{b7} enter/esc done",
+            (Screen::Browse, false) => {
+                "/ search \u{b7} space check \u{b7} P private \u{b7} U public \u{b7} e edit \u{b7} x unstage \u{b7} A apply \u{b7} ? help \u{b7} q quit"
+            }
+            (Screen::Apply, _) if self.is_running() && self.cancel.is_cancelled() => {
+                "aborting after the changes in flight\u{2026}"
+            }
+            (Screen::Apply, _) if self.is_running() => "applying\u{2026} \u{b7} q abort",
+            (Screen::Apply, _) if self.applying => "q quit",
+            (Screen::Apply, _) => "y apply \u{b7} q back",
+        };
+        frame.render_widget(Paragraph::new(help).dim(), footer);
+        if self.help {
+            draw_help(frame, body);
+        }
+    }
+
+    fn draw_browse(&mut self, frame: &mut Frame, area: Rect) {
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(area);
+        let [detail, pending] =
+            Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(right);
+
+        let now = chrono::Utc::now();
+        let rows = self.visible.iter().map(|&index| {
+            let repo = &self.repos[index];
+            let staged = self.pending.iter().find(|pending| pending.repo == index);
+            Row::new(vec![
+                Cell::from(match self.checked[index] {
+                    true => "[x]",
+                    false => "[ ]",
+                }),
+                Cell::from(repo.name.clone()),
+                Cell::from(visibility(repo.private)),
+                Cell::from(
+                    match staged.map(|pending| (pending.private, pending.edit.is_empty())) {
+                        Some((Some(private), true)) => {
+                            format!("\u{2192} {}", visibility(Some(private)))
+                        }
+                        Some((Some(private), false)) => {
+                            format!("\u{2192} {}+", visibility(Some(private)))
+                        }
+                        Some((None, _)) => "edited".to_owned(),
+                        None => String::new(),
+                    },
+                )
+                .fg(Color::Yellow),
+                Cell::from(
+                    repo.pushed_at
+                        .map_or(String::new(), |pushed_at| format::date(pushed_at, now)),
+                ),
+            ])
+        });
+        let title = match self.query.is_empty() && !self.searching {
+            true => format!(" Repositories ({}) ", self.repos.len()),
+            false => format!(
+                " Repositories ({}/{}) /{} ",
+                self.visible.len(),
+                self.repos.len(),
+                self.query
+            ),
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Fill(1),
+                Constraint::Length(7),
+                Constraint::Length(10),
+                Constraint::Length(format::date_width().max("pushed".len()) as u16),
+            ],
+        )
+        .header(Row::new(["", "name", "", "staged", "pushed"]).bold())
+        .block(Block::bordered().title(title))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, left, &mut self.table);
+
+        let lines = match self.highlighted().map(|index| &self.repos[index]) {
+            Some(repo) => vec![
+                Line::from(repo.name.clone().bold()),
+                Line::from(repo.url.clone().underlined()),
+                Line::from(repo.description.clone().unwrap_or_default()),
+                Line::from(""),
+                Line::from(format!("visibility  {}", visibility(repo.private))),
+                Line::from(format!("topics      {}", repo.topics.join(", "))),
+                Line::from(format!(
+                    "features    {}",
+                    [
+                        (repo.has_issues, "issues"),
+                        (repo.has_projects, "projects"),
+                        (repo.has_wiki, "wiki"),
+                    ]
+                    .iter()
+                    .filter(|(enabled, _)| *enabled)
+                    .map(|(_, feature)| *feature)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                )),
+                Line::from(format!(
+                    "branch      {}",
+                    repo.default_branch.as_deref().unwrap_or("-")
+                )),
+                Line::from(format!(
+                    "created     {}",
+                    repo.created_at
+                        .map_or("-".to_owned(), |created_at| format::date(created_at, now))
+                )),
+                Line::from(format!(
+                    "pushed      {}",
+                    repo.pushed_at
+                        .map_or("never".to_owned(), |pushed_at| format::date(pushed_at, now))
+                )),
+                Line::from(match repo.is_empty() {
+                    true => "size        empty, no commits yet".to_owned(),
+                    false => format!("size        {} KB", format::thousands(repo.size as usize)),
+                }),
+                Line::from(format!(
+                    "stars {}  forks {}  open issues {}",
+                    repo.stargazers_count, repo.forks_count, repo.open_issues_count
+                )),
+                Line::from(
+                    [
+                        (repo.fork, "fork"),
+                        (repo.archived, "archived"),
+                        (repo.is_empty(), "empty"),
+                        (!repo.can_administer(), "read-only"),
+                    ]
+                    .iter()
+                    .filter(|(enabled, _)| *enabled)
+                    .map(|(_, label)| Span::from(format!("{label} ")).magenta())
+                    .collect::<Vec<_>>(),
+                ),
+            ],
+            None => vec![Line::from("No repository selected").dim()],
+        };
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: true })
+                .block(Block::bordered().title(" Details ")),
+            detail,
+        );
+
+        let items: Vec<ListItem> = self
+            .pending
+            .iter()
+            .map(|pending| {
+                let repo = &self.repos[pending.repo];
+                ListItem::new(format!("{}: {}", repo.name, pending.summary(repo)))
+            })
+            .collect();
+        frame.render_widget(
+            List::new(items).block(
+                Block::bordered().title(format!(" Pending changes ({}) ", self.pending.len())),
+            ),
+            pending,
+        );
+    }
+
+    fn draw_apply(&self, frame: &mut Frame, area: Rect) {
+        let [gauge, list] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+        let finished = self
+            .progress
+            .iter()
+            .filter(|state| {
+                matches!(
+                    state,
+                    ChangeState::Done
+                        | ChangeState::Skipped
+                        | ChangeState::Failed(_)
+                        | ChangeState::Cancelled
+                )
+            })
+            .count();
+        let ratio = match self.progress.is_empty() {
+            true => 0.0,
+            false => finished as f64 / self.progress.len() as f64,
+        };
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::bordered().title(" Apply "))
+                .gauge_style(Style::new().fg(Color::Green))
+                .label(format!("{finished}/{}", self.progress.len()))
+                .ratio(ratio),
+            gauge,
+        );
+
+        let items: Vec<ListItem> = self
+            .pending
+            .iter()
+            .zip(&self.progress)
+            .map(|(pending, state)| {
+                let repo = &self.repos[pending.repo];
+                let state = match state {
+                    ChangeState::Queued => Span::from("queued").dim(),
+                    ChangeState::Running(step) => Span::from(*step).yellow(),
+                    ChangeState::Done => Span::from("done").green(),
+                    ChangeState::Skipped => {
+                        Span::from(apply::already(pending.private.unwrap_or_default())).dim()
+                    }
+                    ChangeState::Failed(err) => Span::from(format!("failed: {err}")).red(),
+                    ChangeState::Cancelled => Span::from("not started").dim(),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::from(format!("{:30} {}  ", repo.name, pending.summary(repo))),
+                    state,
+                ]))
+            })
+            .collect();
+        frame.render_widget(
+            List::new(items).block(Block::bordered().title(" Changes ")),
+            list,
+        );
+    }
+}
+
+/// Draws the edit form of `repo` in a box centered over `area`, the focused field highlighted.
+fn draw_form(frame: &mut Frame, area: Rect, form: &Form, repo: &Repository) {
+    let lines: Vec<Line> = FIELDS
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let value = form.value(field);
+            match index == form.focus {
+                true => Line::from(vec![
+                    format!("{field:<12}").cyan().bold(),
+                    Span::from(format!("{value}\u{2581}")).reversed(),
+                ]),
+                false => Line::from(vec![format!("{field:<12}").cyan(), Span::from(value)]),
+            }
+        })
+        .collect();
+    let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [popup] = Layout::horizontal([Constraint::Length(72)])
+        .flex(Flex::Center)
+        .areas(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(format!(" Edit {} ", repo.name))),
+        popup,
+    );
+}
+
+/// Draws the keyboard shortcuts in a box centered over `area`.
+fn draw_help(frame: &mut Frame, area: Rect) {
+    let lines: Vec<Line> = KEYBINDINGS
+        .iter()
+        .map(|(keys, action)| Line::from(vec![format!("{keys:<12}").cyan(), Span::from(*action)]))
+        .collect();
+    let [popup] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [popup] = Layout::horizontal([Constraint::Length(72)])
+        .flex(Flex::Center)
+        .areas(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Keys (any key to close) ")),
+        popup,
+    );
+}
+
+fn visibility(private: Option<bool>) -> &'static str {
+    match private {
+        Some(true) => "private",
+        Some(false) => "public",
+        None => "unknown",
+    }
+}
+
+/// Fetches the repositories of the token's user and runs the terminal UI until it is quit.
+///
+/// Repositories without an owner belong to `username`, the token's user unless given.
+pub(crate) async fn run(
+    pat_token: &str,
+    username: Option<&str>,
+    sort: SortKey,
+    order: SortOrder,
+) -> Result<()> {
+    let actor = github::get_authenticated_user(pat_token).await?;
+    let username = username.unwrap_or(&actor);
+    let repos = github::get_repos_request(username, pat_token, sort, order).await?;
+    let mut app = App::new(repos);
+
+    let mut terminal = ratatui::init();
+    let (keys, terminal_events) = mpsc::unbounded_channel();
+    let reader = tokio::task::spawn_blocking(move || read_terminal_events(&keys));
+    let users = Users {
+        username,
+        actor: &actor,
+    };
+    let result = event_loop(&mut terminal, &mut app, pat_token, users, terminal_events).await;
+    // The loop dropped the receiver, which stops the reader at its next poll.
+    let _ = reader.await;
+    ratatui::restore();
+
+    result
+}
+
+/// Sends the events of the terminal to `events` until its receiver is dropped. Reading them
+/// blocks, so this runs on a thread of its own rather than on a worker of the runtime.
+fn read_terminal_events(events: &mpsc::UnboundedSender<std::io::Result<Event>>) {
+    while !events.is_closed() {
+        match event::poll(Duration::from_millis(100)) {
+            Ok(false) => {}
+            Ok(true) => {
+                if events.send(event::read()).is_err() {
+                    break;
+                }
+            }
+            Err(err) => {
+                let _ = events.send(Err(err));
+                break;
+            }
+        }
+    }
+}
+
+/// Who the changes are for and who makes them.
+#[derive(Clone, Copy)]
+struct Users<'a> {
+    /// Owner of the repositories listed without one.
+    username: &'a str,
+    /// The token's user, recorded in the audit trail.
+    actor: &'a str,
+}
+
+/// The repository an event is about and the state it leaves its change in, for the events of
+/// changes.
+pub(crate) fn change_state(event: &events::Event) -> Option<(&str, ChangeState)> {
+    match event {
+        events::Event::ChangeQueued { repo } => Some((repo, ChangeState::Queued)),
+        events::Event::ChangeProgress { repo, step } => Some((repo, ChangeState::Running(step))),
+        events::Event::ChangeApplied { repo, already, .. } => match already {
+            true => Some((repo, ChangeState::Skipped)),
+            false => Some((repo, ChangeState::Done)),
+        },
+        events::Event::ChangeSkipped { repo, .. } => Some((repo, ChangeState::Skipped)),
+        events::Event::ChangeFailed { repo, error } => {
+            Some((repo, ChangeState::Failed(error.clone())))
+        }
+        events::Event::ChangeNotStarted { repo } => Some((repo, ChangeState::Cancelled)),
+        _ => None,
+    }
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    pat_token: &str,
+    users: Users<'_>,
+    mut terminal_events: mpsc::UnboundedReceiver<std::io::Result<Event>>,
+) -> Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<events::Event>();
+    // `owner/name` of each pending change, as its events name it.
+    let mut names: Vec<String> = Vec::new();
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        let key = tokio::select! {
+            Some(event) = rx.recv() => {
+                if let Some((repo, state)) = change_state(&event) {
+                    if let Some(index) = names.iter().position(|name| name == repo) {
+                        app.progress[index] = state;
+                    }
+                }
+                continue;
+            }
+            event = terminal_events.recv() => match event {
+                Some(Ok(Event::Key(key))) => key,
+                // Resized, or another event that only needs a redraw.
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => return Err(err.into()),
+                None => break,
+            },
+        };
+        if key.kind != KeyEventKind::Press || !app.handle_key(key.code) {
+            continue;
+        }
+
+        // Apply the staged changes in the background, their events coming back over the channel.
+        let semaphore = Arc::new(Semaphore::new(CONCURRENCY));
+        let frontend: Arc<dyn events::Frontend> = Arc::new(tx.clone());
+        names = app
+            .pending
+            .iter()
+            .map(|pending| apply::full_name(&app.repos[pending.repo], users.username))
+            .collect();
+        for (pending, name) in app.pending.iter().zip(&names) {
+            let repo = app.repos[pending.repo].clone();
+            let (private, edit) = (pending.private, pending.edit.clone());
+            let name = name.clone();
+            let semaphore = Arc::clone(&semaphore);
+            let pat_token = pat_token.to_owned();
+            let (username, actor) = (users.username.to_owned(), users.actor.to_owned());
+            let token = app.cancel.clone();
+            let (settings, frontend) = (transport::settings(), Arc::clone(&frontend));
+            tokio::spawn(cancel::scope(token.clone(), async move {
+                transport::with_settings(
+                    settings,
+                    events::with_frontend(frontend, async move {
+                        let permit = tokio::select! {
+                            permit = semaphore.acquire_owned() => permit.ok(),
+                            _ = token.cancelled() => None,
+                        };
+                        let Some(_permit) = permit else {
+                            events::emit(events::Event::ChangeNotStarted { repo: name });
+                            return;
+                        };
+                        let report = |step: &'static str| {
+                            events::emit(events::Event::ChangeProgress {
+                                repo: name.clone(),
+                                step,
+                            })
+                        };
+                        // The other settings go first, the visibility with its backup last.
+                        let result = async {
+                            if !edit.is_empty() {
+                                report("editing");
+                                apply::apply_edit(&repo, &edit, &username, &pat_token, &actor)
+                                    .await?;
+                            }
+                            match private {
+                                Some(private) => {
+                                    apply::apply_one(
+                                        &repo,
+                                        &private.to_string(),
+                                        &username,
+                                        &pat_token,
+                                        &actor,
+                                        &report,
+                                    )
+                                    .await
+                                }
+                                None => Ok(apply::Applied::Changed),
+                            }
+                        }
+                        .await;
+                        events::emit(match result {
+                            Ok(apply::Applied::Skipped) => events::Event::ChangeSkipped {
+                                repo: name,
+                                reason: SkipReason::ChangedSincePlanned,
+                            },
+                            Ok(applied) => events::Event::ChangeApplied {
+                                repo: name,
+                                private: private.or(repo.private).unwrap_or_default(),
+                                already: edit.is_empty() && applied == apply::Applied::AlreadySet,
+                            },
+                            Err(err) if cancel::is_cancelled(&err) => {
+                                events::Event::ChangeNotStarted { repo: name }
+                            }
+                            Err(err) => events::Event::ChangeFailed {
+                                repo: name,
+                                error: err.to_string(),
+                            },
+                        });
+                    }),
+                )
+                .await
+            }));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! Construction of GitHub API and web URLs.
+//!
+//! Path segments are percent-encoded by [`Url`], so names are never spliced into URLs by hand.
+//! `GH_HOST` selects a GitHub Enterprise Server instead of github.com, like the `gh` CLI.
+
+use std::sync::OnceLock;
+use url::Url;
+
+/// A GitHub instance, either github.com or a GitHub Enterprise Server.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Host {
+    /// Root of the web pages, such as `https://github.com/`.
+    pub web: Url,
+    /// Root of the REST API, such as `https://api.github.com/`.
+    pub api: Url,
+}
+
+impl Default for Host {
+    fn default() -> Self {
+        Self {
+            web: Url::parse("https://github.com/").expect("valid URL"),
+            api: Url::parse("https://api.github.com/").expect("valid URL"),
+        }
+    }
+}
+
+impl Host {
+    /// A GitHub Enterprise Server at `hostname`, which serves its API under `/api/v3/`.
+    ///
+    /// Returns `None` if `hostname` is not a valid host name.
+    pub(crate) fn enterprise(hostname: &str) -> Option<Self> {
+        let web = Url::parse(&format!("https://{hostname}/")).ok()?;
+        if web.path() != "/" || web.host_str() != Some(hostname) {
+            return None;
+        }
+        let api = web.join("api/v3/").ok()?;
+
+        Some(Self { web, api })
+    }
+
+    /// The host name, such as `github.com`.
+    pub(crate) fn name(&self) -> &str {
+        self.web.host_str().unwrap_or_default()
+    }
+
+    /// The API endpoint made of `segments`, such as `["user", "repos"]`.
+    pub(crate) fn api_url(&self, segments: &[&str]) -> Url {
+        join(&self.api, segments)
+    }
+
+    /// The API endpoint made of `segments` with the query `params`.
+    pub(crate) fn list_url(&self, segments: &[&str], params: &[(&str, &str)]) -> Url {
+        let mut url = self.api_url(segments);
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+
+        url
+    }
+
+    /// The API endpoint of the repository `owner/name`.
+    pub(crate) fn api_repo_url(&self, owner: &str, name: &str) -> Url {
+        self.api_url(&["repos", owner, name])
+    }
+
+    /// The web page of the repository `owner/name`.
+    pub(crate) fn html_repo_url(&self, owner: &str, name: &str) -> Url {
+        join(&self.web, &[owner, name])
+    }
+
+    /// The instance whose REST API is at `api`: github.com for `https://api.github.com/`, and a
+    /// GitHub Enterprise Server for any other URL, with its web pages at the root.
+    pub(crate) fn from_api(api: Url) -> Self {
+        let mut api = api;
+        if !api.path().ends_with('/') {
+            api.set_path(&format!("{}/", api.path()));
+        }
+        let web = match api.host_str() {
+            Some("api.github.com") => Url::parse("https://github.com/").expect("valid URL"),
+            _ => api.join("/").expect("valid URL"),
+        };
+
+        Self { web, api }
+    }
+}
+
+/// Returns the host of the current client, see [`crate::Client::builder`], and otherwise the one
+/// selected by `GH_HOST`.
+pub(crate) fn host() -> Host {
+    crate::transport::settings().host.clone()
+}
+
+/// Returns the host selected by `GH_HOST`, github.com if it is unset or not a valid host name.
+pub(crate) fn from_env() -> &'static Host {
+    static HOST: OnceLock<Host> = OnceLock::new();
+    HOST.get_or_init(|| {
+        std::env::var("GH_HOST")
+            .ok()
+            .filter(|hostname| hostname != "github.com")
+            .and_then(|hostname| Host::enterprise(&hostname))
+            .unwrap_or_default()
+    })
+}
+
+fn join(base: &Url, segments: &[&str]) -> Url {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .expect("http URLs have a path")
+        .pop_if_empty()
+        .extend(segments);
+
+    url
+}
@@ -0,0 +1,119 @@
+//! Validation of user-entered usernames and repositories before any URL is built from them.
+//!
+//! Malformed input is rejected with a hint right away, rather than surfacing later as a
+//! confusing `404` from the API.
+
+use crate::{Result, ERROR_ICON};
+use anyhow::anyhow;
+
+/// A repository named by the user, with the owner if one was given.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RepoRef {
+    pub owner: Option<String>,
+    pub name: String,
+}
+
+/// Checks `input` is a valid GitHub username or organization name.
+///
+/// Names are at most 39 alphanumeric characters or single hyphens, and can't begin or end with
+/// a hyphen.
+pub(crate) fn username(input: &str) -> Result<String> {
+    let valid = !input.is_empty()
+        && input.len() <= 39
+        && input.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !input.starts_with('-')
+        && !input.ends_with('-')
+        && !input.contains("--");
+    match valid {
+        true => Ok(input.to_owned()),
+        false => Err(anyhow!(
+            "{ERROR_ICON} `{input}` is not a valid GitHub username. Hint! Usernames only contain letters, digits and single hyphens"
+        )),
+    }
+}
+
+/// Checks `input` names a team as `org/team`, with the team's slug as in its web page, and
+/// returns both parts.
+pub(crate) fn team(input: &str) -> Result<(String, String)> {
+    let invalid = || {
+        anyhow!("{ERROR_ICON} `{input}` is not a team. Hint! Enter it as `org/team`, such as `my-org/platform`")
+    };
+    let (org, slug) = input.trim().split_once('/').ok_or_else(invalid)?;
+    let valid = !slug.is_empty()
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    match valid {
+        true => Ok((username(org)?, slug.to_owned())),
+        false => Err(invalid()),
+    }
+}
+
+/// Checks `input` is a valid topic, returned in lowercase like GitHub stores it.
+///
+/// Topics are at most 50 letters, digits or hyphens, and begin with a letter or digit.
+pub(crate) fn topic(input: &str) -> Result<String> {
+    let topic = input.trim().to_lowercase();
+    let valid = !topic.is_empty()
+        && topic.len() <= 50
+        && topic.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !topic.starts_with('-');
+    match valid {
+        true => Ok(topic),
+        false => Err(anyhow!(
+            "{ERROR_ICON} `{input}` is not a valid topic. Hint! Topics only contain letters, digits and hyphens, such as `to-privatize`"
+        )),
+    }
+}
+
+/// Checks `input` is a valid repository name.
+///
+/// Names are at most 100 letters, digits, `-`, `_` or `.`, and can't be `.` or `..`.
+pub(crate) fn repo_name(input: &str) -> Result<String> {
+    let valid = !input.is_empty()
+        && input.len() <= 100
+        && input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && input != "."
+        && input != "..";
+    match valid {
+        true => Ok(input.to_owned()),
+        false => Err(anyhow!(
+            "{ERROR_ICON} `{input}` is not a valid repository name. Hint! Names only contain letters, digits, `-`, `_` and `.`"
+        )),
+    }
+}
+
+/// Parses a repository given as `name`, `owner/name`, a GitHub URL or an SSH clone URL.
+///
+/// ```text
+/// gittidy
+/// lloydlobo/gittidy
+/// https://github.com/lloydlobo/gittidy/tree/main
+/// git@github.com:lloydlobo/gittidy.git
+/// ```
+pub(crate) fn repo(input: &str) -> Result<RepoRef> {
+    let input = input.trim();
+    let path = match (url::Url::parse(input), input.strip_prefix("git@")) {
+        (_, Some(ssh)) => ssh.split_once(':').map_or(ssh, |(_, path)| path).to_owned(),
+        (Ok(url), None) if url.has_host() => url.path().to_owned(),
+        _ => input.to_owned(),
+    };
+    let mut segments = path.trim_matches('/').split('/');
+    let (owner, name) = match (segments.next(), segments.next()) {
+        (Some(name), None) => (None, name),
+        (Some(owner), Some(name)) => (Some(username(owner)?), name),
+        (None, _) => (None, ""),
+    };
+    // Web URLs may point into the repository, such as `/tree/main`, but `owner/name/more` can't
+    // be meant as a repository.
+    if owner.is_some() && segments.next().is_some() && !input.contains("://") {
+        return Err(anyhow!(
+            "{ERROR_ICON} `{input}` is not a repository. Hint! Enter `name`, `owner/name` or a GitHub URL"
+        ));
+    }
+    let name = repo_name(name.strip_suffix(".git").unwrap_or(name))?;
+
+    Ok(RepoRef { owner, name })
+}
@@ -0,0 +1,181 @@
+//! `privateer watch`: keeps newly created repositories at a default visibility.
+//!
+//! Every interval, the repositories created since the previous check are listed, and any that
+//! doesn't have the default visibility is changed right away, unless it is allowlisted. The time
+//! of the last check is saved in the state directory, so a restart picks up where it left off
+//! instead of touching repositories that existed before watching began. A check that fails to
+//! change any repository leaves that time as it was, so the next check tries it again. Forks,
+//! archived repositories and those the token can't administer are skipped instead, since they
+//! could never be changed.
+
+use crate::apply::{self, Applied};
+use crate::cli::{SortKey, SortOrder, WatchArgs};
+use crate::github::{self, Repository};
+use crate::i18n::t;
+use crate::plan;
+use crate::policy::Policy;
+use crate::queue::{self, Priority};
+use crate::server::{self, Response};
+use crate::{heartbeat, lock, metrics, notify, paths, Result, ERROR_ICON, SUCCESS_ICON};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use console::style;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WatchState {
+    last_check: DateTime<Utc>,
+}
+
+/// Returns the repositories created after `since` that violate `policy`.
+pub(crate) fn violations<'a>(
+    repos: &'a [Repository],
+    since: DateTime<Utc>,
+    policy: &Policy,
+) -> Vec<&'a Repository> {
+    policy.violations(
+        repos
+            .iter()
+            .filter(|repo| repo.created_at.is_some_and(|created| created > since)),
+    )
+}
+
+fn read_last_check() -> Result<Option<DateTime<Utc>>> {
+    let path = paths::watch_file()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(&path)?;
+    let state: WatchState =
+        serde_json::from_str(&text).with_context(|| format!("Malformed watch state {path:?}"))?;
+
+    Ok(Some(state.last_check))
+}
+
+fn write_last_check(last_check: DateTime<Utc>) -> Result<()> {
+    let path = paths::watch_file()?;
+    std::fs::write(&path, serde_json::to_string(&WatchState { last_check })?)
+        .with_context(|| format!("Failed to write watch state {path:?}"))
+}
+
+fn log(message: impl std::fmt::Display) {
+    println!(
+        "{} {message}",
+        style(Utc::now().format("%Y-%m-%d %H:%M:%S")).dim()
+    );
+}
+
+/// Runs one check: changes every new repository created after `since` that violates the
+/// default visibility.
+///
+/// Returns the names of the repositories that failed to change, with why.
+pub(crate) async fn check(
+    pat_token: &str,
+    actor: &str,
+    since: DateTime<Utc>,
+    args: &WatchArgs,
+) -> Result<Vec<(String, String)>> {
+    let started = Utc::now();
+    let private = args.default_visibility.is_private();
+    let privacy = private.to_string();
+    let repos = github::get_repos_request("", pat_token, SortKey::Created, SortOrder::Desc).await?;
+    metrics::scanned(repos.len());
+    let mut failures = Vec::new();
+    for repo in violations(&repos, since, &args.policy()) {
+        // Forks, archived repositories and those the token can't administer would fail on every
+        // check, so they are left alone like `enforce` leaves them.
+        if let Some(reason) = plan::skip_reason(repo, private) {
+            log(format!(
+                "{name}: {}",
+                t!("change-skipped", reason = reason.describe()),
+                name = repo.name
+            ));
+            continue;
+        }
+        let owner = repo.owner.login.as_str();
+        match apply::apply_one(repo, &privacy, owner, pat_token, actor, &|_| {}).await {
+            Ok(Applied::Changed) => log(format!(
+                "{SUCCESS_ICON} {}",
+                t!(
+                    "watch-changed",
+                    repo = repo.name.as_str(),
+                    visibility = args.default_visibility.name()
+                )
+            )),
+            Ok(Applied::AlreadySet | Applied::Skipped) => {}
+            Err(err) => {
+                log(format!("{ERROR_ICON} {name}: {err}", name = repo.name));
+                failures.push((repo.name.clone(), err.to_string()));
+            }
+        }
+    }
+    notify::send(&notify::Summary::since(started, actor, failures.clone())?).await;
+
+    Ok(failures)
+}
+
+/// Checks for new repositories every `args.interval` until interrupted with `Ctrl-C`.
+pub(crate) async fn run(pat_token: &str, args: &WatchArgs, force: bool) -> Result<()> {
+    let actor = github::get_authenticated_user(pat_token).await?;
+    let mut last_check = match read_last_check()? {
+        Some(last_check) => last_check,
+        None => {
+            let now = Utc::now();
+            write_last_check(now)?;
+            now
+        }
+    };
+    queue::configure(args.requests_per_minute);
+    if let Some(addr) = args.metrics_addr {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to listen on {addr}"))?;
+        tokio::spawn(server::serve(listener, |request| async move {
+            metrics::route(&request).unwrap_or_else(Response::not_found)
+        }));
+        log(t!(
+            "serving-metrics",
+            url = format!("http://{addr}/metrics")
+        ));
+    }
+    log(t!(
+        "watch-started",
+        interval = humantime::format_duration(args.interval).to_string(),
+        visibility = args.default_visibility.name()
+    ));
+
+    loop {
+        // Another run may be changing repositories, in which case this check waits for the next.
+        match lock::acquire(force) {
+            Ok(_lock) => {
+                let started = Utc::now();
+                let checked = check(pat_token, &actor, last_check, args);
+                let result = queue::with_priority(Priority::Background, checked).await;
+                metrics::ran(Utc::now(), result.as_ref().is_ok_and(Vec::is_empty));
+                match result {
+                    Ok(failures) if failures.is_empty() => {
+                        last_check = started;
+                        write_last_check(last_check)?;
+                        heartbeat::beat("watch")?;
+                    }
+                    Ok(failures) => {
+                        log(format!(
+                            "{ERROR_ICON} {}",
+                            t!("watch-failures", count = failures.len())
+                        ));
+                    }
+                    Err(err) => log(format!(
+                        "{ERROR_ICON} {}",
+                        t!("watch-check-failed", error = err.to_string())
+                    )),
+                }
+            }
+            Err(err) => log(err),
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(args.interval) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
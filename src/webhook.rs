@@ -0,0 +1,174 @@
+//! `privateer serve`: enforces the policy as soon as GitHub reports a repository event.
+//!
+//! Point an organization or repository webhook at `http://<host>:<port>/webhook` with the
+//! `application/json` content type, the `repository` event and a secret. Every delivery's
+//! `X-Hub-Signature-256` is verified against the secret before its payload is trusted, and
+//! accepted events are applied one at a time in the background so GitHub gets a quick answer.
+
+use crate::cli::ServeArgs;
+use crate::config;
+use crate::daemon::{self, Event as SignalEvent, Level, Signals};
+use crate::enforce::{self, Status};
+use crate::github::{self, Repository};
+use crate::plan;
+use crate::policy::Policy;
+use crate::queue::{self, Priority};
+use crate::server::{self, Request, Response};
+use crate::{lock, notify, paths, Result, ERROR_ICON};
+use anyhow::{anyhow, Context};
+use hmac::{Hmac, KeyInit, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+/// Actions after which a repository's visibility may violate the policy.
+const ACTIONS: [&str; 3] = ["created", "publicized", "privatized"];
+
+#[derive(Debug, Deserialize)]
+struct RepositoryEvent {
+    action: String,
+    repository: Repository,
+}
+
+/// Checks `signature`, the `X-Hub-Signature-256` header, is the HMAC-SHA256 of `body` keyed
+/// with `secret`. The comparison takes constant time.
+pub(crate) fn verify_signature(secret: &[u8], body: &[u8], signature: Option<&str>) -> bool {
+    let Some(tag) = signature
+        .and_then(|signature| signature.strip_prefix("sha256="))
+        .and_then(|digest| hex::decode(digest).ok())
+    else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(body);
+
+    mac.verify_slice(&tag).is_ok()
+}
+
+/// Answers one delivery, queueing the repository of an accepted event on `queue`.
+pub(crate) fn handle(
+    request: &Request,
+    secret: &[u8],
+    queue: &UnboundedSender<Repository>,
+) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/webhook") => {}
+        (_, "/webhook") => return Response::text(405, "Method Not Allowed\n"),
+        _ => return Response::not_found(),
+    }
+    if !verify_signature(secret, &request.body, request.header("x-hub-signature-256")) {
+        return Response::text(401, "Invalid signature\n");
+    }
+    match request.header("x-github-event") {
+        Some("ping") => return Response::text(200, "pong\n"),
+        Some("repository") => {}
+        _ => return Response::text(200, "Ignored\n"),
+    }
+    let event: RepositoryEvent = match serde_json::from_slice(&request.body) {
+        Ok(event) => event,
+        Err(err) => return Response::text(400, format!("Malformed payload: {err}\n")),
+    };
+    if !ACTIONS.contains(&event.action.as_str()) {
+        return Response::text(200, "Ignored\n");
+    }
+    match queue.send(event.repository) {
+        Ok(()) => Response::text(202, "Accepted\n"),
+        Err(_) => Response::text(503, "Shutting down\n"),
+    }
+}
+
+/// Applies `policy` to `repo`, which was just created or had its visibility changed.
+async fn apply_policy(
+    repo: &Repository,
+    policy: &Policy,
+    pat_token: &str,
+    actor: &str,
+    force: bool,
+) {
+    let full_name = format!("{}/{}", repo.owner.login, repo.name);
+    let wanted = plan::for_policy(policy, std::slice::from_ref(repo));
+    if wanted.is_empty() {
+        return;
+    }
+    let _lock = match lock::acquire(force) {
+        Ok(lock) => lock,
+        Err(err) => {
+            daemon::log(Level::Error, format!("Skipped `{full_name}`: {err}"));
+            return;
+        }
+    };
+    for entry in enforce::fix(&wanted, pat_token, actor).await {
+        match (entry.status, entry.error) {
+            (Status::Changed, _) => daemon::log(
+                Level::Info,
+                format!(
+                    "Made `{full_name}` {visibility}",
+                    visibility = policy.default_visibility.name()
+                ),
+            ),
+            (Status::Failed, Some(err)) => {
+                daemon::log(Level::Error, format!("`{full_name}`: {err}"))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Serves `POST /webhook` on `args.port` until stopped with `SIGTERM` or `Ctrl-C`.
+pub(crate) async fn run(pat_token: &str, args: &ServeArgs, force: bool) -> Result<()> {
+    if args.webhook_secret.is_empty() {
+        return Err(anyhow!(
+            "{ERROR_ICON} The webhook secret is empty. Hint! Unsigned deliveries are never accepted"
+        ));
+    }
+    daemon::init_log(false)?;
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => paths::config_file()?,
+    };
+    let config = config::load(&path)?;
+    notify::configure(&config.notifications);
+    queue::configure(config.daemon.requests_per_minute);
+    let policy = config.policy;
+    let actor = github::get_authenticated_user(pat_token).await?;
+
+    let listener = TcpListener::bind(("0.0.0.0", args.port))
+        .await
+        .with_context(|| format!("Failed to listen on port {}", args.port))?;
+    let (queue, mut events) = mpsc::unbounded_channel();
+    let secret = args.webhook_secret.clone().into_bytes();
+    let server = tokio::spawn(server::serve(listener, move |request| {
+        let response = handle(&request, &secret, &queue);
+        async move { response }
+    }));
+    daemon::log(
+        Level::Info,
+        format!(
+            "Listening for webhooks on port {port}, making repos {visibility}",
+            port = args.port,
+            visibility = policy.default_visibility.name()
+        ),
+    );
+
+    let mut signals = Signals::new()?;
+    loop {
+        tokio::select! {
+            Some(repo) = events.recv() => {
+                let applied = apply_policy(&repo, &policy, pat_token, &actor, force);
+                queue::with_priority(Priority::Explicit, applied).await;
+            }
+            event = signals.next() => match event {
+                SignalEvent::Stop => break,
+                SignalEvent::Reload => daemon::log(
+                    Level::Warning,
+                    "Not reloading the configuration, which `privateer serve` reads once. Hint! Restart it instead",
+                ),
+            },
+        }
+    }
+    daemon::log(Level::Info, "Stopped listening for webhooks");
+    server.abort();
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! The whole command-line flow, answered from the exchanges recorded in `tests/fixtures`.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// A fresh directory for the state and configuration of one run.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("privateer-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn replayed_run_makes_a_repo_private() {
+    let home = scratch("replay");
+    let output = Command::new(env!("CARGO_BIN_EXE_privateer"))
+        .args(["--replay"])
+        .arg(fixture("make-private"))
+        .args([
+            "--username",
+            "octocat",
+            "--repo",
+            "hello",
+            "--private",
+            "true",
+        ])
+        .args(["--yes", "--no-color"])
+        .env_remove("PAT_TOKEN")
+        .env_remove("GH_HOST")
+        .env("XDG_STATE_HOME", home.join("state"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+
+    let audit = std::fs::read_to_string(home.join("state/privateer/audit.jsonl")).unwrap();
+    assert!(audit.contains(r#""repo":"octocat/hello""#), "{audit}");
+    assert!(audit.contains("C0DE:1234:5678:9ABC:64A1B2C3"), "{audit}");
+    let _ = std::fs::remove_dir_all(home);
+}
+
+#[test]
+fn quiet_runs_print_only_the_summary() {
+    let home = scratch("replay-quiet");
+    let output = Command::new(env!("CARGO_BIN_EXE_privateer"))
+        .args(["--replay"])
+        .arg(fixture("make-private"))
+        .args([
+            "--username",
+            "octocat",
+            "--repo",
+            "hello",
+            "--private",
+            "true",
+        ])
+        .args(["--yes", "--no-color", "--quiet"])
+        .env_remove("PAT_TOKEN")
+        .env_remove("GH_HOST")
+        .env("XDG_STATE_HOME", home.join("state"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+
+    // Neither the repo nor the review of its change, only how the run went.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 1, "{stdout}");
+    assert!(lines[0].ends_with("Updated `1` repos"), "{stdout}");
+    let _ = std::fs::remove_dir_all(home);
+}
+
+#[test]
+fn replay_fails_on_a_request_that_was_not_recorded() {
+    let home = scratch("replay-missing");
+    let output = Command::new(env!("CARGO_BIN_EXE_privateer"))
+        .args(["--replay"])
+        .arg(fixture("make-private"))
+        .args(["--repo", "other", "--private", "true"])
+        .args(["--yes", "--no-color"])
+        .env_remove("PAT_TOKEN")
+        .env_remove("GH_HOST")
+        .env("XDG_STATE_HOME", home.join("state"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("answers GET /repos/octocat/other"),
+        "{stderr}"
+    );
+    let _ = std::fs::remove_dir_all(home);
+}
+
+#[test]
+fn listings_for_scripts_print_only_json() {
+    let home = scratch("replay-list");
+    let output = Command::new(env!("CARGO_BIN_EXE_privateer"))
+        .args(["--replay"])
+        .arg(fixture("list"))
+        .args(["list", "--json", "--no-color"])
+        .env_remove("PAT_TOKEN")
+        .env_remove("GH_HOST")
+        .env("XDG_STATE_HOME", home.join("state"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let repos: serde_json::Value = serde_json::from_str(&stdout).expect(&stdout);
+    let names: Vec<&str> = repos
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|repo| repo["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["octocat/hello", "octocat/notes"]);
+    // The progress of the listing still shows, apart from the output.
+    assert!(stderr.contains("Fetched details of `2` repos"), "{stderr}");
+    let _ = std::fs::remove_dir_all(home);
+}
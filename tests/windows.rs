@@ -0,0 +1,89 @@
+//! Runs on Windows consoles: known folders instead of `HOME`, `.env` files with CRLF line
+//! endings, and plain output when standard output isn't a console.
+
+#![cfg(windows)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// A fresh directory for the state and configuration of one run.
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("privateer-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Replays making `octocat/hello` private with only the Windows folders for application data.
+fn replay(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_privateer"))
+        .args(["--replay"])
+        .arg(fixture("make-private"))
+        .args(["--repo", "hello", "--private", "true", "--yes"])
+        .args(args)
+        .env_remove("PAT_TOKEN")
+        .env_remove("GH_HOST")
+        .env_remove("HOME")
+        .env_remove("XDG_STATE_HOME")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_CACHE_HOME")
+        .env_remove("XDG_RUNTIME_DIR")
+        .env("APPDATA", home.join("Roaming"))
+        .env("LOCALAPPDATA", home.join("Local"))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn state_is_kept_in_local_app_data() {
+    let home = scratch("windows-appdata");
+    let output = replay(&home, &["--username", "octocat"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+
+    let audit = std::fs::read_to_string(home.join(r"Local\privateer\audit.jsonl")).unwrap();
+    assert!(audit.contains(r#""repo":"octocat/hello""#), "{audit}");
+    let _ = std::fs::remove_dir_all(home);
+}
+
+#[test]
+fn env_files_with_crlf_line_endings_are_loaded() {
+    let home = scratch("windows-crlf");
+    let env_file = home.join("privateer.env");
+    std::fs::write(&env_file, "# Saved by Notepad\r\nPAT_TOKEN=ghp_example\r\n").unwrap();
+    let output = replay(
+        &home,
+        &[
+            "--username",
+            "octocat",
+            "--env-file",
+            env_file.to_str().unwrap(),
+        ],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+
+    let audit = std::fs::read_to_string(home.join(r"Local\privateer\audit.jsonl")).unwrap();
+    assert!(audit.contains(r#""repo":"octocat/hello""#), "{audit}");
+    let _ = std::fs::remove_dir_all(home);
+}
+
+#[test]
+fn piped_output_has_no_escapes_or_emoji() {
+    let home = scratch("windows-piped");
+    let output = replay(&home, &["--username", "octocat"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+
+    assert!(!stdout.contains('\u{1b}'), "{stdout:?}");
+    assert!(!stdout.contains('\u{2705}'), "{stdout}");
+    let _ = std::fs::remove_dir_all(home);
+}